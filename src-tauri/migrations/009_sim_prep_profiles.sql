@@ -0,0 +1,11 @@
+-- Simulator prep profiles: named simctl setup (permissions, status bar, locale, appearance, media)
+-- applied to the destination simulator before a run, per project.
+CREATE TABLE IF NOT EXISTS sim_prep_profiles (
+    id TEXT PRIMARY KEY NOT NULL,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    config TEXT NOT NULL,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sim_prep_profiles_project_id ON sim_prep_profiles(project_id);
@@ -0,0 +1,14 @@
+-- Compiler, linker, signing and package resolution diagnostics reported while building a run's targets.
+CREATE TABLE IF NOT EXISTS build_diagnostics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL REFERENCES test_runs(id) ON DELETE CASCADE,
+    target_key TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    category TEXT NOT NULL,
+    file_path TEXT,
+    line_number INTEGER,
+    column_number INTEGER,
+    message TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_build_diagnostics_run_id ON build_diagnostics(run_id);
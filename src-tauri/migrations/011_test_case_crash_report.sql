@@ -0,0 +1,2 @@
+-- Path to the .ips crash report for tests that crashed, when one was found.
+ALTER TABLE test_cases ADD COLUMN crash_report_path TEXT;
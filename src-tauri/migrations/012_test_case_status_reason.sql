@@ -0,0 +1,2 @@
+-- Skip reason, expected-failure reason or time limit message reported for a test.
+ALTER TABLE test_cases ADD COLUMN status_reason TEXT;
@@ -0,0 +1,21 @@
+-- Metrics reported by XCTest measure {} blocks, one row per test and metric in a run, with the
+-- baseline each was compared against.
+CREATE TABLE IF NOT EXISTS performance_metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL REFERENCES test_runs(id) ON DELETE CASCADE,
+    suite_name TEXT NOT NULL,
+    test_name TEXT NOT NULL,
+    metric TEXT NOT NULL,
+    unit TEXT NOT NULL,
+    identifier TEXT,
+    average REAL NOT NULL,
+    relative_std_dev REAL NOT NULL,
+    measurements TEXT NOT NULL,
+    baseline_average REAL,
+    baseline_run_id TEXT,
+    baseline_pinned INTEGER NOT NULL DEFAULT 0,
+    change_percent REAL,
+    regressed INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_performance_metrics_run_id ON performance_metrics(run_id);
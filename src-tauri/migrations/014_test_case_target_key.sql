@@ -0,0 +1,3 @@
+-- Run target each test case was reported under; its output is captured to the run directory by
+-- target.
+ALTER TABLE test_cases ADD COLUMN target_key TEXT;
@@ -0,0 +1,33 @@
+-- One row per test of a project, so history can follow a test across runs without matching
+-- names. Target is '' until a result bundle or `Module.Class` suite name reveals it.
+CREATE TABLE IF NOT EXISTS tests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id TEXT NOT NULL DEFAULT '',
+    target_name TEXT NOT NULL DEFAULT '',
+    suite_name TEXT NOT NULL,
+    test_name TEXT NOT NULL,
+    UNIQUE (project_id, target_name, suite_name, test_name)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tests_name ON tests(project_id, suite_name, test_name);
+
+-- Existing rows are linked on startup by persistence::tests::backfill_test_ids, which applies
+-- the same name normalization as new runs.
+ALTER TABLE test_cases ADD COLUMN test_id INTEGER REFERENCES tests(id);
+
+CREATE INDEX IF NOT EXISTS idx_test_cases_test_id ON test_cases(test_id);
+
+-- Performance metrics and their pinned baselines follow the test the same way.
+ALTER TABLE performance_metrics ADD COLUMN test_id INTEGER REFERENCES tests(id);
+
+CREATE INDEX IF NOT EXISTS idx_performance_metrics_test_id ON performance_metrics(test_id, metric);
+
+-- Baselines pinned by the user; metrics without one are compared against the previous run.
+CREATE TABLE IF NOT EXISTS performance_baselines (
+    test_id INTEGER NOT NULL REFERENCES tests(id) ON DELETE CASCADE,
+    metric TEXT NOT NULL,
+    average REAL NOT NULL,
+    run_id TEXT,
+    pinned_at TEXT NOT NULL,
+    PRIMARY KEY (test_id, metric)
+);
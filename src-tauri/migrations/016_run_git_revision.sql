@@ -0,0 +1,3 @@
+-- Commit and branch checked out in the project when the run started (NULL outside a git repository).
+ALTER TABLE test_runs ADD COLUMN git_commit TEXT;
+ALTER TABLE test_runs ADD COLUMN git_branch TEXT;
@@ -0,0 +1,16 @@
+-- Flakiness of each suspect test over its project's recent runs, recomputed after every run by
+-- persistence::flaky::refresh_scores. Tests that are stable (or always failing) have no row.
+CREATE TABLE IF NOT EXISTS flaky_scores (
+    test_id INTEGER PRIMARY KEY REFERENCES tests(id) ON DELETE CASCADE,
+    project_id TEXT NOT NULL DEFAULT '',
+    score REAL NOT NULL,
+    runs INTEGER NOT NULL,
+    failures INTEGER NOT NULL,
+    flips INTEGER NOT NULL,
+    retry_passes INTEGER NOT NULL,
+    isolated_failures INTEGER NOT NULL,
+    evidence TEXT NOT NULL,
+    computed_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_flaky_scores_project ON flaky_scores(project_id, score DESC);
@@ -0,0 +1,23 @@
+-- Tests quarantined per project. In 'run' mode a quarantined test still runs but its failures
+-- do not fail the run; in 'skip' mode it is left out of runs. expires_at is a YYYY-MM-DD date
+-- after which the quarantine no longer applies and is listed for review.
+CREATE TABLE IF NOT EXISTS quarantined_tests (
+    test_id INTEGER PRIMARY KEY REFERENCES tests(id) ON DELETE CASCADE,
+    project_id TEXT NOT NULL DEFAULT '',
+    mode TEXT NOT NULL DEFAULT 'run',
+    reason TEXT NOT NULL,
+    owner TEXT,
+    expires_at TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_quarantined_tests_project ON quarantined_tests(project_id);
+
+-- Swift Testing functions are named `func()` when skipped with xcodebuild, XCTest methods are
+-- not; set once the test's output names it with parentheses.
+ALTER TABLE tests ADD COLUMN swift_testing INTEGER NOT NULL DEFAULT 0;
+
+-- Whether the test was quarantined when the case was recorded, and how many of a run's
+-- failures were quarantined (not included in failed_tests).
+ALTER TABLE test_cases ADD COLUMN quarantined INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE test_runs ADD COLUMN quarantined_failures INTEGER NOT NULL DEFAULT 0;
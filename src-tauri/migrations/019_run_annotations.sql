@@ -0,0 +1,15 @@
+-- Findings attached to a run by analysis after it completed (e.g. duration regressions), each
+-- about one test or one target key. data holds the kind's details as JSON.
+CREATE TABLE IF NOT EXISTS run_annotations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id TEXT NOT NULL REFERENCES test_runs(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    test_id INTEGER REFERENCES tests(id) ON DELETE CASCADE,
+    target_key TEXT,
+    message TEXT NOT NULL,
+    data TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_run_annotations_run_id ON run_annotations(run_id);
+CREATE INDEX IF NOT EXISTS idx_run_annotations_kind ON run_annotations(kind, created_at);
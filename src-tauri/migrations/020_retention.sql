@@ -0,0 +1,6 @@
+-- Pinned runs are never removed by retention.
+ALTER TABLE test_runs ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+
+-- Retention limits besides retain_last_runs; 0 means no limit.
+INSERT OR IGNORE INTO settings (key, value) VALUES ('retain_max_age_days', '0');
+INSERT OR IGNORE INTO settings (key, value) VALUES ('retain_max_disk_mb', '0');
@@ -0,0 +1,272 @@
+use crate::models::history::{ClusterFailure, FailureCluster};
+use crate::models::run::TestStatus;
+use crate::parsing::crash_detector::crash_line;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Distinct messages kept per cluster to show what its failures look like.
+const MAX_REPRESENTATIVE_MESSAGES: usize = 3;
+
+// Pattern: 3F2504E0-4F89-11D3-9A0C-0305E82C3301
+static UUID_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b").unwrap()
+});
+
+// Pattern: <MyObject: 0x600003a1c0c0>
+static ADDRESS_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b0x[0-9a-f]+\b").unwrap());
+
+// Pattern: 42, 0.25 (standalone only; the 2 of testLogin2 is kept)
+static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b\d+(\.\d+)?\b").unwrap());
+
+// Pattern: XCTAssertEqual failed: ("1") is not equal to ("2")
+static XCT_ASSERTION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(XCT\w+) failed").unwrap());
+
+/// First line of the first failure message; later failures of the same test are usually
+/// consequences of the first. A crash's message is the output around it, where the line
+/// reporting the crash says what happened.
+fn first_failure<'a>(status: &TestStatus, message: &'a str) -> &'a str {
+    let crash = (*status == TestStatus::Crashed)
+        .then(|| crash_line(message))
+        .flatten();
+    crash.unwrap_or_else(|| message.trim().lines().next().unwrap_or("").trim())
+}
+
+/// `message` with UUIDs, addresses and numbers replaced by placeholders and runs of whitespace
+/// collapsed, so failures differing only in such values compare equal.
+fn normalize_message(message: &str) -> String {
+    let message = UUID_RE.replace_all(message, "<uuid>");
+    let message = ADDRESS_RE.replace_all(&message, "<addr>");
+    let message = NUMBER_RE.replace_all(&message, "<n>");
+    message.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The assertion or kind of failure behind a failure message.
+fn assertion(status: &TestStatus, message: &str) -> Option<String> {
+    match status {
+        TestStatus::Crashed => return Some("crash".to_string()),
+        TestStatus::TimedOut => return Some("timeout".to_string()),
+        _ => {}
+    }
+    if let Some(caps) = XCT_ASSERTION_RE.captures(message) {
+        return Some(caps[1].to_string());
+    }
+    let kind = if message.starts_with("failed") {
+        "XCTFail"
+    } else if message.starts_with("Expectation failed") {
+        "#expect"
+    } else if message.starts_with("Caught error") {
+        "thrown error"
+    } else if message.starts_with("Issue recorded") {
+        "Issue.record"
+    } else {
+        return None;
+    };
+    Some(kind.to_string())
+}
+
+/// Group failures by signature, largest clusters first and then the most recently seen.
+pub fn cluster(failures: Vec<ClusterFailure>) -> Vec<FailureCluster> {
+    let mut clusters: HashMap<String, FailureCluster> = HashMap::new();
+    for failure in failures {
+        let message = first_failure(
+            &failure.status,
+            failure.failure_message.as_deref().unwrap_or(""),
+        );
+        let assertion = assertion(&failure.status, message);
+        let normalized_message = normalize_message(message);
+        // test-results names only the file, the output the full path
+        let file_name = failure.file_path.as_deref().and_then(|path| {
+            Path::new(path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        });
+        let signature = format!(
+            "{}|{}|{}:{}",
+            assertion.as_deref().unwrap_or(""),
+            normalized_message,
+            file_name.as_deref().unwrap_or(""),
+            failure
+                .line_number
+                .map(|l| l.to_string())
+                .unwrap_or_default()
+        );
+        let cluster = clusters
+            .entry(signature.clone())
+            .or_insert_with(|| FailureCluster {
+                signature,
+                assertion,
+                normalized_message,
+                file_name,
+                line_number: failure.line_number,
+                count: 0,
+                tests: 0,
+                runs: 0,
+                representative_messages: Vec::new(),
+                first_seen_at: failure.run_started_at.clone(),
+                last_seen_at: failure.run_started_at.clone(),
+                failures: Vec::new(),
+            });
+        cluster.failures.push(failure);
+    }
+
+    let mut clusters: Vec<FailureCluster> = clusters
+        .into_values()
+        .map(|mut cluster| {
+            cluster
+                .failures
+                .sort_by(|a, b| b.run_started_at.cmp(&a.run_started_at));
+            cluster.count = cluster.failures.len() as i32;
+            let tests: HashSet<i64> = cluster.failures.iter().map(|f| f.test.id).collect();
+            let runs: HashSet<&str> = cluster.failures.iter().map(|f| f.run_id.as_str()).collect();
+            cluster.tests = tests.len() as i32;
+            cluster.runs = runs.len() as i32;
+            for failure in &cluster.failures {
+                let message = first_failure(
+                    &failure.status,
+                    failure.failure_message.as_deref().unwrap_or(""),
+                );
+                if cluster.representative_messages.len() < MAX_REPRESENTATIVE_MESSAGES
+                    && !message.is_empty()
+                    && !cluster.representative_messages.iter().any(|m| m == message)
+                {
+                    cluster.representative_messages.push(message.to_string());
+                }
+            }
+            if let (Some(last), Some(first)) = (cluster.failures.first(), cluster.failures.last()) {
+                cluster.last_seen_at = last.run_started_at.clone();
+                cluster.first_seen_at = first.run_started_at.clone();
+            }
+            cluster
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| b.last_seen_at.cmp(&a.last_seen_at))
+            .then_with(|| a.signature.cmp(&b.signature))
+    });
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::history::TestInfo;
+
+    fn failure(test_id: i64, run_id: &str, message: &str, line: i32) -> ClusterFailure {
+        ClusterFailure {
+            case_id: 0,
+            run_id: run_id.to_string(),
+            run_started_at: format!("2026-01-12T10:00:00.000Z {}", run_id),
+            test: TestInfo {
+                id: test_id,
+                project_id: None,
+                target_name: Some("AppTests".to_string()),
+                suite_name: "LoginTests".to_string(),
+                test_name: format!("testLogin{}", test_id),
+                swift_testing: false,
+            },
+            status: TestStatus::Failed,
+            failure_message: Some(message.to_string()),
+            file_path: Some("/Users/dev/App/AppTests/LoginTests.swift".to_string()),
+            line_number: Some(line),
+            quarantined: false,
+        }
+    }
+
+    #[test]
+    fn failures_differing_only_in_values_cluster_together() {
+        let clusters = cluster(vec![
+            failure(1, "run-1", "XCTAssertEqual failed: (\"1\") is not equal to (\"2\")", 42),
+            failure(2, "run-2", "XCTAssertEqual failed: (\"10\") is not equal to (\"2.5\")", 42),
+            failure(
+                1,
+                "run-2",
+                "XCTAssertEqual failed: (\"<Session: 0x600003a1c0c0>\") is not equal to (\"3F2504E0-4F89-11D3-9A0C-0305E82C3301\")",
+                42,
+            ),
+        ]);
+        assert_eq!(clusters.len(), 2);
+        let values = &clusters[0];
+        assert_eq!(values.count, 2);
+        assert_eq!((values.tests, values.runs), (2, 2));
+        assert_eq!(values.assertion.as_deref(), Some("XCTAssertEqual"));
+        assert_eq!(
+            values.normalized_message,
+            "XCTAssertEqual failed: (\"<n>\") is not equal to (\"<n>\")"
+        );
+        assert_eq!(values.file_name.as_deref(), Some("LoginTests.swift"));
+        assert_eq!(
+            clusters[1].normalized_message,
+            "XCTAssertEqual failed: (\"<Session: <addr>>\") is not equal to (\"<uuid>\")"
+        );
+    }
+
+    #[test]
+    fn numbers_inside_words_are_kept() {
+        assert_eq!(
+            normalize_message("testLogin2 failed on v1 after 3  retries"),
+            "testLogin2 failed on v1 after <n> retries"
+        );
+    }
+
+    #[test]
+    fn another_file_or_line_is_another_cluster() {
+        let mut elsewhere = failure(2, "run-1", "failed - Login rejected", 42);
+        elsewhere.file_path = Some("SignupTests.swift".to_string());
+        let clusters = cluster(vec![
+            failure(1, "run-1", "failed - Login rejected", 42),
+            failure(1, "run-2", "failed - Login rejected", 43),
+            elsewhere,
+        ]);
+        assert_eq!(clusters.len(), 3);
+        assert!(clusters.iter().all(|c| c.count == 1));
+        assert!(clusters
+            .iter()
+            .all(|c| c.assertion.as_deref() == Some("XCTFail")));
+
+        // The same file name under another directory is the same file
+        let mut moved = failure(2, "run-3", "failed - Login rejected", 42);
+        moved.file_path = Some("LoginTests.swift".to_string());
+        let clusters = cluster(vec![failure(1, "run-1", "failed - Login rejected", 42), moved]);
+        assert_eq!(clusters.len(), 1);
+    }
+
+    #[test]
+    fn crashes_cluster_by_the_line_reporting_them() {
+        let crash = |run_id: &str, before: &str, pid: u32| ClusterFailure {
+            status: TestStatus::Crashed,
+            file_path: None,
+            line_number: None,
+            ..failure(
+                1,
+                run_id,
+                &format!(
+                    "Test Case '-[AppTests.LoginTests testLogin1]' started.\n{}\nApp (Crash: App ({}) EXC_BAD_ACCESS (SIGSEGV))\nTest Suite 'LoginTests' failed",
+                    before, pid
+                ),
+                0,
+            )
+        };
+        let clusters = cluster(vec![
+            crash("run-1", "loading fixtures", 12345),
+            crash("run-2", "Login attempt 2", 23456),
+        ]);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].assertion.as_deref(), Some("crash"));
+        assert_eq!(
+            clusters[0].normalized_message,
+            "App (Crash: App (<n>) EXC_BAD_ACCESS (SIGSEGV))"
+        );
+        assert_eq!(
+            clusters[0].representative_messages,
+            [
+                "App (Crash: App (23456) EXC_BAD_ACCESS (SIGSEGV))",
+                "App (Crash: App (12345) EXC_BAD_ACCESS (SIGSEGV))"
+            ]
+        );
+    }
+}
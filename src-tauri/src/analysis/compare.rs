@@ -0,0 +1,219 @@
+use crate::models::history::{DurationChange, RunComparison, RunTotals, TestChange, TestInfo};
+use crate::models::run::TestStatus;
+use std::collections::{BTreeMap, HashMap};
+
+/// Smallest relative duration change reported, on top of the caller's absolute threshold.
+const MIN_DURATION_CHANGE: f64 = 0.2;
+
+/// A test's result in one run.
+#[derive(Debug, Clone)]
+pub struct TestResult {
+    pub test: TestInfo,
+    /// Run target (scheme, test plan or package) the result was reported under.
+    pub target_key: Option<String>,
+    pub status: TestStatus,
+    pub duration_ms: Option<i64>,
+    pub failure_message: Option<String>,
+}
+
+/// Failures rank above passes and passes above skips.
+fn severity(status: &TestStatus) -> u8 {
+    match status {
+        status if status.is_failure() => 2,
+        TestStatus::Skipped => 0,
+        _ => 1,
+    }
+}
+
+/// Each test's result, by test id. Within a run target a retry supersedes earlier attempts;
+/// a test run by several targets (e.g. two test plans) keeps its worst result.
+fn by_test(results: Vec<TestResult>) -> BTreeMap<i64, TestResult> {
+    let mut last: HashMap<(i64, Option<String>), TestResult> = HashMap::new();
+    for result in results {
+        last.insert((result.test.id, result.target_key.clone()), result);
+    }
+    let mut worst: BTreeMap<i64, TestResult> = BTreeMap::new();
+    for result in last.into_values() {
+        match worst.get(&result.test.id) {
+            Some(kept) if severity(&kept.status) >= severity(&result.status) => {}
+            _ => {
+                worst.insert(result.test.id, result);
+            }
+        }
+    }
+    worst
+}
+
+fn totals(results: &BTreeMap<i64, TestResult>) -> RunTotals {
+    let mut totals = RunTotals {
+        total: results.len() as i32,
+        ..RunTotals::default()
+    };
+    for result in results.values() {
+        match result.status {
+            TestStatus::Passed => totals.passed += 1,
+            TestStatus::Skipped => totals.skipped += 1,
+            ref status if status.is_failure() => totals.failed += 1,
+            _ => {}
+        }
+    }
+    totals
+}
+
+fn change(test: &TestInfo, base: Option<&TestResult>, head: Option<&TestResult>) -> TestChange {
+    let failure_message = head
+        .and_then(|h| h.failure_message.clone())
+        .or_else(|| base.and_then(|b| b.failure_message.clone()));
+    TestChange {
+        test: test.clone(),
+        base_status: base.map(|b| b.status.clone()),
+        head_status: head.map(|h| h.status.clone()),
+        failure_message,
+    }
+}
+
+/// Compare the results of two runs, given in recording order. Duration changes are reported
+/// when they are at least `min_delta_ms` and 20% of the base duration.
+pub fn compare(
+    base_run_id: &str,
+    head_run_id: &str,
+    base: Vec<TestResult>,
+    head: Vec<TestResult>,
+    min_delta_ms: i64,
+) -> RunComparison {
+    let base = by_test(base);
+    let head = by_test(head);
+    let mut comparison = RunComparison {
+        base_run_id: base_run_id.to_string(),
+        head_run_id: head_run_id.to_string(),
+        base_totals: totals(&base),
+        head_totals: totals(&head),
+        newly_failing: Vec::new(),
+        fixed: Vec::new(),
+        still_failing: Vec::new(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        duration_changes: Vec::new(),
+    };
+
+    for (id, h) in &head {
+        let Some(b) = base.get(id) else {
+            comparison.added.push(change(&h.test, None, Some(h)));
+            continue;
+        };
+        match (b.status.is_failure(), h.status.is_failure()) {
+            (false, true) => comparison
+                .newly_failing
+                .push(change(&h.test, Some(b), Some(h))),
+            (true, true) => comparison
+                .still_failing
+                .push(change(&h.test, Some(b), Some(h))),
+            // A test skipped in head is not known to be fixed
+            (true, false) if h.status != TestStatus::Skipped => {
+                comparison.fixed.push(change(&h.test, Some(b), Some(h)))
+            }
+            _ => {}
+        }
+
+        if b.status == TestStatus::Skipped || h.status == TestStatus::Skipped {
+            continue;
+        }
+        if let (Some(base_ms), Some(head_ms)) = (b.duration_ms, h.duration_ms) {
+            let delta_ms = head_ms - base_ms;
+            let relative = if base_ms > 0 {
+                delta_ms as f64 / base_ms as f64
+            } else {
+                f64::INFINITY
+            };
+            if delta_ms.abs() >= min_delta_ms && relative.abs() >= MIN_DURATION_CHANGE {
+                comparison.duration_changes.push(DurationChange {
+                    test: h.test.clone(),
+                    base_duration_ms: base_ms,
+                    head_duration_ms: head_ms,
+                    delta_ms,
+                    change_percent: if base_ms > 0 { relative * 100.0 } else { 0.0 },
+                });
+            }
+        }
+    }
+    for (id, b) in &base {
+        if !head.contains_key(id) {
+            comparison.removed.push(change(&b.test, Some(b), None));
+        }
+    }
+
+    let by_name = |a: &TestChange, b: &TestChange| {
+        (&a.test.suite_name, &a.test.test_name).cmp(&(&b.test.suite_name, &b.test.test_name))
+    };
+    for list in [
+        &mut comparison.newly_failing,
+        &mut comparison.fixed,
+        &mut comparison.still_failing,
+        &mut comparison.added,
+        &mut comparison.removed,
+    ] {
+        list.sort_by(by_name);
+    }
+    comparison
+        .duration_changes
+        .sort_by_key(|c| std::cmp::Reverse(c.delta_ms.abs()));
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(target_key: &str, status: TestStatus) -> TestResult {
+        TestResult {
+            test: TestInfo {
+                id: 7,
+                project_id: None,
+                target_name: Some("AppTests".to_string()),
+                suite_name: "LoginTests".to_string(),
+                test_name: "testValid".to_string(),
+                swift_testing: false,
+            },
+            target_key: Some(target_key.to_string()),
+            status,
+            duration_ms: Some(10),
+            failure_message: None,
+        }
+    }
+
+    #[test]
+    fn keeps_the_worst_result_across_targets() {
+        let results = by_test(vec![
+            result("plan:App:Smoke", TestStatus::Failed),
+            result("plan:App:Full", TestStatus::Passed),
+            result("plan:App:Nightly", TestStatus::Skipped),
+        ]);
+        assert_eq!(results[&7].status, TestStatus::Failed);
+        assert_eq!(results[&7].target_key.as_deref(), Some("plan:App:Smoke"));
+    }
+
+    #[test]
+    fn retry_supersedes_earlier_attempts_in_its_target() {
+        let results = by_test(vec![
+            result("plan:App:Smoke", TestStatus::Failed),
+            result("plan:App:Smoke", TestStatus::Passed),
+        ]);
+        assert_eq!(results[&7].status, TestStatus::Passed);
+    }
+
+    #[test]
+    fn failure_in_one_target_is_newly_failing() {
+        let base = vec![
+            result("plan:App:Smoke", TestStatus::Passed),
+            result("plan:App:Full", TestStatus::Passed),
+        ];
+        let head = vec![
+            result("plan:App:Smoke", TestStatus::Failed),
+            result("plan:App:Full", TestStatus::Passed),
+        ];
+        let comparison = compare("base", "head", base, head, 100);
+        assert_eq!(comparison.newly_failing.len(), 1);
+        assert_eq!(comparison.head_totals.failed, 1);
+        assert_eq!(comparison.head_totals.total, 1);
+    }
+}
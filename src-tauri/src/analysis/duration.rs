@@ -0,0 +1,118 @@
+use crate::models::history::DurationRegression;
+
+/// Earlier durations needed before a test or target is judged.
+const MIN_HISTORY: usize = 5;
+
+/// Scaled MADs above the median a duration must be to count as a regression.
+const MAD_THRESHOLD: f64 = 3.0;
+
+/// Makes the MAD comparable to a standard deviation for normally distributed durations.
+const MAD_SCALE: f64 = 1.4826;
+
+/// A regression must also be this much slower than the median, relatively and absolutely, so
+/// that very steady or very short tests do not flag on noise.
+const MIN_INCREASE: f64 = 0.25;
+const MIN_INCREASE_MS: i64 = 100;
+
+fn median(sorted: &[i64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+    } else {
+        sorted[mid] as f64
+    }
+}
+
+/// Whether `duration_ms` regressed against earlier durations `history` (any order), judged by
+/// the median and median absolute deviation so one slow outlier in history does not hide it.
+pub fn regression(history: &[i64], duration_ms: i64) -> Option<DurationRegression> {
+    if history.len() < MIN_HISTORY {
+        return None;
+    }
+    let mut sorted = history.to_vec();
+    sorted.sort_unstable();
+    let median_ms = median(&sorted);
+    let mut deviations: Vec<i64> = sorted
+        .iter()
+        .map(|d| (*d as f64 - median_ms).abs().round() as i64)
+        .collect();
+    deviations.sort_unstable();
+    let mad_ms = median(&deviations);
+
+    let increase = duration_ms as f64 - median_ms;
+    let regressed = increase > MAD_THRESHOLD * MAD_SCALE * mad_ms
+        && increase >= MIN_INCREASE_MS as f64
+        && increase >= MIN_INCREASE * median_ms;
+    regressed.then(|| DurationRegression {
+        duration_ms,
+        median_ms: median_ms.round() as i64,
+        mad_ms: mad_ms.round() as i64,
+        history_runs: history.len() as i32,
+        change_percent: if median_ms > 0.0 {
+            increase / median_ms * 100.0
+        } else {
+            0.0
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn needs_enough_history() {
+        assert!(regression(&[1000, 1000, 1000, 1000], 5000).is_none());
+        assert!(regression(&[], 5000).is_none());
+        assert!(regression(&[1000, 1000, 1000, 1000, 1000], 5000).is_some());
+    }
+
+    #[test]
+    fn compares_against_the_median_and_mad() {
+        let history = [1010, 990, 1000, 1005, 995];
+        let slow = regression(&history, 1300).unwrap();
+        assert_eq!(
+            (
+                slow.duration_ms,
+                slow.median_ms,
+                slow.mad_ms,
+                slow.history_runs
+            ),
+            (1300, 1000, 5, 5)
+        );
+        assert!((slow.change_percent - 30.0).abs() < 1e-9);
+        // Faster, or slower by less than the relative minimum
+        assert!(regression(&history, 700).is_none());
+        assert!(regression(&history, 1200).is_none());
+
+        // Even history: the median is the mean of the middle two
+        let even = regression(&[1000, 1100, 1200, 1300, 1400, 1500], 2500).unwrap();
+        assert_eq!((even.median_ms, even.mad_ms), (1250, 150));
+    }
+
+    #[test]
+    fn noisy_history_needs_a_larger_increase() {
+        // Median 1000, MAD 300: the threshold is about 1334 ms above the median
+        let history = [500, 1500, 1000, 700, 1300];
+        assert!(regression(&history, 1500).is_none());
+        assert!(regression(&history, 2300).is_none());
+        assert!(regression(&history, 2400).is_some());
+    }
+
+    #[test]
+    fn one_slow_run_in_history_does_not_hide_a_regression() {
+        let history = [1000, 1000, 1000, 1000, 9000];
+        let slow = regression(&history, 1300).unwrap();
+        assert_eq!((slow.median_ms, slow.mad_ms), (1000, 0));
+    }
+
+    #[test]
+    fn short_tests_need_an_absolute_increase() {
+        let history = [10, 10, 10, 10, 10];
+        assert!(regression(&history, 100).is_none());
+        assert!(regression(&history, 110).is_some());
+        // Zero history reports no percentage
+        let from_zero = regression(&[0, 0, 0, 0, 0], 500).unwrap();
+        assert_eq!(from_zero.change_percent, 0.0);
+    }
+}
@@ -0,0 +1,186 @@
+use crate::models::history::FlakyEvidence;
+
+/// How a test ended in one run. A test that both failed and passed in the same run (a retry, or
+/// the same test in two schemes) passed on retry.
+#[derive(Debug, Clone)]
+pub struct RunOutcome {
+    pub run_id: String,
+    pub started_at: String,
+    pub passed: bool,
+    pub failed: bool,
+    pub failure_message: Option<String>,
+    /// No other test failed in the run.
+    pub only_failure: bool,
+}
+
+/// A test's flakiness over its recent runs.
+#[derive(Debug, Clone)]
+pub struct FlakyScore {
+    /// 0 (stable) to 1 (as flaky as it gets).
+    pub score: f64,
+    pub runs: i32,
+    pub failures: i32,
+    /// Changes between passing and failing from one run to the next.
+    pub flips: i32,
+    pub retry_passes: i32,
+    pub isolated_failures: i32,
+    /// Failing runs, most recent first.
+    pub evidence: Vec<FlakyEvidence>,
+}
+
+/// Failing runs kept as evidence per test.
+const MAX_EVIDENCE: usize = 10;
+
+/// Runs a test needs, not counting skips, before it is scored; a failure in one of two runs
+/// says little.
+const MIN_RUNS: usize = 3;
+
+/// Factor the score shrinks by for each run since the test last failed, so that a test that
+/// has settled down drops in the ranking.
+const DECAY: f64 = 0.9;
+
+const FLIP_WEIGHT: f64 = 0.5;
+const RETRY_WEIGHT: f64 = 0.3;
+const ISOLATED_WEIGHT: f64 = 0.2;
+
+/// Score a test from its outcomes, oldest run first; runs where it neither passed nor failed
+/// (skipped) are ignored. The score weighs how often the result flips between runs, and how
+/// many failures passed on retry or were the only failure in their run, decayed by the runs
+/// passed since the last failure. Tests that never failed, or never passed, are not flaky: the
+/// latter are broken. Returns None for those and for tests with fewer than `MIN_RUNS` runs.
+pub fn score(outcomes: &[RunOutcome]) -> Option<FlakyScore> {
+    let outcomes: Vec<&RunOutcome> = outcomes.iter().filter(|o| o.passed || o.failed).collect();
+    let failing: Vec<&RunOutcome> = outcomes.iter().copied().filter(|o| o.failed).collect();
+    if outcomes.len() < MIN_RUNS || failing.is_empty() || !outcomes.iter().any(|o| o.passed) {
+        return None;
+    }
+
+    let runs = outcomes.len();
+    // A pass on retry counts as a pass: the flip is the failure that preceded it
+    let flips = outcomes
+        .windows(2)
+        .filter(|pair| {
+            let failed_before = pair[0].failed && !pair[0].passed;
+            let failed_now = pair[1].failed && !pair[1].passed;
+            failed_before != failed_now
+        })
+        .count();
+    let retry_passes = failing.iter().filter(|o| o.passed).count();
+    let isolated_failures = failing.iter().filter(|o| o.only_failure).count();
+    let runs_since_failure = outcomes.iter().rev().take_while(|o| !o.failed).count();
+
+    let flip_rate = flips as f64 / (runs - 1) as f64;
+    let score = FLIP_WEIGHT * flip_rate
+        + RETRY_WEIGHT * retry_passes as f64 / failing.len() as f64
+        + ISOLATED_WEIGHT * isolated_failures as f64 / failing.len() as f64;
+    let score = score * DECAY.powi(runs_since_failure as i32);
+
+    Some(FlakyScore {
+        score: score.min(1.0),
+        runs: runs as i32,
+        failures: failing.len() as i32,
+        flips: flips as i32,
+        retry_passes: retry_passes as i32,
+        isolated_failures: isolated_failures as i32,
+        evidence: failing
+            .iter()
+            .rev()
+            .take(MAX_EVIDENCE)
+            .map(|o| FlakyEvidence {
+                run_id: o.run_id.clone(),
+                started_at: o.started_at.clone(),
+                failure_message: o.failure_message.clone(),
+                passed_on_retry: o.passed,
+                only_failure: o.only_failure,
+            })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Outcomes from a pattern, oldest run first: `P` passed, `F` failed, `R` failed and
+    /// passed on retry, `I` the only failure of its run, `S` skipped.
+    fn outcomes(pattern: &str) -> Vec<RunOutcome> {
+        pattern
+            .chars()
+            .enumerate()
+            .map(|(i, c)| RunOutcome {
+                run_id: format!("run-{}", i + 1),
+                started_at: format!("2026-01-{:02}T10:00:00.000Z", i + 1),
+                passed: matches!(c, 'P' | 'R'),
+                failed: matches!(c, 'F' | 'R' | 'I'),
+                failure_message: matches!(c, 'F' | 'R' | 'I').then(|| format!("failure {}", i + 1)),
+                only_failure: c == 'I',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stable_and_broken_tests_are_not_flaky() {
+        assert!(score(&outcomes("PPPPP")).is_none());
+        assert!(score(&outcomes("FFFFF")).is_none());
+        assert!(score(&outcomes("SSSSS")).is_none());
+    }
+
+    #[test]
+    fn tests_need_enough_runs_to_be_scored() {
+        assert!(score(&outcomes("PF")).is_none());
+        // Skipped runs do not count towards the minimum
+        assert!(score(&outcomes("PSSF")).is_none());
+        assert!(score(&outcomes("PFP")).is_some());
+    }
+
+    #[test]
+    fn flips_count_changes_between_passing_and_failing() {
+        let flaky = score(&outcomes("PFPFPF")).unwrap();
+        assert_eq!((flaky.runs, flaky.failures, flaky.flips), (6, 3, 5));
+        assert!((flaky.score - FLIP_WEIGHT).abs() < 1e-9);
+
+        let settled = score(&outcomes("PPFFFP")).unwrap();
+        assert_eq!(settled.flips, 2);
+        // Skips between runs do not break a streak
+        assert_eq!(score(&outcomes("PSFSP")).unwrap().flips, 2);
+        // A pass on retry is a pass
+        let retried = score(&outcomes("PPPR")).unwrap();
+        assert_eq!((retried.flips, retried.retry_passes), (0, 1));
+        assert!((retried.score - RETRY_WEIGHT).abs() < 1e-9);
+    }
+
+    #[test]
+    fn isolated_failures_add_to_the_score() {
+        let isolated = score(&outcomes("PPI")).unwrap();
+        let crowded = score(&outcomes("PPF")).unwrap();
+        assert_eq!(isolated.isolated_failures, 1);
+        assert!((isolated.score - crowded.score - ISOLATED_WEIGHT).abs() < 1e-9);
+        assert!(score(&outcomes("RIRIRIR")).unwrap().score <= 1.0);
+    }
+
+    #[test]
+    fn older_runs_weigh_less() {
+        let recent = score(&outcomes("PPPPPPFPF")).unwrap();
+        let old = score(&outcomes("FPFPPPPPP")).unwrap();
+        assert_eq!((recent.flips, old.flips), (3, 3));
+        assert!((recent.score / old.score - 1.0 / DECAY.powi(6)).abs() < 1e-9);
+
+        let retried = score(&outcomes("PPRP")).unwrap();
+        assert!((retried.score - RETRY_WEIGHT * DECAY).abs() < 1e-9);
+    }
+
+    #[test]
+    fn evidence_lists_the_latest_failing_runs() {
+        let flaky = score(&outcomes("FPRPIPFPFPFPFPFPF")).unwrap();
+        assert_eq!(flaky.failures, 9);
+        assert_eq!(flaky.evidence.len(), MAX_EVIDENCE.min(9));
+        assert_eq!(flaky.evidence[0].run_id, "run-17");
+        let last = flaky.evidence.last().unwrap();
+        assert_eq!(last.run_id, "run-1");
+        let retry = flaky.evidence.iter().find(|e| e.run_id == "run-3").unwrap();
+        assert!(retry.passed_on_retry && !retry.only_failure);
+        let isolated = flaky.evidence.iter().find(|e| e.run_id == "run-5").unwrap();
+        assert!(isolated.only_failure);
+        assert_eq!(isolated.failure_message.as_deref(), Some("failure 5"));
+    }
+}
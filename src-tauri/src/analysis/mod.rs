@@ -0,0 +1,5 @@
+pub mod clusters;
+pub mod compare;
+pub mod duration;
+pub mod flaky;
+pub mod performance;
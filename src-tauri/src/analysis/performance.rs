@@ -0,0 +1,74 @@
+/// Regression threshold when settings have none, in percent of the baseline average.
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 10.0;
+
+/// Average a metric is compared against: pinned by the user or the previous run's.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Baseline {
+    pub average: f64,
+    pub run_id: Option<String>,
+    pub pinned: bool,
+}
+
+/// How a metric's average compares with its baseline; empty without one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BaselineComparison {
+    pub baseline: Option<Baseline>,
+    /// Change of the average against the baseline, in percent.
+    pub change_percent: Option<f64>,
+    pub regressed: bool,
+}
+
+/// Compare `average` against `baseline`. XCTest metrics (time, memory, CPU, storage,
+/// signposts) all prefer smaller values, so only an increase past `threshold_percent` counts
+/// as a regression.
+pub fn compare_to_baseline(
+    average: f64,
+    baseline: Option<Baseline>,
+    threshold_percent: f64,
+) -> BaselineComparison {
+    let Some(baseline) = baseline else {
+        return BaselineComparison::default();
+    };
+    let change_percent = (baseline.average != 0.0)
+        .then(|| (average - baseline.average) / baseline.average.abs() * 100.0);
+    BaselineComparison {
+        baseline: Some(baseline),
+        change_percent,
+        regressed: change_percent.is_some_and(|change| change > threshold_percent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn previous(average: f64) -> Option<Baseline> {
+        Some(Baseline {
+            average,
+            run_id: Some("run-1".to_string()),
+            pinned: false,
+        })
+    }
+
+    #[test]
+    fn regresses_only_past_the_threshold() {
+        let slower = compare_to_baseline(1.2, previous(1.0), 10.0);
+        assert!(slower.regressed);
+        assert!((slower.change_percent.unwrap() - 20.0).abs() < 1e-9);
+
+        assert!(!compare_to_baseline(1.05, previous(1.0), 10.0).regressed);
+        assert!(!compare_to_baseline(0.5, previous(1.0), 10.0).regressed);
+    }
+
+    #[test]
+    fn without_a_usable_baseline_nothing_regresses() {
+        assert_eq!(
+            compare_to_baseline(1.0, None, 10.0),
+            BaselineComparison::default()
+        );
+        let zero = compare_to_baseline(1.0, previous(0.0), 10.0);
+        assert_eq!(zero.change_percent, None);
+        assert!(!zero.regressed);
+        assert!(zero.baseline.is_some());
+    }
+}
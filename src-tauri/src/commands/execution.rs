@@ -1,6 +1,18 @@
-use crate::execution::runner;
-use crate::models::run::TestRunEvent;
+use crate::execution::progress::{self, ProgressTracker, TargetEstimate};
+use crate::execution::recorder::{RunChannel, RunWriter};
+use crate::execution::runner::RunContext;
+use crate::execution::{artifacts, git, runner, sim_prep};
+use crate::models::history::TestInfo;
+use crate::models::quarantine::{QuarantineMode, QuarantinedTest};
+use crate::models::run::{RunStatus, TestRun, TestRunEvent};
+use crate::models::simulator::SimPrepProfile;
+use crate::parsing::xcresult;
+use crate::persistence::{annotations, flaky, history, quarantine, retention, runs};
 use crate::state::AppState;
+use sqlx::sqlite::SqlitePool;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tauri::ipc::Channel;
 use tauri::State;
 use tokio_util::sync::CancellationToken;
@@ -16,6 +28,13 @@ pub struct RunTestsParams {
     pub test_plan_runs: Vec<TestPlanRun>,
     /// Optional destination for xcodebuild (e.g. simulator UDID -> "id=UDID").
     pub destination: Option<String>,
+    /// Optional simulator prep profile applied to the destination simulator before any tests run.
+    pub sim_prep_profile: Option<SimPrepProfile>,
+    /// Project the run is recorded under.
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// Scope label stored with the run (e.g. "Project", "Suite: Smoke").
+    pub scope: String,
 }
 
 #[derive(serde::Deserialize)]
@@ -36,49 +55,192 @@ pub struct PackageTarget {
     pub filter: Option<String>,
 }
 
-#[tauri::command]
-pub async fn run_tests(
-    state: State<'_, AppState>,
-    params: RunTestsParams,
-    on_event: Channel<TestRunEvent>,
-) -> Result<String, String> {
-    let run_id = uuid::Uuid::new_v4().to_string();
-    let _ = on_event.send(TestRunEvent::RunStarted {
-        run_id: run_id.clone(),
-    });
+fn scheme_target_key(st: &SchemeTarget) -> String {
+    match &st.only_testing_target {
+        Some(t) => format!("{}|{}", st.scheme, t),
+        None => st.scheme.clone(),
+    }
+}
 
-    // Set up cancellation
-    let cancel_token = CancellationToken::new();
-    {
-        let mut active = state.active_run_id.lock().await;
-        *active = Some(run_id.clone());
-        let mut token = state.cancellation_token.lock().await;
-        *token = Some(cancel_token.clone());
+fn test_plan_key(tp: &TestPlanRun) -> String {
+    format!("plan:{}:{}", tp.scheme, tp.test_plan_name)
+}
+
+fn package_key(pkg: &PackageTarget) -> String {
+    match &pkg.filter {
+        Some(f) => format!("{}|{}", pkg.path, f),
+        None => pkg.path.clone(),
+    }
+}
+
+/// Recent runs of the project whose target results estimate a run's progress and ETA.
+const TARGET_HISTORY_RUNS: i64 = 20;
+
+/// Build the run's progress tracker from history, enumerating package tests where history is missing.
+async fn build_progress_tracker(
+    params: &RunTestsParams,
+    estimates: &[TargetEstimate],
+) -> ProgressTracker {
+    let keys: Vec<String> = params
+        .scheme_targets
+        .iter()
+        .map(scheme_target_key)
+        .chain(params.test_plan_runs.iter().map(test_plan_key))
+        .chain(params.packages.iter().map(package_key))
+        .collect();
+    let mut tracker = ProgressTracker::new(&keys, estimates);
+
+    let missing = tracker.keys_without_estimate();
+    for pkg in &params.packages {
+        let key = package_key(pkg);
+        if missing.contains(&key) {
+            let count = progress::enumerate_package_tests(&pkg.path, pkg.filter.as_deref()).await;
+            if let Some(count) = count {
+                tracker.set_expected_tests(&key, count);
+            }
+        }
     }
+    tracker
+}
 
-    let result_run_id = run_id.clone();
+fn begin_target(progress: &Mutex<ProgressTracker>, key: &str, channel: &RunChannel) {
+    if let Ok(mut tracker) = progress.lock() {
+        let _ = channel.send(tracker.begin_target(key));
+    }
+}
+
+/// Read the result bundle xcodebuild wrote for `key` and send its test cases. A missing or
+/// unreadable bundle (e.g. the build failed) is reported as output and does not fail the run.
+async fn report_result_bundle(
+    bundle_path: PathBuf,
+    key: &str,
+    run_id: &str,
+    channel: &RunChannel,
+) {
+    if !bundle_path.exists() {
+        return;
+    }
+    let path = bundle_path.to_string_lossy().to_string();
+    let parse_path = path.clone();
+    let parsed = tokio::task::spawn_blocking(move || {
+        let test_cases = xcresult::parse_xcresult(&parse_path)?;
+        // Metrics are optional; a bundle without them still reports its tests
+        let metrics = xcresult::parse_performance_metrics(&parse_path).unwrap_or_default();
+        Ok::<_, String>((test_cases, metrics))
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|result| result);
+    match parsed {
+        Ok((mut test_cases, performance_metrics)) => {
+            for case in &mut test_cases {
+                case.run_id = run_id.to_string();
+            }
+            let _ = channel.send(TestRunEvent::ResultBundleParsed {
+                key: key.to_string(),
+                bundle_path: path,
+                test_cases,
+                performance_metrics,
+            });
+        }
+        Err(e) => {
+            let _ = channel.send(TestRunEvent::Stderr {
+                line: format!("Could not read result bundle {}: {}", path, e),
+                raw: None,
+            });
+        }
+    }
+}
+
+fn target_completed(progress: &Mutex<ProgressTracker>, key: String, success: bool) -> TestRunEvent {
+    let (tests_run, duration_ms) = progress
+        .lock()
+        .map(|t| (t.target_tests_run(), t.target_elapsed_ms()))
+        .unwrap_or((0, 0));
+    TestRunEvent::TargetCompleted {
+        key,
+        success,
+        tests_run,
+        duration_ms,
+    }
+}
+
+/// Current time as an ISO 8601 UTC timestamp with milliseconds, as the webview writes them.
+fn timestamp() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Apply the run's simulator profile to its destination, stopping when the run is cancelled.
+async fn prepare_simulator(params: &RunTestsParams, run: &RunContext) -> Result<(), String> {
+    let Some(profile) = &params.sim_prep_profile else {
+        return Ok(());
+    };
+    let udid = params
+        .destination
+        .as_deref()
+        .and_then(crate::execution::xcodebuild::simulator_udid)
+        .ok_or_else(|| {
+            format!(
+                "Simulator profile '{}' needs a simulator destination",
+                profile.name
+            )
+        })?;
+    let prepare = sim_prep::apply_profile(&udid, profile, &params.project_path, &run.channel);
+    tokio::select! {
+        _ = run.cancel_token.cancelled() => Ok(()),
+        prepared = prepare => prepared,
+    }
+}
+
+/// Prepare the simulator, then run every scheme target, test plan and package in turn until
+/// done or cancelled, leaving out the `skipped` quarantined tests. Returns whether all of them
+/// succeeded.
+async fn run_targets(
+    params: &RunTestsParams,
+    skipped: &[TestInfo],
+    run: &RunContext,
+) -> Result<bool, String> {
+    let channel = &run.channel;
+    let progress = &run.progress;
+
+    if let Err(message) = prepare_simulator(params, run).await {
+        let _ = channel.send(TestRunEvent::Error {
+            message: message.clone(),
+        });
+        return Err(message);
+    }
 
     // Create temp directory for result bundles
-    let temp_dir = std::env::temp_dir()
-        .join("xcode-test-runner")
-        .join(&run_id);
+    let temp_dir = artifacts::run_dir(&run.run_id);
     std::fs::create_dir_all(&temp_dir)
         .map_err(|e| format!("Failed to create temp dir: {}", e))?;
 
     let mut overall_success = true;
 
+    // Tests recorded without a target cannot be skipped by xcodebuild; say so instead of
+    // letting them run unannounced
+    if !params.scheme_targets.is_empty() || !params.test_plan_runs.is_empty() {
+        for test in crate::execution::xcodebuild::unskippable(skipped) {
+            let _ = channel.send(TestRunEvent::Stderr {
+                line: format!(
+                    "Quarantined test {}/{} runs: its test target is unknown, so xcodebuild cannot skip it",
+                    test.suite_name, test.test_name
+                ),
+                raw: None,
+            });
+        }
+    }
+
     // Run xcodebuild tests for each scheme target (by-target mode)
     for st in &params.scheme_targets {
-        if cancel_token.is_cancelled() {
+        if run.cancel_token.is_cancelled() {
             break;
         }
 
-        let key = match &st.only_testing_target {
-            Some(t) => format!("{}|{}", st.scheme, t),
-            None => st.scheme.clone(),
-        };
+        let key = scheme_target_key(st);
+        begin_target(progress, &key, channel);
 
-        let build_args = crate::execution::xcodebuild::build_args(
+        let mut build_args = crate::execution::xcodebuild::build_args(
             &params.project_path,
             &st.scheme,
             &temp_dir.to_string_lossy(),
@@ -87,20 +249,24 @@ pub async fn run_tests(
             None,
             params.destination.as_deref(),
         );
+        build_args
+            .args
+            .extend(crate::execution::xcodebuild::skip_testing_args(
+                skipped,
+                st.only_testing_target.as_deref(),
+            ));
 
         let success = runner::spawn_and_stream(
             "xcodebuild",
             &build_args.args,
             &params.project_path,
-            &on_event,
-            cancel_token.clone(),
+            &key,
+            run,
         )
         .await?;
+        report_result_bundle(build_args.result_bundle_path, &key, &run.run_id, channel).await;
 
-        let _ = on_event.send(TestRunEvent::TargetCompleted {
-            key: key.clone(),
-            success,
-        });
+        let _ = channel.send(target_completed(progress, key, success));
         if !success {
             overall_success = false;
         }
@@ -108,13 +274,14 @@ pub async fn run_tests(
 
     // Run xcodebuild tests for each test plan
     for tp in &params.test_plan_runs {
-        if cancel_token.is_cancelled() {
+        if run.cancel_token.is_cancelled() {
             break;
         }
 
-        let key = format!("plan:{}:{}", tp.scheme, tp.test_plan_name);
+        let key = test_plan_key(tp);
+        begin_target(progress, &key, channel);
 
-        let build_args = crate::execution::xcodebuild::build_args(
+        let mut build_args = crate::execution::xcodebuild::build_args(
             &params.project_path,
             &tp.scheme,
             &temp_dir.to_string_lossy(),
@@ -123,20 +290,21 @@ pub async fn run_tests(
             Some(&tp.test_plan_name),
             params.destination.as_deref(),
         );
+        build_args
+            .args
+            .extend(crate::execution::xcodebuild::skip_testing_args(skipped, None));
 
         let success = runner::spawn_and_stream(
             "xcodebuild",
             &build_args.args,
             &params.project_path,
-            &on_event,
-            cancel_token.clone(),
+            &key,
+            run,
         )
         .await?;
+        report_result_bundle(build_args.result_bundle_path, &key, &run.run_id, channel).await;
 
-        let _ = on_event.send(TestRunEvent::TargetCompleted {
-            key,
-            success,
-        });
+        let _ = channel.send(target_completed(progress, key, success));
         if !success {
             overall_success = false;
         }
@@ -144,41 +312,194 @@ pub async fn run_tests(
 
     // Run swift test for each package
     for pkg in &params.packages {
-        if cancel_token.is_cancelled() {
+        if run.cancel_token.is_cancelled() {
             break;
         }
 
-        let key = match &pkg.filter {
-            Some(f) => format!("{}|{}", pkg.path, f),
-            None => pkg.path.clone(),
-        };
+        let key = package_key(pkg);
+        begin_target(progress, &key, channel);
 
-        let args =
+        let mut args =
             crate::execution::swift_test::build_args(&pkg.path, pkg.filter.as_deref());
+        args.extend(crate::execution::swift_test::skip_args(skipped));
 
         let success = runner::spawn_and_stream(
             "swift",
             &args,
             &pkg.path,
-            &on_event,
-            cancel_token.clone(),
+            &key,
+            run,
         )
         .await?;
 
-        let _ = on_event.send(TestRunEvent::TargetCompleted {
-            key,
-            success,
-        });
+        let _ = channel.send(target_completed(progress, key, success));
         if !success {
             overall_success = false;
         }
     }
 
-    // Send completion event
-    let _ = on_event.send(TestRunEvent::RunFinished {
-        run_id: result_run_id.clone(),
-        success: overall_success,
+    Ok(overall_success)
+}
+
+/// Store the run's outcome, counts, output and target results. Test cases were written as they
+/// were recorded. A run fails if a target failed or errored, or if any test failed, except for
+/// quarantined tests: their failures are counted separately, and a target that failed only
+/// because of them (all of its failing tests are quarantined) does not fail the run.
+async fn record_completion(
+    db: &SqlitePool,
+    mut test_run: TestRun,
+    channel: &RunChannel,
+    outcome: &Result<bool, String>,
+    cancelled: bool,
+    started: Instant,
+) -> Result<(), String> {
+    let record = channel.record();
+    let (passed, failed, skipped) = record.counts();
+    let quarantined = record.quarantined_failures();
+    let failed = (failed - quarantined).max(0);
+    let targets_passed = match outcome {
+        Ok(true) => true,
+        Ok(false) => record
+            .target_results
+            .iter()
+            .all(|t| t.success || record.failed_only_quarantined(&t.key)),
+        Err(_) => false,
+    };
+
+    test_run.status = if cancelled {
+        RunStatus::Cancelled
+    } else if failed > 0 || !targets_passed {
+        RunStatus::Failed
+    } else {
+        RunStatus::Passed
+    };
+    test_run.finished_at = Some(timestamp());
+    test_run.duration_ms = Some(started.elapsed().as_millis() as i64);
+    test_run.total_tests = record.test_cases.len() as i32;
+    test_run.passed_tests = passed;
+    test_run.failed_tests = failed;
+    test_run.skipped_tests = skipped;
+    test_run.quarantined_failures = quarantined;
+    test_run.raw_log = Some(record.lines.join("\n"));
+    test_run.target_results =
+        Some(record.target_results).filter(|results| !results.is_empty());
+
+    runs::complete_run(db, &test_run).await
+}
+
+#[tauri::command]
+pub async fn run_tests(
+    state: State<'_, AppState>,
+    params: RunTestsParams,
+    on_event: Channel<TestRunEvent>,
+) -> Result<String, String> {
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let channel = RunChannel::new(&run_id, on_event);
+    let _ = channel.send(TestRunEvent::RunStarted {
+        run_id: run_id.clone(),
     });
+    let started = Instant::now();
+    let started_at = timestamp();
+
+    let quarantined: Vec<QuarantinedTest> =
+        quarantine::list_quarantined(&state.db, Some(params.project_id.as_deref().unwrap_or("")))
+            .await?
+            .into_iter()
+            .filter(|q| !q.expired)
+            .collect();
+    channel.set_quarantined(quarantined.iter().map(|q| &q.test));
+    let skipped: Vec<TestInfo> = quarantined
+        .iter()
+        .filter(|q| q.mode == QuarantineMode::Skip)
+        .map(|q| q.test.clone())
+        .collect();
+    let estimates =
+        history::target_estimates(&state.db, params.project_id.as_deref(), TARGET_HISTORY_RUNS)
+            .await?;
+    let revision = git::revision(&params.project_path).await;
+    let test_run = TestRun {
+        id: run_id.clone(),
+        status: RunStatus::Running,
+        project_path: params.project_path.clone(),
+        project_id: params.project_id.clone(),
+        scope: params.scope.clone(),
+        started_at,
+        finished_at: None,
+        duration_ms: None,
+        total_tests: 0,
+        passed_tests: 0,
+        failed_tests: 0,
+        skipped_tests: 0,
+        raw_log: None,
+        target_results: None,
+        git_commit: revision.commit,
+        git_branch: revision.branch,
+        quarantined_failures: 0,
+        pinned: false,
+    };
+    runs::insert_run(&state.db, &test_run).await?;
+
+    // Set up cancellation
+    let cancel_token = CancellationToken::new();
+    {
+        let mut active = state.active_run_id.lock().await;
+        *active = Some(run_id.clone());
+        let mut token = state.cancellation_token.lock().await;
+        *token = Some(cancel_token.clone());
+    }
+
+    let run = RunContext {
+        run_id: run_id.clone(),
+        channel: channel.clone(),
+        cancel_token: cancel_token.clone(),
+        progress: Arc::new(Mutex::new(build_progress_tracker(&params, &estimates).await)),
+    };
+    let mut writer = RunWriter::new(
+        state.db.clone(),
+        channel.clone(),
+        &run_id,
+        params.project_id.as_deref(),
+    );
+    let outcome = writer.record(run_targets(&params, &skipped, &run)).await;
+
+    // Send completion event
+    if let Ok(success) = &outcome {
+        let _ = channel.send(TestRunEvent::RunFinished {
+            run_id: run_id.clone(),
+            success: *success,
+        });
+    }
+
+    let recorded = record_completion(
+        &state.db,
+        test_run,
+        &channel,
+        &outcome,
+        cancel_token.is_cancelled(),
+        started,
+    )
+    .await;
+    if recorded.is_ok() {
+        let project_id = params.project_id.as_deref().unwrap_or("");
+        if let Err(message) = flaky::refresh_scores(&state.db, project_id).await {
+            let _ = channel.send(TestRunEvent::Error { message });
+        }
+        if !cancel_token.is_cancelled() {
+            if let Err(message) =
+                annotations::annotate_duration_regressions(&state.db, &run_id).await
+            {
+                let _ = channel.send(TestRunEvent::Error { message });
+            }
+        }
+    }
+    // Retention problems are reported with the run's output; the run itself has finished
+    let errors = match retention::apply_retention(&state.db).await {
+        Ok(report) => report.errors,
+        Err(message) => vec![message],
+    };
+    for message in errors {
+        let _ = channel.send(TestRunEvent::Error { message });
+    }
 
     // Clear active run
     {
@@ -188,7 +509,9 @@ pub async fn run_tests(
         *token = None;
     }
 
-    Ok(result_run_id)
+    outcome?;
+    recorded?;
+    Ok(run_id)
 }
 
 #[tauri::command]
@@ -1 +1,182 @@
-// History queries are handled directly by the frontend via @tauri-apps/plugin-sql
+use crate::analysis::{clusters, compare};
+use crate::models::history::{
+    FailureCluster, FlakyTest, RunAnnotation, RunComparison, TestHistory,
+};
+use crate::models::run::{TestCase, TestRun};
+use crate::persistence::{annotations, flaky, history, performance, runs};
+use crate::state::AppState;
+use tauri::State;
+
+/// Runs shown in history when the caller does not ask for a number.
+const DEFAULT_RUN_LIMIT: i64 = 50;
+
+/// Runs a test's history and statistics cover when the caller does not ask for a number.
+const DEFAULT_TEST_HISTORY_LIMIT: i64 = 30;
+
+/// Flaky suspects listed when the caller does not ask for a number.
+const DEFAULT_FLAKY_LIMIT: i64 = 20;
+
+/// Smallest duration change `compare_runs` reports when the caller does not set one.
+const DEFAULT_MIN_DURATION_DELTA_MS: i64 = 100;
+
+/// Duration regressions listed when the caller does not ask for a number.
+const DEFAULT_REGRESSION_LIMIT: i64 = 50;
+
+/// Recent runs whose failures `list_failure_clusters` groups when the caller does not ask for
+/// a number.
+const DEFAULT_CLUSTER_RUNS: i64 = 20;
+
+#[tauri::command]
+pub async fn list_runs(
+    state: State<'_, AppState>,
+    limit: Option<i64>,
+    project_id: Option<String>,
+) -> Result<Vec<TestRun>, String> {
+    runs::list_runs(
+        &state.db,
+        limit.unwrap_or(DEFAULT_RUN_LIMIT),
+        project_id.as_deref(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn get_run(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<Option<TestRun>, String> {
+    runs::get_run(&state.db, &run_id).await
+}
+
+#[tauri::command]
+pub async fn get_test_cases(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<Vec<TestCase>, String> {
+    runs::get_test_cases(&state.db, &run_id).await
+}
+
+/// One test's results over its last `limit` runs, with pass rate, duration percentiles and
+/// first-seen and last-failed times.
+#[tauri::command]
+pub async fn get_test_history(
+    state: State<'_, AppState>,
+    test_id: i64,
+    limit: Option<i64>,
+) -> Result<Option<TestHistory>, String> {
+    history::get_test_history(
+        &state.db,
+        test_id,
+        limit.unwrap_or(DEFAULT_TEST_HISTORY_LIMIT),
+    )
+    .await
+}
+
+/// Tests suspected of being flaky, flakiest first, with the failing runs behind each score.
+/// Scores are refreshed after every run.
+#[tauri::command]
+pub async fn list_flaky_tests(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<FlakyTest>, String> {
+    flaky::list_flaky_tests(
+        &state.db,
+        project_id.as_deref(),
+        limit.unwrap_or(DEFAULT_FLAKY_LIMIT),
+    )
+    .await
+}
+
+/// What changed from run `base_id` to run `head_id`: newly failing, fixed, still failing, added
+/// and removed tests, and duration changes of at least `min_delta_ms`.
+#[tauri::command]
+pub async fn compare_runs(
+    state: State<'_, AppState>,
+    base_id: String,
+    head_id: String,
+    min_delta_ms: Option<i64>,
+) -> Result<RunComparison, String> {
+    for id in [&base_id, &head_id] {
+        if runs::get_run(&state.db, id).await?.is_none() {
+            return Err(format!("Run {} not found", id));
+        }
+    }
+    let base = history::get_run_results(&state.db, &base_id).await?;
+    let head = history::get_run_results(&state.db, &head_id).await?;
+    Ok(compare::compare(
+        &base_id,
+        &head_id,
+        base,
+        head,
+        min_delta_ms.unwrap_or(DEFAULT_MIN_DURATION_DELTA_MS),
+    ))
+}
+
+/// Findings recorded against run `run_id` after it completed, such as duration regressions.
+#[tauri::command]
+pub async fn get_run_annotations(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<Vec<RunAnnotation>, String> {
+    annotations::get_run_annotations(&state.db, &run_id).await
+}
+
+/// Pin the average of recorded performance metric `metric_id` as the baseline its test's later
+/// runs are compared against, or unpin the baseline of its test and metric.
+#[tauri::command]
+pub async fn set_performance_baseline_pinned(
+    state: State<'_, AppState>,
+    metric_id: i64,
+    pinned: bool,
+) -> Result<(), String> {
+    if pinned {
+        performance::pin_baseline(&state.db, metric_id).await
+    } else {
+        performance::unpin_baseline(&state.db, metric_id).await
+    }
+}
+
+/// Tests and targets that ran much slower than their recent median in recent runs, newest run
+/// first.
+#[tauri::command]
+pub async fn list_duration_regressions(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<RunAnnotation>, String> {
+    annotations::list_duration_regressions(
+        &state.db,
+        project_id.as_deref(),
+        limit.unwrap_or(DEFAULT_REGRESSION_LIMIT),
+    )
+    .await
+}
+
+/// Failures of run `run_id` grouped by assertion, normalized message and location, largest
+/// groups first.
+#[tauri::command]
+pub async fn get_failure_clusters(
+    state: State<'_, AppState>,
+    run_id: String,
+) -> Result<Vec<FailureCluster>, String> {
+    let failures = history::get_run_failures(&state.db, &run_id).await?;
+    Ok(clusters::cluster(failures))
+}
+
+/// Failures of the last `runs` runs grouped the same way, so a cause recurring across runs
+/// shows as one group.
+#[tauri::command]
+pub async fn list_failure_clusters(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    runs: Option<i64>,
+) -> Result<Vec<FailureCluster>, String> {
+    let failures = history::get_recent_failures(
+        &state.db,
+        project_id.as_deref(),
+        runs.unwrap_or(DEFAULT_CLUSTER_RUNS),
+    )
+    .await?;
+    Ok(clusters::cluster(failures))
+}
@@ -1,6 +1,8 @@
 pub mod discovery;
 pub mod execution;
 pub mod simulators;
-mod results;
-mod history;
+pub mod results;
+pub mod history;
+pub mod quarantine;
+pub mod retention;
 mod settings;
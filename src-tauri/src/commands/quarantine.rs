@@ -0,0 +1,52 @@
+use crate::models::quarantine::{QuarantineMode, QuarantinedTest};
+use crate::persistence::quarantine;
+use crate::state::AppState;
+use tauri::State;
+
+/// Quarantined tests, expired ones (due for review) first.
+#[tauri::command]
+pub async fn list_quarantined_tests(
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+) -> Result<Vec<QuarantinedTest>, String> {
+    quarantine::list_quarantined(&state.db, project_id.as_deref()).await
+}
+
+/// Quarantine a test in its project, or update its quarantine. `expires_at` is a YYYY-MM-DD
+/// date; the quarantine applies through that day.
+#[tauri::command]
+pub async fn quarantine_test(
+    state: State<'_, AppState>,
+    test_id: i64,
+    mode: QuarantineMode,
+    reason: String,
+    owner: Option<String>,
+    expires_at: Option<String>,
+) -> Result<(), String> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err("A quarantine needs a reason".into());
+    }
+    if let Some(date) = &expires_at {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid expiry date '{}', expected YYYY-MM-DD", date))?;
+    }
+    let owner = owner.as_deref().map(str::trim).filter(|o| !o.is_empty());
+    quarantine::quarantine_test(
+        &state.db,
+        test_id,
+        mode,
+        reason,
+        owner,
+        expires_at.as_deref(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn release_quarantined_test(
+    state: State<'_, AppState>,
+    test_id: i64,
+) -> Result<(), String> {
+    quarantine::release_test(&state.db, test_id).await
+}
@@ -1,2 +1,49 @@
-// Results queries are handled directly by the frontend via @tauri-apps/plugin-sql
-// These commands are kept as stubs for potential future Rust-side result processing
+// Results read from a run's artifacts directory rather than the database: captured test
+// output and result bundle attachments
+use crate::execution::{artifacts, test_output};
+use crate::models::run::TestAttachment;
+use crate::parsing::xcresult;
+
+/// Output the test `test_id` ("Suite/test", or the bare name for suite-less tests) of the
+/// run target `target_key` printed in a run, from its started line to its completion line.
+#[tauri::command]
+pub async fn get_test_output(
+    run_id: String,
+    target_key: String,
+    test_id: String,
+) -> Result<String, String> {
+    test_output::read_test_output(&run_id, &target_key, &test_id)
+}
+
+/// Export attachments (screenshots, `XCTAttachment`s) from a run's result bundles into
+/// `<run dir>/attachments`, for the whole run or only the test `test_id`
+/// (e.g. "MyTests/testFoo()").
+#[tauri::command]
+pub async fn export_attachments(
+    run_id: String,
+    test_id: Option<String>,
+) -> Result<Vec<TestAttachment>, String> {
+    let bundles = artifacts::result_bundles(&run_id);
+    if bundles.is_empty() {
+        return Err(format!("No result bundles found for run {}", run_id));
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let mut attachments = Vec::new();
+        for bundle in bundles {
+            let stem = bundle
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let output_dir = artifacts::run_dir(&run_id).join("attachments").join(stem);
+            attachments.extend(xcresult::export_attachments(
+                &bundle.to_string_lossy(),
+                &output_dir,
+                test_id.as_deref(),
+            )?);
+        }
+        Ok(attachments)
+    })
+    .await
+    .map_err(|e| format!("Attachment export failed: {}", e))?
+}
@@ -0,0 +1,30 @@
+use crate::models::settings::RetentionReport;
+use crate::persistence::retention;
+use crate::state::AppState;
+use tauri::State;
+
+/// Apply the retention limits in settings now, e.g. after they changed. Also applied on
+/// startup and after each run.
+#[tauri::command]
+pub async fn apply_retention(state: State<'_, AppState>) -> Result<RetentionReport, String> {
+    retention::apply_retention(&state.db).await
+}
+
+/// Delete a run with its results, log, result bundles and other artifacts.
+#[tauri::command]
+pub async fn delete_run(state: State<'_, AppState>, run_id: String) -> Result<(), String> {
+    if state.active_run_id.lock().await.as_deref() == Some(run_id.as_str()) {
+        return Err("A run cannot be deleted while it is running".into());
+    }
+    retention::delete_run(&state.db, &run_id).await
+}
+
+/// Pin a run so retention keeps it, or unpin it.
+#[tauri::command]
+pub async fn set_run_pinned(
+    state: State<'_, AppState>,
+    run_id: String,
+    pinned: bool,
+) -> Result<(), String> {
+    retention::set_pinned(&state.db, &run_id, pinned).await
+}
@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+thread_local! {
+    /// `runs_root` of tests on this thread, keeping their artifacts apart.
+    static TEST_ROOT: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+#[cfg(test)]
+pub fn set_test_root(root: Option<PathBuf>) {
+    TEST_ROOT.with(|test_root| *test_root.borrow_mut() = root);
+}
+
+/// Directory holding every run's artifacts directory.
+pub fn runs_root() -> PathBuf {
+    #[cfg(test)]
+    if let Some(root) = TEST_ROOT.with(|root| root.borrow().clone()) {
+        return root;
+    }
+    std::env::temp_dir().join("xcode-test-runner")
+}
+
+/// Directory holding a run's artifacts: result bundles and anything exported from them.
+pub fn run_dir(run_id: &str) -> PathBuf {
+    runs_root().join(run_id)
+}
+
+/// Directory holding the output each test of a run printed, one file per test.
+pub fn test_output_dir(run_id: &str) -> PathBuf {
+    run_dir(run_id).join("test-output")
+}
+
+/// The `.xcresult` bundles xcodebuild wrote for a run.
+pub fn result_bundles(run_id: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(run_dir(run_id)) else {
+        return Vec::new();
+    };
+    let mut bundles: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "xcresult"))
+        .collect();
+    bundles.sort();
+    bundles
+}
+
+/// Run ids are UUIDs; anything else (e.g. `""` or `".."`) must not name a directory to remove.
+fn is_run_id(run_id: &str) -> bool {
+    uuid::Uuid::parse_str(run_id).is_ok()
+}
+
+/// Ids of the runs that have an artifacts directory.
+pub fn run_dir_ids() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(runs_root()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| is_run_id(name))
+        .collect()
+}
+
+/// Bytes taken by the files below `path`, 0 when it does not exist. Symlinks are not followed.
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// Remove a run's artifacts directory: result bundles, exported attachments and test output.
+/// Only a directory directly below `runs_root` named by a valid run id is removed.
+pub fn remove_run_dir(run_id: &str) -> Result<(), String> {
+    if !is_run_id(run_id) {
+        return Err(format!("Invalid run id '{}'", run_id));
+    }
+    let dir = match run_dir(run_id).canonicalize() {
+        Ok(dir) => dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => {
+            return Err(format!(
+                "Failed to resolve the artifacts of run {}: {}",
+                run_id, e
+            ))
+        }
+    };
+    let root = runs_root().canonicalize().map_err(|e| e.to_string())?;
+    if dir.parent() != Some(root.as_path()) {
+        return Err(format!(
+            "The artifacts of run {} are outside {}",
+            run_id,
+            root.display()
+        ));
+    }
+    match std::fs::remove_dir_all(&dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!(
+            "Failed to remove the artifacts of run {}: {}",
+            run_id, e
+        )),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_only_run_directories() {
+        let root = std::env::temp_dir().join(format!("artifacts-test-{}", uuid::Uuid::new_v4()));
+        set_test_root(Some(root.clone()));
+        for run_id in ["", ".", "..", "../x", "run-1", "/tmp"] {
+            assert!(remove_run_dir(run_id).is_err(), "{:?}", run_id);
+        }
+
+        let run_id = uuid::Uuid::new_v4().to_string();
+        std::fs::create_dir_all(test_output_dir(&run_id)).unwrap();
+        assert!(run_dir_ids().contains(&run_id));
+        remove_run_dir(&run_id).unwrap();
+        assert!(!run_dir(&run_id).exists());
+        // Already gone
+        remove_run_dir(&run_id).unwrap();
+        set_test_root(None);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
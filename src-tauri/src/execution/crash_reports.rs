@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Where macOS writes `.ips` crash reports for the current user.
+fn diagnostic_reports_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+    Some(
+        PathBuf::from(home)
+            .join("Library")
+            .join("Logs")
+            .join("DiagnosticReports"),
+    )
+}
+
+/// Find an `.ips` crash report written since `since` for the crashed process.
+/// Matches on pid when known (the report body records it), otherwise on the process name
+/// that prefixes the report's file name. Reports in `exclude` were already claimed by
+/// another crash in the same run. Reads the reports, so it blocks.
+pub fn find_crash_report(
+    since: SystemTime,
+    process: Option<&str>,
+    pid: Option<u32>,
+    exclude: &[PathBuf],
+) -> Option<PathBuf> {
+    let dir = diagnostic_reports_dir()?;
+    let mut candidates: Vec<(SystemTime, PathBuf)> = std::fs::read_dir(&dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|e| e != "ips") || exclude.contains(&path) {
+                return None;
+            }
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            (modified >= since).then_some((modified, path))
+        })
+        .collect();
+    // Newest first
+    candidates.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+
+    if let Some(pid) = pid {
+        let needles = [format!("\"pid\" : {},", pid), format!("\"pid\":{},", pid)];
+        let by_pid = candidates.iter().find(|(_, path)| {
+            std::fs::read_to_string(path)
+                .map(|content| needles.iter().any(|n| content.contains(n)))
+                .unwrap_or(false)
+        });
+        if let Some((_, path)) = by_pid {
+            return Some(path.clone());
+        }
+    }
+
+    let process = process?;
+    candidates
+        .into_iter()
+        .find(|(_, path)| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(process))
+        })
+        .map(|(_, path)| path)
+}
@@ -0,0 +1,38 @@
+use tokio::process::Command;
+
+/// Commit and branch checked out in a project's repository when a run starts.
+#[derive(Debug, Default)]
+pub struct GitRevision {
+    pub commit: Option<String>,
+    /// None on a detached HEAD.
+    pub branch: Option<String>,
+}
+
+async fn rev_parse(dir: &str, rev: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse"])
+        .args(rev)
+        .current_dir(dir)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Some(value).filter(|v| !v.is_empty())
+}
+
+/// Revision of the repository containing `dir`; empty when it is not in one or git is missing.
+pub async fn revision(dir: &str) -> GitRevision {
+    let Some(commit) = rev_parse(dir, &["HEAD"]).await else {
+        return GitRevision::default();
+    };
+    let branch = rev_parse(dir, &["--abbrev-ref", "HEAD"])
+        .await
+        .filter(|b| b != "HEAD");
+    GitRevision {
+        commit: Some(commit),
+        branch,
+    }
+}
@@ -1,3 +1,10 @@
+pub mod artifacts;
+pub mod crash_reports;
+pub mod git;
+pub mod progress;
+pub mod recorder;
 pub mod runner;
+pub mod sim_prep;
+pub mod test_output;
 pub mod xcodebuild;
 pub mod swift_test;
@@ -0,0 +1,296 @@
+use crate::models::run::TestRunEvent;
+use regex::Regex;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Last known test count and duration for a target key, from run history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetEstimate {
+    pub key: String,
+    pub test_count: i32,
+    pub duration_ms: Option<i64>,
+}
+
+struct TargetPlan {
+    key: String,
+    expected_tests: Option<i32>,
+    expected_duration_ms: Option<i64>,
+    tests_seen: i32,
+}
+
+/// Tracks completed tests across all targets of a run and turns them into `Progress` events.
+/// Totals come from per-target estimates; targets without one only add the tests actually seen,
+/// and the ETA is only reported while every remaining target has a historical duration.
+pub struct ProgressTracker {
+    targets: Vec<TargetPlan>,
+    current: Option<usize>,
+    target_started: Instant,
+    tests_run: i32,
+}
+
+impl ProgressTracker {
+    /// `keys` are the run's target keys in execution order.
+    pub fn new(keys: &[String], estimates: &[TargetEstimate]) -> Self {
+        let by_key: HashMap<&str, &TargetEstimate> =
+            estimates.iter().map(|e| (e.key.as_str(), e)).collect();
+        let targets = keys
+            .iter()
+            .map(|key| {
+                let estimate = by_key.get(key.as_str());
+                TargetPlan {
+                    key: key.clone(),
+                    expected_tests: estimate.map(|e| e.test_count).filter(|&n| n > 0),
+                    expected_duration_ms: estimate.and_then(|e| e.duration_ms),
+                    tests_seen: 0,
+                }
+            })
+            .collect();
+        Self {
+            targets,
+            current: None,
+            target_started: Instant::now(),
+            tests_run: 0,
+        }
+    }
+
+    /// Fill in test counts for targets that have no history (e.g. from enumerated tests).
+    pub fn set_expected_tests(&mut self, key: &str, count: i32) {
+        if let Some(t) = self.targets.iter_mut().find(|t| t.key == key) {
+            if t.expected_tests.is_none() && count > 0 {
+                t.expected_tests = Some(count);
+            }
+        }
+    }
+
+    /// Keys of targets that still have no expected test count.
+    pub fn keys_without_estimate(&self) -> Vec<String> {
+        self.targets
+            .iter()
+            .filter(|t| t.expected_tests.is_none())
+            .map(|t| t.key.clone())
+            .collect()
+    }
+
+    /// Mark `key` as the target now running and return the initial progress for it.
+    pub fn begin_target(&mut self, key: &str) -> TestRunEvent {
+        self.current = self.targets.iter().position(|t| t.key == key);
+        self.target_started = Instant::now();
+        self.progress_event()
+    }
+
+    /// Tests counted for the current target so far.
+    pub fn target_tests_run(&self) -> i32 {
+        self.current.map_or(0, |i| self.targets[i].tests_seen)
+    }
+
+    /// Elapsed time of the current target.
+    pub fn target_elapsed_ms(&self) -> i64 {
+        self.target_started.elapsed().as_millis() as i64
+    }
+
+    /// Count one parsed test completion and return the updated progress.
+    pub fn record_test(&mut self) -> TestRunEvent {
+        self.tests_run += 1;
+        if let Some(i) = self.current {
+            self.targets[i].tests_seen += 1;
+        }
+        self.progress_event()
+    }
+
+    fn progress_event(&self) -> TestRunEvent {
+        TestRunEvent::Progress {
+            tests_run: self.tests_run,
+            tests_total: self.tests_total(),
+            eta_ms: self.eta_ms(),
+        }
+    }
+
+    fn tests_total(&self) -> i32 {
+        let total: i32 = self
+            .targets
+            .iter()
+            .enumerate()
+            .map(|(i, t)| match self.current {
+                // Finished targets count what actually ran
+                Some(c) if i < c => t.tests_seen,
+                // A target can run more tests than last time (new tests added)
+                Some(c) if i == c => t.expected_tests.unwrap_or(0).max(t.tests_seen),
+                _ => t.expected_tests.unwrap_or(0),
+            })
+            .sum();
+        total.max(self.tests_run)
+    }
+
+    fn eta_ms(&self) -> Option<i64> {
+        let current = self.current?;
+        let target = &self.targets[current];
+        let expected = target.expected_duration_ms?;
+        let elapsed = self.target_elapsed_ms();
+
+        let mut remaining = (expected - elapsed).max(0);
+        if remaining == 0 && target.tests_seen > 0 {
+            // Running slower than last time: extrapolate from the pace of this target's tests
+            if let Some(expected_tests) = target.expected_tests {
+                let left = (expected_tests - target.tests_seen).max(0) as i64;
+                remaining = elapsed / target.tests_seen as i64 * left;
+            }
+        }
+
+        for t in &self.targets[current + 1..] {
+            remaining += t.expected_duration_ms?;
+        }
+        Some(remaining)
+    }
+}
+
+/// Count tests in an already-built Swift package with `swift test list --skip-build`.
+/// Returns None when the package has not been built yet or listing fails.
+///
+/// `swift test list` takes no `--filter`, so the listed tests are matched against the filter
+/// here, the way `swift test --filter` matches them.
+pub async fn enumerate_package_tests(package_path: &str, filter: Option<&str>) -> Option<i32> {
+    let output = tokio::process::Command::new("swift")
+        .args([
+            "test",
+            "list",
+            "--skip-build",
+            "--package-path",
+            package_path,
+        ])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    count_listed_tests(&String::from_utf8_lossy(&output.stdout), filter)
+}
+
+/// Count the `Target.Suite/test` lines of `swift test list` output matching `filter`, a
+/// regular expression as `swift test --filter` takes it. None when nothing matches or the
+/// filter is not a valid expression.
+fn count_listed_tests(listing: &str, filter: Option<&str>) -> Option<i32> {
+    let filter = filter.map(Regex::new).transpose().ok()?;
+    let count = listing
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .filter(|l| filter.as_ref().is_none_or(|f| f.is_match(l)))
+        .count() as i32;
+    Some(count).filter(|&n| n > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn estimate(key: &str, test_count: i32, duration_ms: Option<i64>) -> TargetEstimate {
+        TargetEstimate {
+            key: key.to_string(),
+            test_count,
+            duration_ms,
+        }
+    }
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    fn progress(event: TestRunEvent) -> (i32, i32, Option<i64>) {
+        match event {
+            TestRunEvent::Progress {
+                tests_run,
+                tests_total,
+                eta_ms,
+            } => (tests_run, tests_total, eta_ms),
+            other => panic!("expected Progress, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn totals_follow_estimates_then_the_tests_that_ran() {
+        let mut tracker = ProgressTracker::new(
+            &keys(&["app", "kit", "pkg"]),
+            &[estimate("app", 2, None), estimate("kit", 3, None)],
+        );
+        assert_eq!(tracker.keys_without_estimate(), ["pkg"]);
+        tracker.set_expected_tests("pkg", 4);
+        tracker.set_expected_tests("app", 10);
+        assert!(tracker.keys_without_estimate().is_empty());
+
+        assert_eq!(progress(tracker.begin_target("app")), (0, 9, None));
+        tracker.record_test();
+        tracker.record_test();
+        // A target running more tests than last time raises the total
+        assert_eq!(progress(tracker.record_test()), (3, 10, None));
+        assert_eq!(tracker.target_tests_run(), 3);
+
+        // A finished target counts what ran, even when fewer than expected
+        tracker.begin_target("kit");
+        assert_eq!(progress(tracker.record_test()), (4, 10, None));
+        assert_eq!(progress(tracker.begin_target("pkg")), (4, 8, None));
+        assert_eq!(tracker.target_tests_run(), 0);
+    }
+
+    #[test]
+    fn targets_without_history_add_only_the_tests_seen() {
+        let mut tracker = ProgressTracker::new(&keys(&["app", "pkg"]), &[estimate("pkg", 0, None)]);
+        assert_eq!(tracker.keys_without_estimate(), ["app", "pkg"]);
+        tracker.begin_target("app");
+        assert_eq!(progress(tracker.record_test()), (1, 1, None));
+        assert_eq!(progress(tracker.record_test()), (2, 2, None));
+    }
+
+    #[test]
+    fn eta_needs_the_duration_of_every_remaining_target() {
+        let estimates = [
+            estimate("app", 2, Some(60_000)),
+            estimate("kit", 2, Some(30_000)),
+        ];
+        let mut tracker = ProgressTracker::new(&keys(&["app", "kit"]), &estimates);
+        assert_eq!(progress(tracker.record_test()).2, None);
+        let (_, _, eta) = progress(tracker.begin_target("app"));
+        let eta = eta.unwrap();
+        assert!((89_000..=90_000).contains(&eta), "eta {}", eta);
+
+        let mut tracker =
+            ProgressTracker::new(&keys(&["app", "pkg"]), &[estimate("app", 2, Some(60_000))]);
+        assert_eq!(progress(tracker.begin_target("app")).2, None);
+        let (_, _, eta) = progress(tracker.begin_target("pkg"));
+        assert_eq!(eta, None);
+    }
+
+    #[test]
+    fn eta_extrapolates_when_a_target_runs_slower_than_last_time() {
+        let mut tracker = ProgressTracker::new(&keys(&["app"]), &[estimate("app", 4, Some(1_000))]);
+        tracker.begin_target("app");
+        tracker.target_started = Instant::now() - Duration::from_millis(3_000);
+        // Overdue with no test done yet: nothing to extrapolate from
+        assert_eq!(progress(tracker.progress_event()).2, Some(0));
+
+        // Three tests in about three seconds, one left
+        tracker.record_test();
+        tracker.record_test();
+        let (_, _, eta) = progress(tracker.record_test());
+        let eta = eta.unwrap();
+        assert!((1_000..1_100).contains(&eta), "eta {}", eta);
+    }
+
+    #[test]
+    fn listed_tests_are_counted_through_the_filter() {
+        let listing = "AppTests.LoginTests/testValid\n\
+                       AppTests.LoginTests/testInvalid\n\
+                       AppTests.MatrixTests/identity()\n\
+                       \n";
+        assert_eq!(count_listed_tests(listing, None), Some(3));
+        assert_eq!(count_listed_tests(listing, Some("LoginTests")), Some(2));
+        assert_eq!(
+            count_listed_tests(listing, Some(r"LoginTests/testValid$|identity")),
+            Some(2)
+        );
+        assert_eq!(count_listed_tests(listing, Some("SignupTests")), None);
+        assert_eq!(count_listed_tests(listing, Some("Login(")), None);
+        assert_eq!(count_listed_tests("", None), None);
+    }
+}
@@ -0,0 +1,1026 @@
+use crate::analysis::performance::compare_to_baseline;
+use crate::models::history::TestInfo;
+use crate::models::run::{
+    BuildDiagnostic, PerformanceMetric, TargetResult, TestCase, TestRunEvent, TestStatus,
+};
+use crate::persistence::{performance, runs};
+use crate::persistence::tests::{resolve_test_id, TestKey};
+use sqlx::sqlite::SqlitePool;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+
+/// How often recorded test cases, target results, diagnostics and metrics are written while a run is in progress.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often the run's log is written while its output keeps growing; it is also written
+/// whenever a target completes.
+const LOG_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Sends a run's events to the webview and records the results they carry, so the run is
+/// stored whether or not the webview handles every event.
+#[derive(Clone)]
+pub struct RunChannel {
+    channel: Channel<TestRunEvent>,
+    record: Arc<Mutex<RunRecord>>,
+}
+
+impl RunChannel {
+    pub fn new(run_id: &str, channel: Channel<TestRunEvent>) -> Self {
+        Self {
+            channel,
+            record: Arc::new(Mutex::new(RunRecord::new(run_id))),
+        }
+    }
+
+    pub fn send(&self, event: TestRunEvent) -> tauri::Result<()> {
+        if let Ok(mut record) = self.record.lock() {
+            record.observe(&event);
+        }
+        self.channel.send(event)
+    }
+
+    /// Flag the cases of `tests`, the quarantined tests of the run's project, as quarantined.
+    pub fn set_quarantined<'a>(&self, tests: impl IntoIterator<Item = &'a TestInfo>) {
+        let keys: Vec<TestKey> = tests
+            .into_iter()
+            .map(|t| TestKey::new(t.target_name.as_deref(), &t.suite_name, &t.test_name))
+            .collect();
+        if let Ok(mut record) = self.record.lock() {
+            record.quarantined = keys;
+        }
+    }
+
+    /// What the run has recorded so far.
+    pub fn record(&self) -> RunRecord {
+        self.record
+            .lock()
+            .map(|record| record.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    fn take_changes(&self, log_due: bool) -> RecordChanges {
+        self.record
+            .lock()
+            .map(|mut record| record.take_changes(log_due))
+            .unwrap_or_default()
+    }
+
+    fn restore_changes(&self, changes: &RecordChanges) {
+        if let Ok(mut record) = self.record.lock() {
+            record.restore_changes(changes);
+        }
+    }
+}
+
+/// What changed in a record since the last write.
+#[derive(Default)]
+struct RecordChanges {
+    /// Changed test cases, by index in `RunRecord::test_cases`.
+    test_cases: Vec<(usize, TestCase)>,
+    target_results: Option<Vec<TargetResult>>,
+    /// Log lines added since the log was last written, to append to the stored log.
+    log_lines: Option<LogLines>,
+    /// Diagnostics reported since, with the key of the target that reported each.
+    build_diagnostics: Vec<(String, BuildDiagnostic)>,
+    performance_metrics: Vec<PerformanceMetric>,
+}
+
+impl RecordChanges {
+    fn is_empty(&self) -> bool {
+        self.test_cases.is_empty()
+            && self.target_results.is_none()
+            && self.log_lines.is_none()
+            && self.build_diagnostics.is_empty()
+            && self.performance_metrics.is_empty()
+    }
+}
+
+/// Lines added to a run's log, joined as they continue the stored log.
+struct LogLines {
+    text: String,
+    count: usize,
+}
+
+/// What a write stored.
+#[derive(Default)]
+struct Written {
+    /// Row ids of test cases inserted, by index in `RunRecord::test_cases`.
+    case_ids: Vec<(usize, i64)>,
+    /// A line for each metric that regressed against its baseline.
+    regressions: Vec<String>,
+}
+
+/// Writes a run's test cases, target results, build diagnostics, performance metrics and log
+/// to the database while it is in progress, so a run interrupted by the app quitting keeps
+/// what it recorded.
+pub struct RunWriter {
+    db: SqlitePool,
+    channel: RunChannel,
+    run_id: String,
+    /// Project the run's tests belong to ('' for none).
+    project_id: String,
+    /// Row id of each test case already written, by index in `RunRecord::test_cases`.
+    case_ids: HashMap<usize, i64>,
+    log_written_at: Instant,
+}
+
+impl RunWriter {
+    pub fn new(
+        db: SqlitePool,
+        channel: RunChannel,
+        run_id: &str,
+        project_id: Option<&str>,
+    ) -> Self {
+        Self {
+            db,
+            channel,
+            run_id: run_id.to_string(),
+            project_id: project_id.unwrap_or_default().to_string(),
+            case_ids: HashMap::new(),
+            log_written_at: Instant::now(),
+        }
+    }
+
+    /// Drive `run` to completion, writing what it records as it goes and once more when it
+    /// finishes; the final log is stored with the run's completion.
+    pub async fn record<T>(&mut self, run: impl Future<Output = T>) -> T {
+        tokio::pin!(run);
+        let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+        let output = loop {
+            tokio::select! {
+                output = &mut run => break output,
+                _ = ticker.tick() => self.flush_reporting().await,
+            }
+        };
+        self.flush_reporting().await;
+        output
+    }
+
+    /// A failed write is reported and retried with the next flush; the run goes on.
+    async fn flush_reporting(&mut self) {
+        if let Err(message) = self.flush().await {
+            let _ = self.channel.send(TestRunEvent::Error { message });
+        }
+    }
+
+    /// Write the changes recorded since the last flush in one transaction.
+    async fn flush(&mut self) -> Result<(), String> {
+        let log_due = self.log_written_at.elapsed() >= LOG_CHECKPOINT_INTERVAL;
+        let changes = self.channel.take_changes(log_due);
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let written = self.write(&changes).await;
+        match written {
+            Ok(written) => {
+                self.case_ids.extend(written.case_ids);
+                if changes.log_lines.is_some() {
+                    self.log_written_at = Instant::now();
+                }
+                for line in written.regressions {
+                    let _ = self.channel.send(TestRunEvent::Stdout { line, raw: None });
+                }
+                Ok(())
+            }
+            Err(e) => {
+                self.channel.restore_changes(&changes);
+                Err(e)
+            }
+        }
+    }
+
+    /// Metrics are compared against their baselines as they are stored.
+    async fn write(&self, changes: &RecordChanges) -> Result<Written, String> {
+        let mut tx = self.db.begin().await.map_err(|e| e.to_string())?;
+        let mut written = Written::default();
+        for (index, tc) in &changes.test_cases {
+            let id = self.case_ids.get(index).copied();
+            let row_id = runs::save_test_case(&mut tx, &self.project_id, id, tc).await?;
+            if id.is_none() {
+                written.case_ids.push((*index, row_id));
+            }
+        }
+        for (key, diagnostic) in &changes.build_diagnostics {
+            runs::save_build_diagnostic(&mut tx, &self.run_id, key, diagnostic).await?;
+        }
+        if !changes.performance_metrics.is_empty() {
+            let threshold = performance::load_regression_threshold(&mut tx).await?;
+            for metric in &changes.performance_metrics {
+                let key = TestKey::new(None, &metric.suite_name, &metric.test_name);
+                let test_id = resolve_test_id(&mut tx, &self.project_id, &key).await?;
+                let baseline =
+                    performance::find_baseline(&mut tx, test_id, &self.run_id, &metric.metric)
+                        .await?;
+                let comparison = compare_to_baseline(metric.average, baseline, threshold);
+                if let (true, Some(change), Some(baseline)) = (
+                    comparison.regressed,
+                    comparison.change_percent,
+                    &comparison.baseline,
+                ) {
+                    written.regressions.push(format!(
+                        "Performance regression: {}.{} {} +{:.1}% vs {}",
+                        metric.suite_name,
+                        metric.test_name,
+                        metric.metric,
+                        change,
+                        if baseline.pinned { "pinned baseline" } else { "previous run" }
+                    ));
+                }
+                performance::save_metric(&mut tx, &self.run_id, test_id, metric, &comparison)
+                    .await?;
+            }
+        }
+        if changes.target_results.is_some() || changes.log_lines.is_some() {
+            runs::save_run_progress(
+                &mut tx,
+                &self.run_id,
+                changes.target_results.as_deref(),
+                changes.log_lines.as_ref().map(|log| log.text.as_str()),
+            )
+            .await?;
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(written)
+    }
+}
+
+/// Output, test cases, target results, build diagnostics and performance metrics collected
+/// from a run's events.
+#[derive(Clone)]
+pub struct RunRecord {
+    run_id: String,
+    /// Output lines, stderr prefixed with `[stderr] `.
+    pub lines: Vec<String>,
+    pub test_cases: Vec<TestCase>,
+    pub target_results: Vec<TargetResult>,
+    /// Build diagnostics with the key of the target that reported each.
+    pub build_diagnostics: Vec<(String, BuildDiagnostic)>,
+    /// Metrics reported in the output, then those only result bundles had.
+    pub performance_metrics: Vec<PerformanceMetric>,
+    /// Tests quarantined in the run's project.
+    quarantined: Vec<TestKey>,
+    /// Test cases added or changed since the last write, by index in `test_cases`.
+    changed_cases: BTreeSet<usize>,
+    targets_changed: bool,
+    /// A target completed since the log was last written.
+    log_checkpoint: bool,
+    /// Lines in the log when it was last written.
+    logged_lines: usize,
+    /// Diagnostics and metrics already written, from the start of their lists.
+    written_diagnostics: usize,
+    written_metrics: usize,
+}
+
+/// Output names Swift classes `Module.Suite`; result bundles may list just `Suite`.
+fn same_suite(a: &str, b: &str) -> bool {
+    a == b || a.ends_with(&format!(".{}", b)) || b.ends_with(&format!(".{}", a))
+}
+
+/// Whether recorded case `recorded` and bundle case `bundled` are the same test. Output names
+/// Swift Testing functions `name()` where bundles list `name`, and leaves the suite empty when
+/// it could not tell which suite was running.
+fn same_test(recorded: &TestCase, bundled: &TestCase) -> bool {
+    match recorded.test_name.strip_suffix("()") {
+        Some(name) => {
+            name == bundled.test_name
+                && (recorded.suite_name.is_empty()
+                    || same_suite(&recorded.suite_name, &bundled.suite_name))
+        }
+        None => {
+            recorded.test_name == bundled.test_name
+                && same_suite(&recorded.suite_name, &bundled.suite_name)
+        }
+    }
+}
+
+impl RunRecord {
+    fn new(run_id: &str) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            lines: Vec::new(),
+            test_cases: Vec::new(),
+            target_results: Vec::new(),
+            build_diagnostics: Vec::new(),
+            performance_metrics: Vec::new(),
+            quarantined: Vec::new(),
+            changed_cases: BTreeSet::new(),
+            targets_changed: false,
+            log_checkpoint: false,
+            logged_lines: 0,
+            written_diagnostics: 0,
+            written_metrics: 0,
+        }
+    }
+
+    /// Whether `tc` is a case of a quarantined test. A case or quarantine without a target
+    /// matches by suite and name alone, as its test row does.
+    fn is_quarantined(&self, tc: &TestCase) -> bool {
+        let key = TestKey::new(tc.target_name.as_deref(), &tc.suite_name, &tc.test_name);
+        self.quarantined.iter().any(|q| {
+            q.suite_name == key.suite_name
+                && q.test_name == key.test_name
+                && (q.target_name == key.target_name
+                    || q.target_name.is_empty()
+                    || key.target_name.is_empty())
+        })
+    }
+
+    fn push_test_case(&mut self, mut tc: TestCase) {
+        tc.quarantined = self.is_quarantined(&tc);
+        self.changed_cases.insert(self.test_cases.len());
+        self.test_cases.push(tc);
+    }
+
+    /// Take the test cases and target results changed and the diagnostics and metrics added
+    /// since the last call, and the log lines added since it was last written when a target
+    /// completed since or `log_due`.
+    fn take_changes(&mut self, log_due: bool) -> RecordChanges {
+        let log_grew = self.lines.len() != self.logged_lines;
+        let log_lines = (log_grew && (self.log_checkpoint || log_due)).then(|| {
+            let added = &self.lines[self.logged_lines..];
+            // Lines after the first written continue the stored log on a new line
+            let separator = if self.logged_lines == 0 { "" } else { "\n" };
+            self.logged_lines = self.lines.len();
+            LogLines {
+                text: format!("{}{}", separator, added.join("\n")),
+                count: added.len(),
+            }
+        });
+        self.log_checkpoint = false;
+        let build_diagnostics = self.build_diagnostics[self.written_diagnostics..].to_vec();
+        self.written_diagnostics = self.build_diagnostics.len();
+        let performance_metrics = self.performance_metrics[self.written_metrics..].to_vec();
+        self.written_metrics = self.performance_metrics.len();
+        RecordChanges {
+            test_cases: std::mem::take(&mut self.changed_cases)
+                .into_iter()
+                .map(|index| (index, self.test_cases[index].clone()))
+                .collect(),
+            target_results: std::mem::take(&mut self.targets_changed)
+                .then(|| self.target_results.clone()),
+            log_lines,
+            build_diagnostics,
+            performance_metrics,
+        }
+    }
+
+    /// Mark changes that could not be written as pending again.
+    fn restore_changes(&mut self, changes: &RecordChanges) {
+        self.changed_cases
+            .extend(changes.test_cases.iter().map(|(index, _)| *index));
+        self.targets_changed |= changes.target_results.is_some();
+        if let Some(log) = &changes.log_lines {
+            self.log_checkpoint = true;
+            self.logged_lines -= log.count;
+        }
+        self.written_diagnostics -= changes.build_diagnostics.len();
+        self.written_metrics -= changes.performance_metrics.len();
+    }
+
+    fn observe(&mut self, event: &TestRunEvent) {
+        match event {
+            TestRunEvent::Stdout { line, .. } => self.lines.push(line.clone()),
+            TestRunEvent::Stderr { line, .. } => self.lines.push(format!("[stderr] {}", line)),
+            TestRunEvent::TestCompleted {
+                key,
+                name,
+                suite,
+                status,
+                duration_ms,
+                failure_message,
+                file_path,
+                line_number,
+                status_reason,
+            } => {
+                self.push_test_case(TestCase {
+                    id: None,
+                    run_id: self.run_id.clone(),
+                    suite_name: suite.clone(),
+                    test_name: name.clone(),
+                    status: TestStatus::from_str(status),
+                    duration_ms: Some(*duration_ms),
+                    failure_message: failure_message.clone(),
+                    file_path: file_path.clone(),
+                    line_number: *line_number,
+                    crash_report_path: None,
+                    status_reason: status_reason.clone(),
+                    target_key: Some(key.clone()),
+                    test_id: None,
+                    target_name: None,
+                    quarantined: false,
+                });
+            }
+            TestRunEvent::ResultBundleParsed {
+                key,
+                test_cases,
+                performance_metrics,
+                ..
+            } => {
+                let mut merged = HashSet::new();
+                for tc in test_cases {
+                    self.merge_bundle_case(key, tc, &mut merged);
+                }
+                // Output lines are reported first; the bundle adds metrics they did not include
+                for metric in performance_metrics {
+                    let reported = self.performance_metrics.iter().any(|m| {
+                        m.test_name == metric.test_name
+                            && m.metric == metric.metric
+                            && same_suite(&m.suite_name, &metric.suite_name)
+                    });
+                    if !reported {
+                        self.performance_metrics.push(metric.clone());
+                    }
+                }
+            }
+            TestRunEvent::PerformanceMetric { metric, .. } => {
+                self.performance_metrics.push(metric.clone());
+            }
+            TestRunEvent::BuildDiagnostic { key, diagnostic } => {
+                self.build_diagnostics.push((key.clone(), diagnostic.clone()));
+            }
+            // Sent after the process exits; attach the excerpt to the crashed result
+            TestRunEvent::TestCrashed {
+                suite,
+                name,
+                reason,
+                log_excerpt,
+                crash_report_path,
+                ..
+            } => {
+                let crashed = self.test_cases.iter().position(|tc| {
+                    tc.test_name == *name
+                        && tc.suite_name == *suite
+                        && tc.status == TestStatus::Crashed
+                });
+                if let Some(index) = crashed {
+                    self.changed_cases.insert(index);
+                    let crashed = &mut self.test_cases[index];
+                    crashed.failure_message = Some(if log_excerpt.is_empty() {
+                        reason.clone()
+                    } else {
+                        log_excerpt.clone()
+                    });
+                    crashed.crash_report_path = crash_report_path.clone();
+                }
+            }
+            TestRunEvent::TargetCompleted {
+                key,
+                success,
+                tests_run,
+                duration_ms,
+            } => {
+                self.target_results.push(TargetResult {
+                    key: key.clone(),
+                    success: *success,
+                    tests_run: Some(*tests_run),
+                    duration_ms: Some(*duration_ms),
+                });
+                self.targets_changed = true;
+                self.log_checkpoint = true;
+            }
+            _ => {}
+        }
+    }
+
+    /// Fill in failure messages from the bundle of target `key` and add tests the output did
+    /// not report. A bundle case is merged into the latest attempt of the test in the same
+    /// target not yet `merged` with another case of the bundle.
+    fn merge_bundle_case(&mut self, key: &str, tc: &TestCase, merged: &mut HashSet<usize>) {
+        let existing = self.test_cases.iter().enumerate().rposition(|(index, r)| {
+            r.target_key.as_deref() == Some(key) && same_test(r, tc) && !merged.contains(&index)
+        });
+        let Some(index) = existing else {
+            merged.insert(self.test_cases.len());
+            self.push_test_case(TestCase {
+                run_id: self.run_id.clone(),
+                duration_ms: tc.duration_ms.or(Some(0)),
+                target_key: Some(key.to_string()),
+                ..tc.clone()
+            });
+            return;
+        };
+        merged.insert(index);
+        self.changed_cases.insert(index);
+        let existing = &mut self.test_cases[index];
+        if existing.failure_message.is_none() {
+            existing.failure_message = tc.failure_message.clone();
+        }
+        if existing.target_name.is_none() {
+            existing.target_name = tc.target_name.clone();
+        }
+        if existing.suite_name.is_empty() {
+            existing.suite_name = tc.suite_name.clone();
+        }
+        // Output failure lines carry full paths; bundles may only name the file
+        if existing.file_path.is_none() {
+            existing.file_path = tc.file_path.clone();
+            existing.line_number = tc.line_number;
+        }
+        // Bundles record skip reasons, expected failures and time limits the output may not
+        // print
+        let refines_status = matches!(
+            tc.status,
+            TestStatus::Skipped | TestStatus::ExpectedFailure | TestStatus::TimedOut
+        );
+        if existing.status_reason.is_none() && tc.status_reason.is_some() && refines_status {
+            existing.status = tc.status.clone();
+            existing.status_reason = tc.status_reason.clone();
+        }
+        // The bundle's target name may tell which of two same-named tests this is
+        self.test_cases[index].quarantined = self.is_quarantined(&self.test_cases[index]);
+    }
+
+    /// (passed, failed, skipped) test counts.
+    pub fn counts(&self) -> (i32, i32, i32) {
+        let statuses = || self.test_cases.iter().map(|tc| &tc.status);
+        let passed = statuses().filter(|s| **s == TestStatus::Passed).count();
+        let failed = statuses().filter(|s| s.is_failure()).count();
+        let skipped = statuses().filter(|s| **s == TestStatus::Skipped).count();
+        (passed as i32, failed as i32, skipped as i32)
+    }
+
+    /// Failed, crashed or timed-out cases of quarantined tests; they are included in the
+    /// failed count of `counts`.
+    pub fn quarantined_failures(&self) -> i32 {
+        self.test_cases
+            .iter()
+            .filter(|tc| tc.quarantined && tc.status.is_failure())
+            .count() as i32
+    }
+
+    /// Whether target `key` failed only because of quarantined tests: some of its tests
+    /// failed, and all of those are quarantined.
+    pub fn failed_only_quarantined(&self, key: &str) -> bool {
+        let mut failures = self
+            .test_cases
+            .iter()
+            .filter(|tc| tc.target_key.as_deref() == Some(key) && tc.status.is_failure())
+            .peekable();
+        failures.peek().is_some() && failures.all(|tc| tc.quarantined)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::run::{DiagnosticCategory, DiagnosticSeverity, RunStatus, TestRun};
+    use crate::persistence::testing::{self, TestDb};
+
+    fn channel(run_id: &str) -> RunChannel {
+        RunChannel::new(run_id, Channel::new(|_| Ok(())))
+    }
+
+    fn new_run(id: &str) -> TestRun {
+        testing::run(id, "2026-01-12T10:00:00.000Z")
+    }
+
+    fn completed(key: &str, suite: &str, name: &str, status: TestStatus) -> TestRunEvent {
+        TestRunEvent::TestCompleted {
+            key: key.to_string(),
+            name: name.to_string(),
+            suite: suite.to_string(),
+            status: status.to_string(),
+            duration_ms: 10,
+            failure_message: None,
+            file_path: None,
+            line_number: None,
+            status_reason: None,
+        }
+    }
+
+    fn bundle_case(suite: &str, name: &str, message: &str) -> TestCase {
+        TestCase {
+            id: None,
+            run_id: String::new(),
+            suite_name: suite.to_string(),
+            test_name: name.to_string(),
+            status: TestStatus::Failed,
+            duration_ms: Some(10),
+            failure_message: Some(message.to_string()),
+            file_path: None,
+            line_number: None,
+            crash_report_path: None,
+            status_reason: None,
+            target_key: None,
+            test_id: None,
+            target_name: Some("AppTests".to_string()),
+            quarantined: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_run_while_in_progress_and_on_completion() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        let run = new_run("run-1");
+        runs::insert_run(pool, &run).await.unwrap();
+        let stored = runs::get_run(pool, "run-1").await.unwrap().unwrap();
+        assert_eq!(stored.status, RunStatus::Running);
+
+        let channel = channel("run-1");
+        let mut writer = RunWriter::new(pool.clone(), channel.clone(), "run-1", None);
+        let in_progress = writer
+            .record(async {
+                let send = |event| channel.send(event).unwrap();
+                send(TestRunEvent::Stdout {
+                    line: "Test Case '-[AppTests.LoginTests testValid]' started.".to_string(),
+                    raw: None,
+                });
+                send(completed(
+                    "App",
+                    "AppTests.LoginTests",
+                    "testValid",
+                    TestStatus::Passed,
+                ));
+                send(completed(
+                    "App",
+                    "AppTests.LoginTests",
+                    "testInvalid",
+                    TestStatus::Failed,
+                ));
+                send(completed(
+                    "App",
+                    "AppTests.LoginTests",
+                    "testFaceID",
+                    TestStatus::Skipped,
+                ));
+                send(TestRunEvent::TargetCompleted {
+                    key: "App".to_string(),
+                    success: false,
+                    tests_run: 3,
+                    duration_ms: 1200,
+                });
+                // Past the flush interval the run's results are in the database
+                tokio::time::sleep(FLUSH_INTERVAL * 3).await;
+                let cases = runs::get_test_cases(pool, "run-1").await.unwrap();
+                let run = runs::get_run(pool, "run-1").await.unwrap().unwrap();
+                (cases, run)
+            })
+            .await;
+        let (cases, stored) = in_progress;
+        assert_eq!(cases.len(), 3);
+        assert!(cases
+            .iter()
+            .all(|tc| tc.target_key.as_deref() == Some("App")));
+        assert_eq!(stored.status, RunStatus::Running);
+        let targets = stored.target_results.unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].tests_run, Some(3));
+        assert!(stored.raw_log.is_some_and(|log| log.contains("testValid")));
+
+        let record = channel.record();
+        let (passed, failed, skipped) = record.counts();
+        let mut run = run;
+        run.status = RunStatus::Failed;
+        run.finished_at = Some("2026-01-12T10:00:02.000Z".to_string());
+        run.total_tests = record.test_cases.len() as i32;
+        (run.passed_tests, run.failed_tests, run.skipped_tests) = (passed, failed, skipped);
+        run.raw_log = Some(record.lines.join("\n"));
+        run.target_results = Some(record.target_results);
+        runs::complete_run(pool, &run).await.unwrap();
+
+        let stored = runs::get_run(pool, "run-1").await.unwrap().unwrap();
+        assert_eq!(stored.status, RunStatus::Failed);
+        assert_eq!(
+            (
+                stored.total_tests,
+                stored.passed_tests,
+                stored.failed_tests,
+                stored.skipped_tests
+            ),
+            (3, 1, 1, 1)
+        );
+        // Completion does not write the cases again
+        assert_eq!(runs::get_test_cases(pool, "run-1").await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn marks_runs_left_running_as_interrupted() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        runs::insert_run(pool, &new_run("cut-off")).await.unwrap();
+        let channel = channel("cut-off");
+        let mut writer = RunWriter::new(pool.clone(), channel.clone(), "cut-off", None);
+        writer
+            .record(async {
+                channel
+                    .send(completed(
+                        "App",
+                        "LoginTests",
+                        "testValid",
+                        TestStatus::Passed,
+                    ))
+                    .unwrap();
+                channel
+                    .send(completed(
+                        "App",
+                        "LoginTests",
+                        "testCrash",
+                        TestStatus::Crashed,
+                    ))
+                    .unwrap();
+            })
+            .await;
+
+        assert_eq!(runs::mark_interrupted_runs(pool).await.unwrap(), 1);
+        let stored = runs::get_run(pool, "cut-off").await.unwrap().unwrap();
+        assert_eq!(stored.status, RunStatus::Interrupted);
+        assert_eq!(
+            (stored.total_tests, stored.passed_tests, stored.failed_tests),
+            (2, 1, 1)
+        );
+        assert_eq!(runs::mark_interrupted_runs(pool).await.unwrap(), 0);
+    }
+
+    fn time_metric(suite: &str, name: &str, values: Vec<f64>) -> PerformanceMetric {
+        PerformanceMetric::from_values(suite, name, "Time", "seconds", None, values).unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_diagnostics_and_metrics_while_in_progress() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        let earlier = testing::run("run-1", "2026-01-11T10:00:00.000Z");
+        runs::insert_run(pool, &earlier).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        let key = TestKey::new(None, "AppTests.LoginTests", "testSpeed");
+        let test_id = resolve_test_id(&mut conn, "", &key).await.unwrap();
+        performance::save_metric(
+            &mut conn,
+            "run-1",
+            test_id,
+            &time_metric("AppTests.LoginTests", "testSpeed", vec![1.0]),
+            &Default::default(),
+        )
+        .await
+        .unwrap();
+        drop(conn);
+        runs::insert_run(pool, &new_run("run-2")).await.unwrap();
+
+        let channel = channel("run-2");
+        let mut writer = RunWriter::new(pool.clone(), channel.clone(), "run-2", None);
+        let in_progress = writer
+            .record(async {
+                let send = |event| channel.send(event).unwrap();
+                send(TestRunEvent::BuildDiagnostic {
+                    key: "App".to_string(),
+                    diagnostic: BuildDiagnostic::without_location(
+                        DiagnosticSeverity::Warning,
+                        DiagnosticCategory::Linker,
+                        "ld: warning: duplicate library",
+                    ),
+                });
+                send(TestRunEvent::PerformanceMetric {
+                    key: "App".to_string(),
+                    metric: time_metric("AppTests.LoginTests", "testSpeed", vec![1.5, 1.5]),
+                });
+                // The bundle's copy of a reported metric is dropped; the one it alone has is kept
+                send(TestRunEvent::ResultBundleParsed {
+                    key: "App".to_string(),
+                    bundle_path: "/tmp/App.xcresult".to_string(),
+                    test_cases: Vec::new(),
+                    performance_metrics: vec![
+                        time_metric("LoginTests", "testSpeed", vec![1.5]),
+                        time_metric("LoginTests", "testLaunch", vec![0.2]),
+                    ],
+                });
+                tokio::time::sleep(FLUSH_INTERVAL * 3).await;
+                let diagnostics: Vec<(String, String, String)> = sqlx::query_as(
+                    "SELECT target_key, severity, category FROM build_diagnostics WHERE run_id = 'run-2'",
+                )
+                .fetch_all(pool)
+                .await
+                .unwrap();
+                let metrics: Vec<(String, Option<f64>, Option<f64>, bool)> = sqlx::query_as(
+                    "SELECT test_name, baseline_average, change_percent, regressed
+                     FROM performance_metrics WHERE run_id = 'run-2' ORDER BY id",
+                )
+                .fetch_all(pool)
+                .await
+                .unwrap();
+                (diagnostics, metrics)
+            })
+            .await;
+        let (diagnostics, metrics) = in_progress;
+        assert_eq!(
+            diagnostics,
+            [(
+                "App".to_string(),
+                "warning".to_string(),
+                "linker".to_string()
+            )]
+        );
+        assert_eq!(
+            metrics,
+            [
+                ("testSpeed".to_string(), Some(1.0), Some(50.0), true),
+                ("testLaunch".to_string(), None, None, false),
+            ]
+        );
+        // Written once, and the regression is reported in the run's output
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM performance_metrics WHERE run_id = 'run-2'")
+                .fetch_one(pool)
+                .await
+                .unwrap();
+        assert_eq!(count, 2);
+        assert!(channel.record().lines.contains(
+            &"Performance regression: AppTests.LoginTests.testSpeed Time +50.0% vs previous run"
+                .to_string()
+        ));
+    }
+
+    #[tokio::test]
+    async fn log_checkpoints_append_only_new_lines() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        runs::insert_run(pool, &new_run("run-1")).await.unwrap();
+        let mut record = RunRecord::new("run-1");
+        let stdout = |line: &str| TestRunEvent::Stdout {
+            line: line.to_string(),
+            raw: None,
+        };
+        let checkpoint = |record: &mut RunRecord| {
+            let changes = record.take_changes(true);
+            let log = changes.log_lines.map(|log| log.text);
+            let pool = pool.clone();
+            async move {
+                let mut conn = pool.acquire().await.unwrap();
+                runs::save_run_progress(&mut conn, "run-1", None, log.as_deref())
+                    .await
+                    .unwrap();
+                log
+            }
+        };
+
+        record.observe(&stdout("first"));
+        record.observe(&stdout("second"));
+        assert_eq!(
+            checkpoint(&mut record).await.as_deref(),
+            Some("first\nsecond")
+        );
+        record.observe(&stdout("third"));
+        assert_eq!(checkpoint(&mut record).await.as_deref(), Some("\nthird"));
+        assert_eq!(checkpoint(&mut record).await, None);
+
+        // Lines of a failed write are written with the next checkpoint
+        record.observe(&stdout("fourth"));
+        let failed = record.take_changes(true);
+        record.restore_changes(&failed);
+        record.observe(&stdout("fifth"));
+        assert_eq!(
+            checkpoint(&mut record).await.as_deref(),
+            Some("\nfourth\nfifth")
+        );
+
+        let stored = runs::get_run(pool, "run-1").await.unwrap().unwrap();
+        assert_eq!(stored.raw_log, Some(record.lines.join("\n")));
+    }
+
+    #[test]
+    fn bundle_cases_merge_into_the_latest_attempt_of_their_target() {
+        let mut record = RunRecord::new("run");
+        record.observe(&completed(
+            "App",
+            "AppTests.LoginTests",
+            "testValid",
+            TestStatus::Failed,
+        ));
+        record.observe(&completed(
+            "App",
+            "AppTests.LoginTests",
+            "testValid",
+            TestStatus::Failed,
+        ));
+        record.observe(&completed(
+            "Other",
+            "AppTests.LoginTests",
+            "testValid",
+            TestStatus::Failed,
+        ));
+
+        record.observe(&TestRunEvent::ResultBundleParsed {
+            key: "App".to_string(),
+            bundle_path: "/tmp/App.xcresult".to_string(),
+            test_cases: vec![
+                bundle_case("LoginTests", "testValid", "second attempt"),
+                bundle_case("LoginTests", "testValid", "first attempt"),
+                bundle_case("LoginTests", "testValid", "not in the output"),
+            ],
+            performance_metrics: Vec::new(),
+        });
+
+        let messages: Vec<(Option<&str>, Option<&str>)> = record
+            .test_cases
+            .iter()
+            .map(|tc| (tc.target_key.as_deref(), tc.failure_message.as_deref()))
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                (Some("App"), Some("first attempt")),
+                (Some("App"), Some("second attempt")),
+                (Some("Other"), None),
+                (Some("App"), Some("not in the output")),
+            ]
+        );
+    }
+
+    #[test]
+    fn bundle_cases_merge_into_swift_testing_output_cases() {
+        let mut record = RunRecord::new("run");
+        record.observe(&completed("App", "", "parsesEmptyConfig()", TestStatus::Failed));
+        record.observe(&completed("App", "", "topLevel()", TestStatus::Failed));
+        record.observe(&completed(
+            "App",
+            "MatrixTests",
+            "identity()",
+            TestStatus::Failed,
+        ));
+
+        record.observe(&TestRunEvent::ResultBundleParsed {
+            key: "App".to_string(),
+            bundle_path: "/tmp/App.xcresult".to_string(),
+            test_cases: vec![
+                bundle_case("ConfigTests", "parsesEmptyConfig", "from a suite"),
+                bundle_case("", "topLevel", "top level"),
+                bundle_case("VectorTests", "identity", "another suite's test"),
+            ],
+            performance_metrics: Vec::new(),
+        });
+
+        let cases: Vec<(&str, &str, Option<&str>)> = record
+            .test_cases
+            .iter()
+            .map(|tc| {
+                (
+                    tc.suite_name.as_str(),
+                    tc.test_name.as_str(),
+                    tc.failure_message.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            cases,
+            [
+                ("ConfigTests", "parsesEmptyConfig()", Some("from a suite")),
+                ("", "topLevel()", Some("top level")),
+                ("MatrixTests", "identity()", None),
+                ("VectorTests", "identity", Some("another suite's test")),
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_cases_of_quarantined_tests() {
+        let channel = channel("run");
+        let quarantined = |target: Option<&str>, suite: &str, name: &str| TestInfo {
+            id: 1,
+            project_id: None,
+            target_name: target.map(str::to_string),
+            suite_name: suite.to_string(),
+            test_name: name.to_string(),
+            swift_testing: false,
+        };
+        channel.set_quarantined(&[
+            quarantined(Some("AppTests"), "LoginTests", "testFlaky"),
+            quarantined(None, "SyncTests", "testUpload"),
+        ]);
+        let send = |event| channel.send(event).unwrap();
+        send(completed(
+            "App",
+            "AppTests.LoginTests",
+            "testFlaky",
+            TestStatus::Failed,
+        ));
+        send(completed(
+            "App",
+            "OtherTests.LoginTests",
+            "testFlaky",
+            TestStatus::Failed,
+        ));
+        send(completed(
+            "Sync",
+            "SyncTests",
+            "testUpload",
+            TestStatus::Failed,
+        ));
+        send(completed(
+            "Sync",
+            "SyncTests",
+            "testDownload",
+            TestStatus::Passed,
+        ));
+
+        let record = channel.record();
+        let flags: Vec<bool> = record.test_cases.iter().map(|tc| tc.quarantined).collect();
+        assert_eq!(flags, [true, false, true, false]);
+        assert_eq!(record.quarantined_failures(), 2);
+        assert!(!record.failed_only_quarantined("App"));
+        assert!(record.failed_only_quarantined("Sync"));
+        assert!(!record.failed_only_quarantined("Other"));
+    }
+}
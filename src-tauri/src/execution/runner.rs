@@ -1,5 +1,17 @@
-use crate::models::run::TestRunEvent;
-use tauri::ipc::Channel;
+use crate::execution::crash_reports;
+use crate::execution::progress::ProgressTracker;
+use crate::execution::recorder::RunChannel;
+use crate::execution::test_output::TestOutputCapture;
+use crate::models::run::{TestRunEvent, TestStatus};
+use crate::parsing::build_diagnostics::BuildDiagnosticsParser;
+use crate::parsing::build_progress::BuildProgressParser;
+use crate::parsing::crash_detector::{CrashDetector, TestCrash};
+use crate::parsing::output_parser::{CompositeParser, ParsedEvent, TestOutputParser};
+use crate::parsing::swift_testing_parser::SwiftTestingParser;
+use crate::parsing::xctest_parser::XCTestParser;
+use regex::Regex;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::SystemTime;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio_util::sync::CancellationToken;
@@ -25,17 +37,269 @@ fn resolve_command(program: &str, args: &[String]) -> (String, Vec<String>) {
     }
 }
 
+// Pattern: CSI (ESC [ 1;31 m, ESC [ 2K), OSC (ESC ] 0;title BEL), character set selection
+// (ESC ( B, part of `tput sgr0`) and two-character escapes
+static TERMINAL_ESCAPE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)?|[()*+][0-~]|[@-Z\\-_])",
+    )
+    .unwrap()
+});
+
+/// Clean a line read from the PTY for parsing and search: strip terminal escape sequences,
+/// keep only the text after the last carriage return (progress rewrites redraw the line) and
+/// apply backspaces. Returns the clean line and the raw line if it differed by more than the
+/// line ending.
+fn normalize_line(raw: String) -> (String, Option<String>) {
+    let stripped = TERMINAL_ESCAPE_RE.replace_all(&raw, "");
+    // The PTY ends lines with \r\n; only a \r inside the line is a rewrite
+    let line = stripped.trim_end_matches('\r');
+    let line = line.rsplit('\r').next().unwrap_or(line);
+
+    let mut clean = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '\x08' => {
+                clean.pop();
+            }
+            '\t' => clean.push(c),
+            c if c.is_control() => {}
+            c => clean.push(c),
+        }
+    }
+
+    if clean == raw.trim_end_matches('\r') {
+        (clean, None)
+    } else {
+        (clean, Some(raw))
+    }
+}
+
+/// Parsers for a program's output. `swift test` prints Linux-style `Suite.test` names on
+/// swift-corelibs-xctest, `-[Suite test]` names on macOS, and Swift Testing results.
+fn output_parser(program: &str) -> CompositeParser {
+    let mut parsers: Vec<Box<dyn TestOutputParser>> = vec![Box::new(XCTestParser::xcodebuild())];
+    if program == "swift" {
+        parsers.push(Box::new(XCTestParser::linux()));
+        parsers.push(Box::new(SwiftTestingParser::new()));
+    }
+    parsers.push(Box::new(BuildProgressParser::new()));
+    parsers.push(Box::new(BuildDiagnosticsParser::new()));
+    CompositeParser::new(parsers)
+}
+
+/// State shared by every process of a run.
+pub struct RunContext {
+    pub run_id: String,
+    pub channel: RunChannel,
+    pub cancel_token: CancellationToken,
+    pub progress: Arc<Mutex<ProgressTracker>>,
+}
+
+/// Parses one process's output into events; shared by the stdout and stderr readers.
+struct OutputHandler {
+    target_key: String,
+    channel: RunChannel,
+    progress: Arc<Mutex<ProgressTracker>>,
+    parser: Mutex<CompositeParser>,
+    crashes: Mutex<CrashDetector>,
+    test_output: Mutex<TestOutputCapture>,
+}
+
+impl OutputHandler {
+    /// Store a finished test's output.
+    fn complete_output(&self, suite: &str, name: &str) {
+        let stored = self
+            .test_output
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|mut capture| capture.completed(suite, name));
+        self.report_output_error(stored);
+    }
+
+    /// A failure to store captured test output is reported; the run goes on without it.
+    fn report_output_error(&self, stored: Result<(), String>) {
+        if let Err(e) = stored {
+            let _ = self
+                .channel
+                .send(TestRunEvent::Stderr { line: e, raw: None });
+        }
+    }
+
+    /// Send a test completion and count it towards run progress.
+    fn record_test(&self, event: TestRunEvent) {
+        let _ = self.channel.send(event);
+        if let Ok(mut tracker) = self.progress.lock() {
+            let _ = self.channel.send(tracker.record_test());
+        }
+    }
+
+    fn record_crash(&self, crash: &TestCrash) {
+        self.complete_output(&crash.suite, &crash.name);
+        self.record_test(TestRunEvent::TestCompleted {
+            key: self.target_key.clone(),
+            name: crash.name.clone(),
+            suite: crash.suite.clone(),
+            status: TestStatus::Crashed.to_string(),
+            duration_ms: 0,
+            failure_message: None,
+            file_path: None,
+            line_number: None,
+            status_reason: Some(crash.reason.clone()),
+        });
+    }
+
+    /// Forward crashes, parsed test completions (counted towards run progress), build steps
+    /// and build diagnostics, and capture the line as output of the tests running.
+    fn handle_line(&self, line: &str) {
+        if let Ok(mut capture) = self.test_output.lock() {
+            capture.line(line);
+        }
+
+        let crash = self
+            .crashes
+            .lock()
+            .ok()
+            .and_then(|mut detector| detector.parse_line(line));
+        if let Some(crash) = crash {
+            self.record_crash(&crash);
+            return;
+        }
+
+        let events = self
+            .parser
+            .lock()
+            .map(|mut parser| parser.feed(line))
+            .unwrap_or_default();
+        if let Ok(mut capture) = self.test_output.lock() {
+            for event in &events {
+                if let ParsedEvent::TestStarted { suite, name } = event {
+                    capture.started(suite, name, line);
+                }
+            }
+        }
+        self.send_parsed(events);
+    }
+
+    fn send_parsed(&self, events: Vec<ParsedEvent>) {
+        for event in events {
+            match event {
+                ParsedEvent::TestStarted { suite, name } => {
+                    let _ = self.channel.send(TestRunEvent::TestStarted {
+                        key: self.target_key.clone(),
+                        suite,
+                        name,
+                    });
+                }
+                ParsedEvent::TestCompleted {
+                    suite,
+                    name,
+                    status,
+                    duration_ms,
+                    failure,
+                    reason,
+                } => {
+                    self.complete_output(&suite, &name);
+                    self.record_test(TestRunEvent::TestCompleted {
+                        key: self.target_key.clone(),
+                        name,
+                        suite,
+                        status,
+                        duration_ms,
+                        failure_message: failure.as_ref().map(|f| f.message.clone()),
+                        file_path: failure.as_ref().and_then(|f| f.file_path.clone()),
+                        line_number: failure.and_then(|f| f.line_number),
+                        status_reason: reason,
+                    });
+                }
+                ParsedEvent::BuildProgress(step) => {
+                    let _ = self.channel.send(TestRunEvent::BuildProgress {
+                        key: self.target_key.clone(),
+                        target: step.target,
+                        step: step.step,
+                        completed_steps: step.completed_steps,
+                        total_steps: step.total_steps,
+                    });
+                }
+                ParsedEvent::PerformanceMetric(metric) => {
+                    let _ = self.channel.send(TestRunEvent::PerformanceMetric {
+                        key: self.target_key.clone(),
+                        metric,
+                    });
+                }
+                ParsedEvent::BuildDiagnostic(diagnostic) => {
+                    let _ = self.channel.send(TestRunEvent::BuildDiagnostic {
+                        key: self.target_key.clone(),
+                        diagnostic,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Called once the process has exited and both streams are drained: reports a test left
+    /// running as crashed (unless the run was cancelled) and sends each crash with its log
+    /// excerpt and any `.ips` report written since the process started.
+    fn finish(&self, cancelled: bool, started: SystemTime) {
+        let events = self
+            .parser
+            .lock()
+            .map(|mut parser| parser.finish())
+            .unwrap_or_default();
+        self.send_parsed(events);
+
+        let Ok(mut detector) = self.crashes.lock() else {
+            return;
+        };
+        if let Some(crash) = detector.end_of_output(cancelled) {
+            self.record_crash(&crash);
+        }
+        let stored = self
+            .test_output
+            .lock()
+            .map_err(|e| e.to_string())
+            .and_then(|mut capture| capture.finish());
+        self.report_output_error(stored);
+        let mut claimed = Vec::new();
+        for crash in detector.take_crashes() {
+            let report = crash_reports::find_crash_report(
+                started,
+                crash.process.as_deref(),
+                crash.pid,
+                &claimed,
+            );
+            if let Some(path) = &report {
+                claimed.push(path.clone());
+            }
+            let _ = self.channel.send(TestRunEvent::TestCrashed {
+                key: self.target_key.clone(),
+                suite: crash.suite,
+                name: crash.name,
+                reason: crash.reason,
+                log_excerpt: crash.log_excerpt,
+                crash_report_path: report.map(|p| p.to_string_lossy().to_string()),
+            });
+        }
+    }
+}
+
 /// Spawn a child process and stream its output through the Tauri channel.
 /// For xcodebuild and swift, the process is run inside a PTY (via `script` on macOS)
 /// so output is not fully buffered and appears during long phases (e.g. pre-build).
+/// Test completions parsed from the output are sent as events and counted in the run's
+/// progress, each test's output is stored in the run directory, and build diagnostics are
+/// reported against `target_key`.
 pub async fn spawn_and_stream(
     program: &str,
     args: &[String],
     working_dir: &str,
-    channel: &Channel<TestRunEvent>,
-    cancel_token: CancellationToken,
+    target_key: &str,
+    run: &RunContext,
 ) -> Result<bool, String> {
+    let channel = &run.channel;
+    let cancel_token = run.cancel_token.clone();
     let (resolved_program, resolved_args) = resolve_command(program, args);
+    let started = SystemTime::now();
 
     let mut child = Command::new(&resolved_program)
         .args(&resolved_args)
@@ -48,8 +312,16 @@ pub async fn spawn_and_stream(
     let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
     let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
 
-    let channel_stdout = channel.clone();
-    let channel_stderr = channel.clone();
+    let handler = Arc::new(OutputHandler {
+        target_key: target_key.to_string(),
+        channel: channel.clone(),
+        progress: run.progress.clone(),
+        parser: Mutex::new(output_parser(program)),
+        crashes: Mutex::new(CrashDetector::new()),
+        test_output: Mutex::new(TestOutputCapture::new(&run.run_id, target_key)),
+    });
+    let handler_stdout = handler.clone();
+    let handler_stderr = handler.clone();
     let cancel_stdout = cancel_token.clone();
     let cancel_stderr = cancel_token.clone();
 
@@ -62,8 +334,10 @@ pub async fn spawn_and_stream(
                 _ = cancel_stdout.cancelled() => break,
                 line = lines.next_line() => {
                     match line {
-                        Ok(Some(line)) => {
-                            let _ = channel_stdout.send(TestRunEvent::Stdout { line });
+                        Ok(Some(raw)) => {
+                            let (line, raw) = normalize_line(raw);
+                            let _ = handler_stdout.channel.send(TestRunEvent::Stdout { line: line.clone(), raw });
+                            handler_stdout.handle_line(&line);
                         }
                         Ok(None) => break,
                         Err(_) => break,
@@ -82,8 +356,10 @@ pub async fn spawn_and_stream(
                 _ = cancel_stderr.cancelled() => break,
                 line = lines.next_line() => {
                     match line {
-                        Ok(Some(line)) => {
-                            let _ = channel_stderr.send(TestRunEvent::Stderr { line });
+                        Ok(Some(raw)) => {
+                            let (line, raw) = normalize_line(raw);
+                            let _ = handler_stderr.channel.send(TestRunEvent::Stderr { line: line.clone(), raw });
+                            handler_stderr.handle_line(&line);
                         }
                         Ok(None) => break,
                         Err(_) => break,
@@ -116,6 +392,62 @@ pub async fn spawn_and_stream(
     // Wait for stream tasks to finish
     let _ = stdout_handle.await;
     let _ = stderr_handle.await;
+    // Storing output and looking for crash reports read and write files
+    let cancelled = cancel_token.is_cancelled();
+    tokio::task::spawn_blocking(move || handler.finish(cancelled, started))
+        .await
+        .map_err(|e| format!("Failed to finish output: {}", e))?;
 
     Ok(success)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(raw: &str) -> (String, Option<String>) {
+        normalize_line(raw.to_string())
+    }
+
+    #[test]
+    fn unchanged_lines_have_no_raw_copy() {
+        assert_eq!(
+            normalize("Test Suite 'All tests' started"),
+            ("Test Suite 'All tests' started".to_string(), None)
+        );
+        // The PTY's line ending alone does not count as a change
+        assert_eq!(normalize("Build succeeded\r"), ("Build succeeded".to_string(), None));
+        assert_eq!(normalize("\tindented"), ("\tindented".to_string(), None));
+    }
+
+    #[test]
+    fn terminal_escapes_are_stripped() {
+        let raw = "\x1b[1;31merror:\x1b[0m build failed\x1b[K";
+        assert_eq!(
+            normalize(raw),
+            ("error: build failed".to_string(), Some(raw.to_string()))
+        );
+        let raw = "\x1b]0;swift test\x07Compiling App";
+        assert_eq!(normalize(raw).0, "Compiling App");
+        let raw = "\x1b[2K\x1b(BBuilding for debugging...";
+        assert_eq!(normalize(raw).0, "Building for debugging...");
+    }
+
+    #[test]
+    fn progress_rewrites_keep_the_last_state() {
+        let raw = "[1/3] Compiling\r[2/3] Compiling\r[3/3] Linking App\r";
+        assert_eq!(
+            normalize(raw),
+            ("[3/3] Linking App".to_string(), Some(raw.to_string()))
+        );
+        assert_eq!(normalize("\x1b[1A\x1b[2K\r[8/8] Done").0, "[8/8] Done");
+    }
+
+    #[test]
+    fn backspaces_erase_the_previous_character() {
+        assert_eq!(normalize("Tesst\x08\x08t").0, "Test");
+        assert_eq!(normalize("\x08\x08ok").0, "ok");
+        // Other control characters are dropped
+        assert_eq!(normalize("done\x07\x00").0, "done");
+    }
+}
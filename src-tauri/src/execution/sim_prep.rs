@@ -0,0 +1,313 @@
+use crate::execution::recorder::RunChannel;
+use crate::models::run::TestRunEvent;
+use crate::models::simulator::{PrivacyAction, SimPrepProfile, StatusBarOverride};
+use std::path::Path;
+use tokio::process::Command;
+
+/// One `xcrun simctl` call of a profile and the output line announcing it.
+#[derive(Debug, PartialEq)]
+struct Step {
+    message: String,
+    args: Vec<String>,
+}
+
+impl Step {
+    fn new<S: AsRef<str>>(message: String, args: &[S]) -> Self {
+        Self {
+            message,
+            args: args.iter().map(|a| a.as_ref().to_string()).collect(),
+        }
+    }
+}
+
+/// Apply a simulator prep profile to the simulator `udid` before tests run.
+/// Boots the simulator first (privacy, status bar and appearance need a booted device),
+/// then applies each configured section, reporting every step as an output line. The profile
+/// is checked before anything is changed. Dropping the future stops the `simctl` call in
+/// progress.
+pub async fn apply_profile(
+    udid: &str,
+    profile: &SimPrepProfile,
+    project_path: &str,
+    channel: &RunChannel,
+) -> Result<(), String> {
+    let steps = profile_steps(udid, profile, project_path)?;
+    report(
+        channel,
+        &format!("Applying simulator profile '{}'", profile.name),
+    );
+
+    // -b boots the device if needed and waits until it has finished booting
+    simctl(&["bootstatus", udid, "-b"]).await?;
+
+    for step in steps {
+        report(channel, &step.message);
+        simctl(&step.args).await?;
+    }
+    Ok(())
+}
+
+/// The `simctl` calls applying `profile` to `udid`, in order.
+fn profile_steps(
+    udid: &str,
+    profile: &SimPrepProfile,
+    project_path: &str,
+) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+
+    for perm in &profile.permissions {
+        let mut args = vec![
+            "privacy".to_string(),
+            udid.to_string(),
+            perm.action.to_string(),
+            perm.service.clone(),
+        ];
+        match (&perm.action, &perm.bundle_id) {
+            (_, Some(bundle)) if !bundle.is_empty() => args.push(bundle.clone()),
+            (PrivacyAction::Reset, _) => {}
+            _ => {
+                return Err(format!(
+                    "Privacy {} of '{}' requires a bundle id",
+                    perm.action, perm.service
+                ))
+            }
+        }
+        steps.push(Step::new(format!("simctl {}", args[2..].join(" ")), &args));
+    }
+
+    if let Some(status_bar) = &profile.status_bar {
+        let overrides = status_bar_args(status_bar);
+        if !overrides.is_empty() {
+            let mut args = vec![
+                "status_bar".to_string(),
+                udid.to_string(),
+                "override".to_string(),
+            ];
+            args.extend(overrides);
+            steps.push(Step::new("Overriding status bar".to_string(), &args));
+        }
+    }
+
+    if let Some(locale) = profile.locale.as_deref().filter(|l| !l.is_empty()) {
+        steps.push(Step::new(
+            format!("Setting locale to {}", locale),
+            &[
+                "spawn",
+                udid,
+                "defaults",
+                "write",
+                "-g",
+                "AppleLocale",
+                "-string",
+                locale,
+            ],
+        ));
+    }
+
+    if let Some(language) = profile.language.as_deref().filter(|l| !l.is_empty()) {
+        steps.push(Step::new(
+            format!("Setting language to {}", language),
+            &[
+                "spawn",
+                udid,
+                "defaults",
+                "write",
+                "-g",
+                "AppleLanguages",
+                "-array",
+                language,
+            ],
+        ));
+    }
+
+    if let Some(appearance) = &profile.appearance {
+        steps.push(Step::new(
+            format!("Setting appearance to {}", appearance),
+            &["ui", udid, "appearance", &appearance.to_string()],
+        ));
+    }
+
+    if !profile.media.is_empty() {
+        let mut args = vec!["addmedia".to_string(), udid.to_string()];
+        for media in &profile.media {
+            let p = Path::new(media);
+            let resolved = if p.is_absolute() {
+                p.to_path_buf()
+            } else {
+                Path::new(project_path).join(p)
+            };
+            if !resolved.exists() {
+                return Err(format!("Media file not found: {}", resolved.display()));
+            }
+            args.push(resolved.to_string_lossy().to_string());
+        }
+        steps.push(Step::new(
+            format!("Adding {} media file(s)", profile.media.len()),
+            &args,
+        ));
+    }
+
+    Ok(steps)
+}
+
+/// Map the set fields of a status bar override to `simctl status_bar override` flags.
+fn status_bar_args(o: &StatusBarOverride) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut push = |flag: &str, value: Option<String>| {
+        if let Some(v) = value {
+            args.push(flag.to_string());
+            args.push(v);
+        }
+    };
+    push("--time", o.time.clone());
+    push("--dataNetwork", o.data_network.clone());
+    push("--wifiMode", o.wifi_mode.clone());
+    push("--wifiBars", o.wifi_bars.map(|b| b.to_string()));
+    push("--cellularMode", o.cellular_mode.clone());
+    push("--cellularBars", o.cellular_bars.map(|b| b.to_string()));
+    push("--operatorName", o.operator_name.clone());
+    push("--batteryState", o.battery_state.clone());
+    push("--batteryLevel", o.battery_level.map(|l| l.to_string()));
+    args
+}
+
+fn report(channel: &RunChannel, message: &str) {
+    let _ = channel.send(TestRunEvent::Stdout {
+        line: format!("[sim-prep] {}", message),
+        raw: None,
+    });
+}
+
+async fn simctl<S: AsRef<str>>(args: &[S]) -> Result<(), String> {
+    let output = Command::new("xcrun")
+        .arg("simctl")
+        .args(args.iter().map(|a| a.as_ref()))
+        .kill_on_drop(true)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run xcrun simctl: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let subcommand = args.first().map(|a| a.as_ref()).unwrap_or("");
+        return Err(format!(
+            "xcrun simctl {} failed: {}",
+            subcommand,
+            stderr.trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::simulator::{Appearance, PrivacyPermission};
+
+    const UDID: &str = "8A1B2C3D-0000-4000-8000-000000000001";
+
+    fn profile() -> SimPrepProfile {
+        SimPrepProfile {
+            name: "Screenshots".to_string(),
+            permissions: Vec::new(),
+            status_bar: None,
+            locale: None,
+            language: None,
+            appearance: None,
+            media: Vec::new(),
+        }
+    }
+
+    fn permission(action: PrivacyAction, bundle_id: Option<&str>) -> PrivacyPermission {
+        PrivacyPermission {
+            action,
+            service: "photos".to_string(),
+            bundle_id: bundle_id.map(str::to_string),
+        }
+    }
+
+    fn args(steps: &[Step]) -> Vec<String> {
+        steps.iter().map(|s| s.args.join(" ")).collect()
+    }
+
+    #[test]
+    fn an_empty_profile_has_no_steps() {
+        let mut empty = profile();
+        empty.status_bar = Some(StatusBarOverride::default());
+        empty.locale = Some(String::new());
+        assert!(profile_steps(UDID, &empty, "/tmp").unwrap().is_empty());
+    }
+
+    #[test]
+    fn sections_become_simctl_calls_in_order() {
+        let mut profile = profile();
+        profile.appearance = Some(Appearance::Dark);
+        profile.language = Some("fr".to_string());
+        profile.locale = Some("fr_FR".to_string());
+        profile.permissions = vec![
+            permission(PrivacyAction::Grant, Some("com.example.App")),
+            permission(PrivacyAction::Reset, None),
+        ];
+        profile.status_bar = Some(StatusBarOverride {
+            time: Some("9:41".to_string()),
+            wifi_bars: Some(3),
+            battery_level: Some(100),
+            ..Default::default()
+        });
+
+        let steps = profile_steps(UDID, &profile, "/tmp").unwrap();
+        assert_eq!(
+            args(&steps),
+            [
+                format!("privacy {} grant photos com.example.App", UDID),
+                format!("privacy {} reset photos", UDID),
+                format!(
+                    "status_bar {} override --time 9:41 --wifiBars 3 --batteryLevel 100",
+                    UDID
+                ),
+                format!("spawn {} defaults write -g AppleLocale -string fr_FR", UDID),
+                format!("spawn {} defaults write -g AppleLanguages -array fr", UDID),
+                format!("ui {} appearance dark", UDID),
+            ]
+        );
+        assert_eq!(steps[0].message, "simctl grant photos com.example.App");
+        assert_eq!(steps[2].message, "Overriding status bar");
+    }
+
+    #[test]
+    fn grants_and_revokes_need_a_bundle_id() {
+        let mut profile = profile();
+        profile.permissions = vec![permission(PrivacyAction::Revoke, Some(""))];
+        assert_eq!(
+            profile_steps(UDID, &profile, "/tmp").unwrap_err(),
+            "Privacy revoke of 'photos' requires a bundle id"
+        );
+    }
+
+    #[test]
+    fn media_paths_resolve_against_the_project() {
+        let project = std::env::temp_dir().join(format!("sim-prep-{}", std::process::id()));
+        std::fs::create_dir_all(&project).unwrap();
+        let photo = project.join("photo.png");
+        std::fs::write(&photo, b"").unwrap();
+
+        let mut profile = profile();
+        profile.media = vec!["photo.png".to_string(), photo.to_string_lossy().to_string()];
+        let project_path = project.to_string_lossy().to_string();
+        let steps = profile_steps(UDID, &profile, &project_path).unwrap();
+        let photo = photo.to_string_lossy().to_string();
+        assert_eq!(steps[0].args, ["addmedia", UDID, &photo, &photo]);
+        assert_eq!(steps[0].message, "Adding 2 media file(s)");
+
+        profile.media.push("missing.png".to_string());
+        let missing = profile_steps(UDID, &profile, &project_path).unwrap_err();
+        std::fs::remove_dir_all(&project).unwrap();
+        assert_eq!(
+            missing,
+            format!(
+                "Media file not found: {}",
+                project.join("missing.png").display()
+            )
+        );
+    }
+}
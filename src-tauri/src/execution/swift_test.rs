@@ -1,3 +1,5 @@
+use crate::models::history::TestInfo;
+
 /// Build swift test arguments for a given package path
 pub fn build_args(package_path: &str, filter: Option<&str>) -> Vec<String> {
     let mut args = vec!["test".to_string()];
@@ -10,3 +12,96 @@ pub fn build_args(package_path: &str, filter: Option<&str>) -> Vec<String> {
 
     args
 }
+
+/// `--skip` arguments leaving out quarantined tests. Each matches the test's identifier
+/// (`Module.Suite/test`, or `Module.test()` for a Swift Testing function outside a suite),
+/// with any module when the test's target is unknown.
+pub fn skip_args(tests: &[TestInfo]) -> Vec<String> {
+    tests
+        .iter()
+        .flat_map(|test| {
+            let prefix = match &test.target_name {
+                Some(target) => format!("^{}\\.", regex::escape(target)),
+                None => "(^|\\.)".to_string(),
+            };
+            let suite = if test.suite_name.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", regex::escape(&test.suite_name))
+            };
+            let pattern = format!(
+                "{}{}{}(\\(|$)",
+                prefix,
+                suite,
+                regex::escape(&test.test_name)
+            );
+            ["--skip".to_string(), pattern]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn test(target: Option<&str>, suite: &str, name: &str) -> TestInfo {
+        TestInfo {
+            id: 1,
+            project_id: None,
+            target_name: target.map(str::to_string),
+            suite_name: suite.to_string(),
+            test_name: name.to_string(),
+            swift_testing: false,
+        }
+    }
+
+    /// Identifiers the `--skip` pattern of `test` matches, out of `identifiers`.
+    fn skipped<'a>(test: TestInfo, identifiers: &[&'a str]) -> Vec<&'a str> {
+        let args = skip_args(&[test]);
+        let pattern = Regex::new(&args[1]).unwrap();
+        identifiers
+            .iter()
+            .copied()
+            .filter(|id| pattern.is_match(id))
+            .collect()
+    }
+
+    const IDENTIFIERS: [&str; 5] = [
+        "CoreTests.LoginTests/testValid",
+        "AppTests.LoginTests/testValid",
+        "CoreTests.LoginTests/testValidEmail",
+        "CoreTests.checksum()",
+        "CoreTests.ChecksumTests/checksum()",
+    ];
+
+    #[test]
+    fn skips_suite_tests_by_target_and_identifier() {
+        assert_eq!(
+            skipped(
+                test(Some("CoreTests"), "LoginTests", "testValid"),
+                &IDENTIFIERS
+            ),
+            ["CoreTests.LoginTests/testValid"]
+        );
+        assert_eq!(
+            skipped(test(None, "LoginTests", "testValid"), &IDENTIFIERS),
+            [
+                "CoreTests.LoginTests/testValid",
+                "AppTests.LoginTests/testValid"
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_functions_outside_a_suite() {
+        assert_eq!(
+            skipped(test(Some("CoreTests"), "", "checksum"), &IDENTIFIERS),
+            ["CoreTests.checksum()"]
+        );
+        assert_eq!(
+            skipped(test(None, "", "checksum"), &IDENTIFIERS),
+            ["CoreTests.checksum()"]
+        );
+    }
+}
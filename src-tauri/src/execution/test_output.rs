@@ -0,0 +1,206 @@
+use crate::execution::artifacts;
+use regex::Regex;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// Lines kept per test; beyond this the rest of the test's output is dropped.
+const MAX_LINES_PER_TEST: usize = 5000;
+
+/// Characters of the test id kept in a capture's file name; the hash tells ids apart.
+const MAX_FILE_NAME_CHARS: usize = 80;
+
+// Pattern: Test Suite 'LoginTests' passed at 2024-01-01 10:00:00.000.
+//          ✘ Test run with 3 tests failed after 0.004 seconds with 1 issue.
+static SUITE_FINISHED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"Test Suite '.+' (?:passed|failed) at |Test run with \d+ tests? (?:passed|failed) after",
+    )
+    .unwrap()
+});
+
+/// Identifier of a test's captured output: "Suite/test", or the bare name for tests without a
+/// suite (top-level Swift Testing functions).
+fn test_id(suite: &str, name: &str) -> String {
+    if suite.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", suite, name)
+    }
+}
+
+/// A running test and the lines captured for it so far.
+struct ActiveTest {
+    id: String,
+    /// Swift Testing test (no suite); these run in parallel within a process.
+    parallel: bool,
+    lines: Vec<String>,
+}
+
+/// Collects the lines each test printed, from its started line to its completion line, and
+/// writes them to the run's test output directory, one file per test of the target
+/// `target_key`.
+pub struct TestOutputCapture {
+    dir: PathBuf,
+    target_key: String,
+    active: Vec<ActiveTest>,
+}
+
+impl TestOutputCapture {
+    pub fn new(run_id: &str, target_key: &str) -> Self {
+        Self {
+            dir: artifacts::test_output_dir(run_id),
+            target_key: target_key.to_string(),
+            active: Vec::new(),
+        }
+    }
+
+    /// The test `name` of `suite` started on `line`; a retried test starts over. XCTest runs
+    /// one test at a time, so a start ends the captures of tests that never completed; only
+    /// Swift Testing tests run alongside each other.
+    pub fn started(&mut self, suite: &str, name: &str, line: &str) {
+        let id = test_id(suite, name);
+        let parallel = suite.is_empty();
+        self.active
+            .retain(|test| test.id != id && parallel && test.parallel);
+        self.active.push(ActiveTest {
+            id,
+            parallel,
+            lines: vec![line.to_string()],
+        });
+    }
+
+    /// Add an output line to every running test. Parallel tests can interleave, so a line is
+    /// kept for each test it may belong to. Once a suite or the Swift Testing run has
+    /// finished, tests still running never completed and their captures are dropped.
+    pub fn line(&mut self, line: &str) {
+        if SUITE_FINISHED_RE.is_match(line) {
+            self.active.clear();
+            return;
+        }
+        for test in &mut self.active {
+            if test.lines.len() < MAX_LINES_PER_TEST {
+                test.lines.push(line.to_string());
+            }
+        }
+    }
+
+    /// The test finished (or crashed); store what it printed.
+    pub fn completed(&mut self, suite: &str, name: &str) -> Result<(), String> {
+        let id = test_id(suite, name);
+        let Some(index) = self.active.iter().position(|test| test.id == id) else {
+            return Ok(());
+        };
+        let test = self.active.remove(index);
+        self.write(&test)
+    }
+
+    /// The output ended; store what the tests still running had printed.
+    pub fn finish(&mut self) -> Result<(), String> {
+        for test in std::mem::take(&mut self.active) {
+            self.write(&test)?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, test: &ActiveTest) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create test output dir: {}", e))?;
+        let mut contents = test.lines.join("\n");
+        if test.lines.len() >= MAX_LINES_PER_TEST {
+            contents.push_str("\n… output truncated");
+        }
+        std::fs::write(
+            self.dir.join(file_name(&self.target_key, &test.id)),
+            contents,
+        )
+        .map_err(|e| format!("Failed to write output of {}: {}", test.id, e))
+    }
+}
+
+/// The output the test `test_id` of the target `target_key` printed in a run, as captured by
+/// `TestOutputCapture`.
+pub fn read_test_output(run_id: &str, target_key: &str, test_id: &str) -> Result<String, String> {
+    let path = artifacts::test_output_dir(run_id).join(file_name(target_key, test_id));
+    std::fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "No output captured for {} in run {}: {}",
+            test_id, run_id, e
+        )
+    })
+}
+
+/// Test names can contain spaces, quotes and slashes (Swift Testing display names), which
+/// are replaced for a readable name; a hash of the target and the raw id keeps names that
+/// differ only in such characters, or only in their target, apart.
+fn file_name(target_key: &str, test_id: &str) -> String {
+    let readable: String = test_id
+        .chars()
+        .take(MAX_FILE_NAME_CHARS)
+        .map(|c| match c {
+            '/' | '\\' | ':' | '"' | '\'' | '*' | '?' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let key = format!("{}\0{}", target_key, test_id);
+    format!("{}-{:016x}.log", readable, fnv1a(key.as_bytes()))
+}
+
+/// 64-bit FNV-1a; file names must stay the same across builds, which `DefaultHasher` does not
+/// promise.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn active_ids(capture: &TestOutputCapture) -> Vec<&str> {
+        capture.active.iter().map(|test| test.id.as_str()).collect()
+    }
+
+    #[test]
+    fn xctest_start_ends_captures_that_never_completed() {
+        let mut capture = TestOutputCapture::new("run", "App|AppTests");
+        capture.started("", "Case '-[AppTests.LoginTests testValid]'", "started");
+        capture.started("AppTests.LoginTests", "testValid", "started");
+        assert_eq!(active_ids(&capture), ["AppTests.LoginTests/testValid"]);
+        capture.started("AppTests.LoginTests", "testInvalid", "started");
+        assert_eq!(active_ids(&capture), ["AppTests.LoginTests/testInvalid"]);
+    }
+
+    #[test]
+    fn swift_testing_tests_are_captured_in_parallel() {
+        let mut capture = TestOutputCapture::new("run", "/pkg");
+        capture.started("", "identity()", "◇ Test identity() started.");
+        capture.started("", "determinant()", "◇ Test determinant() started.");
+        capture.line("computing");
+        assert_eq!(active_ids(&capture), ["identity()", "determinant()"]);
+        assert!(capture.active.iter().all(|test| test.lines.len() == 2));
+
+        capture.line("✘ Test run with 2 tests failed after 0.004 seconds with 1 issue.");
+        assert!(capture.active.is_empty());
+    }
+
+    #[test]
+    fn suite_end_drops_running_tests() {
+        let mut capture = TestOutputCapture::new("run", "App");
+        capture.started("LoginTests", "testValid", "started");
+        capture.line("Test Suite 'LoginTests' passed at 2026-01-12 10:14:03.134.");
+        assert!(capture.active.is_empty());
+    }
+
+    #[test]
+    fn file_names_keep_targets_and_sanitized_ids_apart() {
+        let a = file_name("App|AppTests", "Suite/test a");
+        assert_eq!(a, file_name("App|AppTests", "Suite/test a"));
+        assert!(a.starts_with("Suite_test a-") && a.ends_with(".log"));
+        assert_ne!(a, file_name("App|OtherTests", "Suite/test a"));
+        assert_ne!(file_name("App", "a/b"), file_name("App", "a:b"));
+        let long = "x".repeat(500);
+        assert!(file_name("App", &long).len() < 120);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::models::history::TestInfo;
 use std::path::PathBuf;
 
 pub struct XcodebuildArgs {
@@ -13,6 +14,18 @@ fn looks_like_udid(s: &str) -> bool {
             .all(|c| c.is_ascii_hexdigit() || c == '-')
 }
 
+/// Simulator UDID from a run destination: a bare UDID or an xcodebuild spec containing `id=UDID`.
+pub fn simulator_udid(destination: &str) -> Option<String> {
+    let dest = destination.trim();
+    if looks_like_udid(dest) {
+        return Some(dest.to_string());
+    }
+    dest.split(',')
+        .filter_map(|part| part.trim().strip_prefix("id="))
+        .find(|id| looks_like_udid(id))
+        .map(|id| id.to_string())
+}
+
 /// Build xcodebuild test arguments for a scheme, with optional single test target or test plan.
 pub fn build_args(
     project_path: &str,
@@ -23,7 +36,15 @@ pub fn build_args(
     test_plan_name: Option<&str>,
     destination: Option<&str>,
 ) -> XcodebuildArgs {
-    let result_bundle_path = PathBuf::from(result_bundle_dir).join(format!("{}.xcresult", scheme));
+    // One bundle per run entry: xcodebuild refuses to write to an existing bundle path
+    let bundle_name = match (only_testing_target, test_plan_name) {
+        (Some(suffix), _) | (None, Some(suffix)) => {
+            format!("{}-{}", scheme, suffix.replace('/', "_"))
+        }
+        (None, None) => scheme.to_string(),
+    };
+    let result_bundle_path =
+        PathBuf::from(result_bundle_dir).join(format!("{}.xcresult", bundle_name));
 
     let mut args = vec![
         "test".to_string(),
@@ -69,6 +90,43 @@ pub fn build_args(
     }
 }
 
+/// `-skip-testing` arguments leaving out quarantined tests. Tests whose target is unknown
+/// cannot be named to xcodebuild and still run (see `unskippable`); when only
+/// `only_testing_target` runs, tests of other targets are left out of the arguments.
+pub fn skip_testing_args(tests: &[TestInfo], only_testing_target: Option<&str>) -> Vec<String> {
+    tests
+        .iter()
+        .filter_map(|test| {
+            let target = test.target_name.as_deref()?;
+            if only_testing_target.is_some_and(|only| only != target) {
+                return None;
+            }
+            Some(format!("-skip-testing:{}", test_identifier(target, test)))
+        })
+        .collect()
+}
+
+/// xcodebuild's identifier of `test` in `target`: `Target/Class/testMethod` for XCTest,
+/// `Target/Suite/function()` for Swift Testing, and `Target/function()` for a Swift Testing
+/// function outside a suite, which result bundles list under a suite named after the target.
+fn test_identifier(target: &str, test: &TestInfo) -> String {
+    let name = if test.swift_testing {
+        format!("{}()", test.test_name)
+    } else {
+        test.test_name.clone()
+    };
+    if test.suite_name.is_empty() || test.suite_name == target {
+        format!("{}/{}", target, name)
+    } else {
+        format!("{}/{}/{}", target, test.suite_name, name)
+    }
+}
+
+/// Quarantined tests `skip_testing_args` cannot name because their target is unknown.
+pub fn unskippable(tests: &[TestInfo]) -> impl Iterator<Item = &TestInfo> {
+    tests.iter().filter(|test| test.target_name.is_none())
+}
+
 fn find_ext(dir: &std::path::Path, ext: &str) -> Option<String> {
     std::fs::read_dir(dir).ok()?.flatten().find_map(|entry| {
         let p = entry.path();
@@ -1,3 +1,4 @@
+mod analysis;
 mod commands;
 mod discovery;
 mod execution;
@@ -7,24 +8,62 @@ mod persistence;
 mod state;
 
 use state::AppState;
+use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
-        .plugin(
-            tauri_plugin_sql::Builder::default()
-                .add_migrations(
-                    "sqlite:xcode_test_runner.db",
-                    persistence::db::get_migrations(),
-                )
-                .build(),
-        )
+        .plugin(tauri_plugin_sql::Builder::default().build())
         .plugin(tauri_plugin_dialog::init())
-        .manage(AppState::new())
+        .setup(|app| {
+            // Migrations run here, before the webview opens the same database via the SQL plugin
+            let config_dir = app.path().app_config_dir()?;
+            let db = tauri::async_runtime::block_on(async {
+                let db = persistence::db::connect(&config_dir).await?;
+                // Only connecting and migrating are fatal; the upkeep below is logged and
+                // retried on the next start
+                // Runs still marked running were cut short when the app last quit
+                if let Err(e) = persistence::runs::mark_interrupted_runs(&db).await {
+                    eprintln!("Failed to mark interrupted runs: {}", e);
+                }
+                if let Err(e) = persistence::flaky::refresh_all_scores(&db).await {
+                    eprintln!("Failed to refresh flaky scores: {}", e);
+                }
+                let errors = match persistence::retention::apply_retention(&db).await {
+                    Ok(report) => report.errors,
+                    Err(e) => vec![e],
+                };
+                for e in errors {
+                    eprintln!("Failed to apply retention: {}", e);
+                }
+                Ok::<_, String>(db)
+            })?;
+            app.manage(AppState::new(db));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::discovery::discover_project,
             commands::execution::run_tests,
             commands::execution::cancel_run,
+            commands::history::list_runs,
+            commands::history::get_run,
+            commands::history::get_test_cases,
+            commands::history::get_test_history,
+            commands::history::list_flaky_tests,
+            commands::history::compare_runs,
+            commands::history::get_run_annotations,
+            commands::history::list_duration_regressions,
+            commands::history::get_failure_clusters,
+            commands::history::list_failure_clusters,
+            commands::history::set_performance_baseline_pinned,
+            commands::quarantine::list_quarantined_tests,
+            commands::quarantine::quarantine_test,
+            commands::quarantine::release_quarantined_test,
+            commands::retention::apply_retention,
+            commands::retention::delete_run,
+            commands::retention::set_run_pinned,
+            commands::results::export_attachments,
+            commands::results::get_test_output,
             commands::simulators::list_simulators,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,222 @@
+use crate::models::run::TestStatus;
+use serde::{Deserialize, Serialize};
+
+/// A test across runs (`tests` row).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestInfo {
+    pub id: i64,
+    /// None for tests of runs started without a project.
+    pub project_id: Option<String>,
+    pub target_name: Option<String>,
+    pub suite_name: String,
+    pub test_name: String,
+    /// A Swift Testing function rather than an XCTest method, once its output has said so.
+    pub swift_testing: bool,
+}
+
+/// One result of a test in one run, with the revision the run tested where it was recorded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestHistoryEntry {
+    pub run_id: String,
+    pub started_at: String,
+    pub status: TestStatus,
+    pub duration_ms: Option<i64>,
+    pub failure_message: Option<String>,
+    pub git_commit: Option<String>,
+    pub git_branch: Option<String>,
+}
+
+/// Summary of a test over its most recent runs. First-seen and last-failed cover all history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestStats {
+    /// Runs the summary covers.
+    pub runs: i32,
+    pub passed: i32,
+    /// Failed, crashed and timed-out results.
+    pub failed: i32,
+    pub skipped: i32,
+    /// Passed over passed and failed results; None when the test never passed or failed.
+    pub pass_rate: Option<f64>,
+    pub p50_duration_ms: Option<i64>,
+    pub p90_duration_ms: Option<i64>,
+    pub max_duration_ms: Option<i64>,
+    pub first_seen_at: Option<String>,
+    pub last_failed_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestHistory {
+    pub test: TestInfo,
+    /// Results in the covered runs, most recent first.
+    pub entries: Vec<TestHistoryEntry>,
+    pub stats: TestStats,
+}
+
+/// A failing run behind a test's flakiness score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlakyEvidence {
+    pub run_id: String,
+    pub started_at: String,
+    pub failure_message: Option<String>,
+    /// The test also passed in the run.
+    pub passed_on_retry: bool,
+    /// No other test failed in the run.
+    pub only_failure: bool,
+}
+
+/// A test suspected of being flaky, as last scored from its project's recent runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FlakyTest {
+    pub test: TestInfo,
+    pub score: f64,
+    /// Runs the test passed or failed in.
+    pub runs: i32,
+    pub failures: i32,
+    pub flips: i32,
+    pub retry_passes: i32,
+    pub isolated_failures: i32,
+    /// Failing runs, most recent first.
+    pub evidence: Vec<FlakyEvidence>,
+    pub computed_at: String,
+}
+
+/// Distinct tests of a run by their last result, so runs of different targets or destinations
+/// count the same way.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunTotals {
+    pub total: i32,
+    pub passed: i32,
+    /// Failed, crashed and timed-out tests.
+    pub failed: i32,
+    pub skipped: i32,
+}
+
+/// A test whose result differs, or is only present, between two runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestChange {
+    pub test: TestInfo,
+    /// None when the test did not run in the base run.
+    pub base_status: Option<TestStatus>,
+    /// None when the test did not run in the head run.
+    pub head_status: Option<TestStatus>,
+    /// Failure in the head run, or in the base run for fixed and removed tests.
+    pub failure_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurationChange {
+    pub test: TestInfo,
+    pub base_duration_ms: i64,
+    pub head_duration_ms: i64,
+    pub delta_ms: i64,
+    pub change_percent: f64,
+}
+
+/// What changed from a base run to a head run. Tests are matched by identity across runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunComparison {
+    pub base_run_id: String,
+    pub head_run_id: String,
+    pub base_totals: RunTotals,
+    pub head_totals: RunTotals,
+    /// Failing in head, not failing in base.
+    pub newly_failing: Vec<TestChange>,
+    /// Failing in base, passed in head.
+    pub fixed: Vec<TestChange>,
+    pub still_failing: Vec<TestChange>,
+    /// Only in head.
+    pub added: Vec<TestChange>,
+    /// Only in base.
+    pub removed: Vec<TestChange>,
+    /// Largest changes first.
+    pub duration_changes: Vec<DurationChange>,
+}
+
+/// A duration well above a test's or target's recent median.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurationRegression {
+    pub duration_ms: i64,
+    pub median_ms: i64,
+    /// Median absolute deviation of the recent durations.
+    pub mad_ms: i64,
+    /// Earlier runs the median was taken over.
+    pub history_runs: i32,
+    pub change_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationKind {
+    DurationRegression,
+}
+
+impl std::fmt::Display for AnnotationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnnotationKind::DurationRegression => write!(f, "duration_regression"),
+        }
+    }
+}
+
+impl AnnotationKind {
+    /// None for kinds this version does not know.
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "duration_regression" => Some(AnnotationKind::DurationRegression),
+            _ => None,
+        }
+    }
+}
+
+/// A finding attached to a run by analysis after it completed, about one test or target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunAnnotation {
+    pub id: i64,
+    pub run_id: String,
+    pub run_started_at: String,
+    pub kind: AnnotationKind,
+    pub test: Option<TestInfo>,
+    pub target_key: Option<String>,
+    pub message: String,
+    /// Set for duration regressions.
+    pub duration: Option<DurationRegression>,
+    pub created_at: String,
+}
+
+/// One failure within a failure cluster.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterFailure {
+    /// The `test_cases` row.
+    pub case_id: i64,
+    pub run_id: String,
+    pub run_started_at: String,
+    pub test: TestInfo,
+    pub status: TestStatus,
+    pub failure_message: Option<String>,
+    pub file_path: Option<String>,
+    pub line_number: Option<i32>,
+    pub quarantined: bool,
+}
+
+/// Failures sharing a signature: the assertion, the failure message with numbers, UUIDs and
+/// addresses stripped, and the file name and line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailureCluster {
+    pub signature: String,
+    /// e.g. "XCTAssertEqual", "#expect", "crash", "timeout".
+    pub assertion: Option<String>,
+    pub normalized_message: String,
+    pub file_name: Option<String>,
+    pub line_number: Option<i32>,
+    /// Failing test cases in the cluster, so a retried test counts once per attempt.
+    pub count: i32,
+    /// Distinct tests and runs among them.
+    pub tests: i32,
+    pub runs: i32,
+    /// Up to three distinct messages as reported, most recent first.
+    pub representative_messages: Vec<String>,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+    /// Most recent first.
+    pub failures: Vec<ClusterFailure>,
+}
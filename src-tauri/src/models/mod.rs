@@ -1,3 +1,6 @@
+pub mod history;
 pub mod project;
+pub mod quarantine;
 pub mod run;
 pub mod settings;
+pub mod simulator;
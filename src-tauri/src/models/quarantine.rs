@@ -0,0 +1,43 @@
+use crate::models::history::TestInfo;
+use serde::{Deserialize, Serialize};
+
+/// What a quarantine does to its test in runs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuarantineMode {
+    /// The test runs; its failures are reported separately and do not fail the run.
+    Run,
+    /// The test is left out with `-skip-testing` / `--skip`.
+    Skip,
+}
+
+impl std::fmt::Display for QuarantineMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuarantineMode::Run => write!(f, "run"),
+            QuarantineMode::Skip => write!(f, "skip"),
+        }
+    }
+}
+
+impl QuarantineMode {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "skip" => QuarantineMode::Skip,
+            _ => QuarantineMode::Run,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedTest {
+    pub test: TestInfo,
+    pub mode: QuarantineMode,
+    pub reason: String,
+    pub owner: Option<String>,
+    /// Last day (YYYY-MM-DD) the quarantine applies; None for no expiry.
+    pub expires_at: Option<String>,
+    pub created_at: String,
+    /// Past its expiry date: no longer applied, and due for review.
+    pub expired: bool,
+}
@@ -5,6 +5,7 @@ pub struct TestRun {
     pub id: String,
     pub status: RunStatus,
     pub project_path: String,
+    pub project_id: Option<String>,
     pub scope: String,
     pub started_at: String,
     pub finished_at: Option<String>,
@@ -13,6 +14,28 @@ pub struct TestRun {
     pub passed_tests: i32,
     pub failed_tests: i32,
     pub skipped_tests: i32,
+    /// Output of the run, stderr lines prefixed with `[stderr] `.
+    pub raw_log: Option<String>,
+    pub target_results: Option<Vec<TargetResult>>,
+    /// Commit checked out in the project when the run started, if it is a git repository.
+    pub git_commit: Option<String>,
+    pub git_branch: Option<String>,
+    /// Failures of quarantined tests, not included in `failed_tests`.
+    pub quarantined_failures: i32,
+    /// Kept regardless of retention limits.
+    pub pinned: bool,
+}
+
+/// Outcome of one scheme, test plan or package run within a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetResult {
+    pub key: String,
+    pub success: bool,
+    /// Tests parsed for the target (absent on runs recorded before progress tracking).
+    #[serde(default)]
+    pub tests_run: Option<i32>,
+    #[serde(default)]
+    pub duration_ms: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -22,6 +45,8 @@ pub enum RunStatus {
     Passed,
     Failed,
     Cancelled,
+    /// The app quit or crashed while the run was in progress.
+    Interrupted,
 }
 
 impl std::fmt::Display for RunStatus {
@@ -31,6 +56,7 @@ impl std::fmt::Display for RunStatus {
             RunStatus::Passed => write!(f, "passed"),
             RunStatus::Failed => write!(f, "failed"),
             RunStatus::Cancelled => write!(f, "cancelled"),
+            RunStatus::Interrupted => write!(f, "interrupted"),
         }
     }
 }
@@ -41,6 +67,7 @@ impl RunStatus {
             "passed" => RunStatus::Passed,
             "failed" => RunStatus::Failed,
             "cancelled" => RunStatus::Cancelled,
+            "interrupted" => RunStatus::Interrupted,
             _ => RunStatus::Running,
         }
     }
@@ -57,14 +84,99 @@ pub struct TestCase {
     pub failure_message: Option<String>,
     pub file_path: Option<String>,
     pub line_number: Option<i32>,
+    /// `.ips` report for a crashed test, when one was found.
+    pub crash_report_path: Option<String>,
+    /// Why the test was skipped, failed expectedly or timed out, when the output says.
+    pub status_reason: Option<String>,
+    /// Run target (scheme, test plan or package) the test was reported under; its captured
+    /// output is stored under this key.
+    pub target_key: Option<String>,
+    /// The test across runs (`tests` row), once recorded.
+    pub test_id: Option<i64>,
+    /// Test target the test belongs to, when a result bundle or the suite name tells.
+    pub target_name: Option<String>,
+    /// The test was quarantined when the case was recorded; its failure does not fail the run.
+    pub quarantined: bool,
+}
+
+/// A file attached to a test in a result bundle (screenshot, `XCTAttachment`, log), exported
+/// into the run's artifact directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestAttachment {
+    /// Test identifier as the bundle reports it, e.g. "MyTests/testFoo()".
+    pub test_identifier: String,
+    pub name: String,
+    pub uniform_type_identifier: String,
+    pub timestamp: Option<String>,
+    pub path: String,
+    /// Captured as part of a failure (e.g. the automatic failure screenshot).
+    pub associated_with_failure: bool,
 }
 
+/// One metric of an XCTest `measure {}` block: every iteration's value and their summary.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
+pub struct PerformanceMetric {
+    pub suite_name: String,
+    pub test_name: String,
+    /// Display name, e.g. "Time" or "Clock Monotonic Time".
+    pub metric: String,
+    /// Unit of the values, e.g. "seconds" or "kB".
+    pub unit: String,
+    /// XCTest metric identifier, e.g. "com.apple.XCTPerformanceMetric_WallClockTime".
+    pub identifier: Option<String>,
+    pub average: f64,
+    /// Relative standard deviation of the values, in percent.
+    pub relative_std_dev: f64,
+    pub values: Vec<f64>,
+}
+
+impl PerformanceMetric {
+    /// Build a metric from raw measurements, computing the average and relative standard
+    /// deviation the way XCTest reports them.
+    pub fn from_values(
+        suite_name: &str,
+        test_name: &str,
+        metric: &str,
+        unit: &str,
+        identifier: Option<String>,
+        values: Vec<f64>,
+    ) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let count = values.len() as f64;
+        let average = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|v| (v - average).powi(2)).sum::<f64>() / count;
+        let relative_std_dev = if average == 0.0 {
+            0.0
+        } else {
+            variance.sqrt() / average.abs() * 100.0
+        };
+        Some(Self {
+            suite_name: suite_name.to_string(),
+            test_name: test_name.to_string(),
+            metric: metric.to_string(),
+            unit: unit.to_string(),
+            identifier,
+            average,
+            relative_std_dev,
+            values,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
 pub enum TestStatus {
     Passed,
     Failed,
     Skipped,
+    /// The test process crashed or exited while this test was running.
+    Crashed,
+    /// Failed in a way the test declared as known (`XCTExpectFailure`, `withKnownIssue`).
+    ExpectedFailure,
+    /// Exceeded its execution time allowance or time limit.
+    TimedOut,
 }
 
 impl std::fmt::Display for TestStatus {
@@ -73,6 +185,9 @@ impl std::fmt::Display for TestStatus {
             TestStatus::Passed => write!(f, "passed"),
             TestStatus::Failed => write!(f, "failed"),
             TestStatus::Skipped => write!(f, "skipped"),
+            TestStatus::Crashed => write!(f, "crashed"),
+            TestStatus::ExpectedFailure => write!(f, "expected_failure"),
+            TestStatus::TimedOut => write!(f, "timed_out"),
         }
     }
 }
@@ -82,9 +197,87 @@ impl TestStatus {
         match s {
             "passed" => TestStatus::Passed,
             "failed" => TestStatus::Failed,
+            "crashed" => TestStatus::Crashed,
+            "expected_failure" => TestStatus::ExpectedFailure,
+            "timed_out" => TestStatus::TimedOut,
             _ => TestStatus::Skipped,
         }
     }
+
+    /// Crashed and timed-out tests count as failures; expected failures count as neither
+    /// passed nor failed.
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            TestStatus::Failed | TestStatus::Crashed | TestStatus::TimedOut
+        )
+    }
+}
+
+/// A compiler, linker, signing or package resolution problem parsed from build output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BuildDiagnostic {
+    pub severity: DiagnosticSeverity,
+    pub category: DiagnosticCategory,
+    pub file_path: Option<String>,
+    pub line: Option<i32>,
+    pub column: Option<i32>,
+    pub message: String,
+}
+
+impl BuildDiagnostic {
+    pub fn without_location(
+        severity: DiagnosticSeverity,
+        category: DiagnosticCategory,
+        message: &str,
+    ) -> Self {
+        Self {
+            severity,
+            category,
+            file_path: None,
+            line: None,
+            column: None,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticCategory {
+    Compiler,
+    Linker,
+    CodeSigning,
+    PackageResolution,
+    Build,
+}
+
+impl std::fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiagnosticSeverity::Error => write!(f, "error"),
+            DiagnosticSeverity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl std::fmt::Display for DiagnosticCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiagnosticCategory::Compiler => write!(f, "compiler"),
+            DiagnosticCategory::Linker => write!(f, "linker"),
+            DiagnosticCategory::CodeSigning => write!(f, "code_signing"),
+            DiagnosticCategory::PackageResolution => write!(f, "package_resolution"),
+            DiagnosticCategory::Build => write!(f, "build"),
+        }
+    }
 }
 
 /// Events sent through the Tauri channel during test execution
@@ -94,27 +287,130 @@ pub enum TestRunEvent {
     RunStarted {
         run_id: String,
     },
-    Stdout { line: String },
-    Stderr { line: String },
+    /// `line` has terminal escapes and carriage-return rewrites removed and is what gets parsed
+    /// and searched; `raw` is the line as printed, when it differed.
+    Stdout { line: String, raw: Option<String> },
+    Stderr { line: String, raw: Option<String> },
     TestCompleted {
+        /// Run target the test belongs to, as in `TestStarted`.
+        key: String,
         name: String,
         suite: String,
         status: String,
         duration_ms: i64,
+        /// First failure reported for the test in the output, with its source location.
+        failure_message: Option<String>,
+        file_path: Option<String>,
+        line_number: Option<i32>,
+        /// Skip reason, expected-failure reason or time limit message.
+        status_reason: Option<String>,
+    },
+    /// A test's started line; `TestCompleted` marks the end of its output.
+    TestStarted {
+        key: String,
+        suite: String,
+        name: String,
     },
     Progress {
         tests_run: i32,
         tests_total: i32,
+        /// Estimated time left in the run, when every remaining target has a known duration.
+        eta_ms: Option<i64>,
     },
     RunFinished {
         run_id: String,
         success: bool,
     },
+    /// Test cases read from a target's xcresult bundle once xcodebuild has finished.
+    ResultBundleParsed {
+        key: String,
+        bundle_path: String,
+        test_cases: Vec<TestCase>,
+        performance_metrics: Vec<PerformanceMetric>,
+    },
+    /// A `measure {}` metric reported in a test's output.
+    PerformanceMetric {
+        key: String,
+        metric: PerformanceMetric,
+    },
+    TestCrashed {
+        key: String,
+        suite: String,
+        name: String,
+        /// The runner line that reported the crash.
+        reason: String,
+        log_excerpt: String,
+        crash_report_path: Option<String>,
+    },
+    BuildProgress {
+        /// Target key of the run entry being built.
+        key: String,
+        /// Build target named by the current step (e.g. "MyFeature").
+        target: Option<String>,
+        step: String,
+        completed_steps: i32,
+        total_steps: Option<i32>,
+    },
+    BuildDiagnostic {
+        /// Target key of the build that reported it.
+        key: String,
+        #[serde(flatten)]
+        diagnostic: BuildDiagnostic,
+    },
     TargetCompleted {
         key: String,
         success: bool,
+        /// Tests parsed for this target; feeds the next run's progress estimate.
+        tests_run: i32,
+        duration_ms: i64,
     },
     Error {
         message: String,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(values: Vec<f64>) -> Option<PerformanceMetric> {
+        PerformanceMetric::from_values(
+            "AppTests.LoginTests",
+            "testSpeed",
+            "Time",
+            "s",
+            None,
+            values,
+        )
+    }
+
+    #[test]
+    fn metrics_need_measurements() {
+        assert!(metric(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn computes_the_average_and_relative_standard_deviation() {
+        let speed = metric(vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(speed.average, 5.0);
+        // Population standard deviation 2 over an average of 5
+        assert!((speed.relative_std_dev - 40.0).abs() < 1e-9);
+        assert_eq!(speed.values.len(), 8);
+        assert_eq!(
+            (speed.suite_name.as_str(), speed.test_name.as_str()),
+            ("AppTests.LoginTests", "testSpeed")
+        );
+
+        let steady = metric(vec![1.5]).unwrap();
+        assert_eq!((steady.average, steady.relative_std_dev), (1.5, 0.0));
+        // Relative to the magnitude of a negative average
+        let negative = metric(vec![-2.0, -4.0, -4.0, -4.0, -5.0, -5.0, -7.0, -9.0]).unwrap();
+        assert!((negative.relative_std_dev - 40.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_zero_average_has_no_relative_deviation() {
+        let zero = metric(vec![-1.0, 1.0]).unwrap();
+        assert_eq!((zero.average, zero.relative_std_dev), (0.0, 0.0));
+    }
+}
@@ -22,3 +22,23 @@ impl Default for AppSettings {
         }
     }
 }
+
+/// Limits retention applies to unpinned runs; None for no limit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub max_runs: Option<i64>,
+    pub max_age_days: Option<i64>,
+    /// Artifacts (result bundles, attachments, test output) of the kept runs.
+    pub max_disk_bytes: Option<u64>,
+}
+
+/// What one application of retention removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub deleted_runs: Vec<String>,
+    /// Artifacts directories left behind by runs no longer recorded.
+    pub removed_orphans: i32,
+    pub freed_bytes: u64,
+    /// Artifacts that could not be removed; their runs' rows are deleted regardless.
+    pub errors: Vec<String>,
+}
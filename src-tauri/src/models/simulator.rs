@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// Named simulator setup applied with `xcrun simctl` before a run starts.
+/// Stored per project; every section is optional so a profile can be as small as one setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimPrepProfile {
+    pub name: String,
+    #[serde(default)]
+    pub permissions: Vec<PrivacyPermission>,
+    #[serde(default)]
+    pub status_bar: Option<StatusBarOverride>,
+    /// e.g. "en_US" (written to the simulator's global `AppleLocale`).
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// e.g. "fr" (written to the simulator's global `AppleLanguages`).
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub appearance: Option<Appearance>,
+    /// Photos / videos / contacts pushed with `simctl addmedia`. Relative paths resolve
+    /// against the project path.
+    #[serde(default)]
+    pub media: Vec<String>,
+}
+
+/// One `simctl privacy` call, e.g. grant `photos` to `com.example.App`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyPermission {
+    pub action: PrivacyAction,
+    /// simctl service name: all, calendar, contacts, location, photos, microphone, ...
+    pub service: String,
+    /// Required for grant/revoke; reset without a bundle id resets the service for all apps.
+    #[serde(default)]
+    pub bundle_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PrivacyAction {
+    Grant,
+    Revoke,
+    Reset,
+}
+
+impl std::fmt::Display for PrivacyAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrivacyAction::Grant => write!(f, "grant"),
+            PrivacyAction::Revoke => write!(f, "revoke"),
+            PrivacyAction::Reset => write!(f, "reset"),
+        }
+    }
+}
+
+/// `simctl status_bar override` values for deterministic screenshots. Unset fields are left alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatusBarOverride {
+    /// e.g. "9:41" or an ISO date string.
+    pub time: Option<String>,
+    /// hide, wifi, 3g, 4g, lte, lte-a, lte+, 5g, 5g+, 5g-uwb, 5g-uc
+    pub data_network: Option<String>,
+    /// searching, failed, active
+    pub wifi_mode: Option<String>,
+    pub wifi_bars: Option<u8>,
+    /// notSupported, searching, failed, active
+    pub cellular_mode: Option<String>,
+    pub cellular_bars: Option<u8>,
+    pub operator_name: Option<String>,
+    /// charging, charged, discharging
+    pub battery_state: Option<String>,
+    pub battery_level: Option<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Appearance {
+    Light,
+    Dark,
+}
+
+impl std::fmt::Display for Appearance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Appearance::Light => write!(f, "light"),
+            Appearance::Dark => write!(f, "dark"),
+        }
+    }
+}
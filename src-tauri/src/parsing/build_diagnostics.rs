@@ -0,0 +1,328 @@
+use crate::models::run::{BuildDiagnostic, DiagnosticCategory, DiagnosticSeverity};
+use crate::parsing::output_parser::{ParsedEvent, TestOutputParser};
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+// Pattern: /path/File.swift:12:5: error: cannot find 'foo' in scope
+// XCTest assertion failures (`/path/File.swift:42: error: -[Suite test] : ...`) have no column,
+// so requiring one keeps test failures out of the build diagnostics.
+static COMPILER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(/[^:]+):(\d+):(\d+): (error|warning): (.+)$").unwrap());
+
+// Pattern: /path/App.xcodeproj: error: Signing for "App" requires a development team. ...
+static FILE_ERROR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(/[^:]+): (error|warning): (.+)$").unwrap());
+
+// Pattern: ld: library not found for -lFoo / ld: warning: ...
+static LD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ld: (?:(error|warning): )?(.+)$").unwrap());
+
+// Pattern: Undefined symbols for architecture arm64:
+static UNDEFINED_SYMBOLS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^Undefined symbols for architecture \S+:$").unwrap());
+
+// Pattern: clang: error: linker command failed with exit code 1 (use -v to see invocation)
+static LINKER_FAILED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^clang(?:\+\+)?: error: (linker command failed.*)$").unwrap());
+
+// Pattern: xcodebuild: error: ... / error: ... / warning: ...
+static TOOL_MESSAGE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:xcodebuild: |swift-build: |swift-test: )?(error|warning): (.+)$").unwrap()
+});
+
+// Pattern: Test Suite 'All tests' started at ... / Test Case '-[Suite test]' started.
+//          ◇ Test run started. (􀟈 in place of ◇ on macOS)
+static TESTING_STARTED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"Test (?:Suite|Case) '.+' started|[\x{25C7}\x{1007C8}] Test run started\.").unwrap()
+});
+
+const BUILD_FAILED_MESSAGE: &str = "Testing cancelled because the build failed.";
+
+/// Build diagnostics from xcodebuild or swift build output. Only the build phase is parsed:
+/// once tests start, `error:` lines come from the tests and their runner (e.g.
+/// `error: Exited with unexpected signal code 11`), not the build.
+#[derive(Default)]
+pub struct BuildDiagnosticsParser {
+    /// xcodebuild repeats diagnostics in its failure summary; report each one once.
+    seen: HashSet<String>,
+    testing: bool,
+}
+
+impl BuildDiagnosticsParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TestOutputParser for BuildDiagnosticsParser {
+    fn feed(&mut self, line: &str) -> Vec<ParsedEvent> {
+        self.testing |= TESTING_STARTED_RE.is_match(line);
+        if self.testing {
+            return Vec::new();
+        }
+        let Some(diagnostic) = parse_line(line) else {
+            return Vec::new();
+        };
+        let fingerprint = format!(
+            "{:?}:{:?}:{:?}:{}",
+            diagnostic.file_path, diagnostic.line, diagnostic.column, diagnostic.message
+        );
+        if self.seen.insert(fingerprint) {
+            vec![ParsedEvent::BuildDiagnostic(diagnostic)]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Parse a single line of xcodebuild or swift build output into a build diagnostic.
+/// Only errors and warnings are reported; notes and remarks are left in the log.
+fn parse_line(line: &str) -> Option<BuildDiagnostic> {
+    let line = line.trim_end();
+
+    if let Some(caps) = COMPILER_RE.captures(line) {
+        let message = caps.get(5)?.as_str().to_string();
+        return Some(BuildDiagnostic {
+            severity: severity(caps.get(4)?.as_str()),
+            category: categorize(&message, DiagnosticCategory::Compiler),
+            file_path: Some(caps.get(1)?.as_str().to_string()),
+            line: caps.get(2)?.as_str().parse().ok(),
+            column: caps.get(3)?.as_str().parse().ok(),
+            message,
+        });
+    }
+
+    if let Some(caps) = FILE_ERROR_RE.captures(line) {
+        let message = caps.get(3)?.as_str().to_string();
+        return Some(BuildDiagnostic {
+            severity: severity(caps.get(2)?.as_str()),
+            category: categorize(&message, DiagnosticCategory::Build),
+            file_path: Some(caps.get(1)?.as_str().to_string()),
+            line: None,
+            column: None,
+            message,
+        });
+    }
+
+    if let Some(caps) = LD_RE.captures(line) {
+        let severity = caps
+            .get(1)
+            .map(|m| severity(m.as_str()))
+            .unwrap_or(DiagnosticSeverity::Error);
+        return Some(BuildDiagnostic::without_location(
+            severity,
+            DiagnosticCategory::Linker,
+            caps.get(2)?.as_str(),
+        ));
+    }
+
+    if UNDEFINED_SYMBOLS_RE.is_match(line) {
+        return Some(BuildDiagnostic::without_location(
+            DiagnosticSeverity::Error,
+            DiagnosticCategory::Linker,
+            line,
+        ));
+    }
+
+    if let Some(caps) = LINKER_FAILED_RE.captures(line) {
+        return Some(BuildDiagnostic::without_location(
+            DiagnosticSeverity::Error,
+            DiagnosticCategory::Linker,
+            caps.get(1)?.as_str(),
+        ));
+    }
+
+    if line == BUILD_FAILED_MESSAGE {
+        return Some(BuildDiagnostic::without_location(
+            DiagnosticSeverity::Error,
+            DiagnosticCategory::Build,
+            line,
+        ));
+    }
+
+    if let Some(caps) = TOOL_MESSAGE_RE.captures(line) {
+        let message = caps.get(2)?.as_str();
+        return Some(BuildDiagnostic::without_location(
+            severity(caps.get(1)?.as_str()),
+            categorize(message, DiagnosticCategory::Build),
+            message,
+        ));
+    }
+
+    None
+}
+
+fn severity(s: &str) -> DiagnosticSeverity {
+    match s {
+        "warning" => DiagnosticSeverity::Warning,
+        _ => DiagnosticSeverity::Error,
+    }
+}
+
+/// Refine the category from the message text: signing and package resolution failures are
+/// reported through the same `error:` forms as ordinary build errors.
+fn categorize(message: &str, default: DiagnosticCategory) -> DiagnosticCategory {
+    let lower = message.to_lowercase();
+    if lower.contains("signing")
+        || lower.contains("provisioning profile")
+        || lower.contains("codesign")
+        || lower.contains("development team")
+        || lower.contains("errsecinternalcomponent")
+    {
+        DiagnosticCategory::CodeSigning
+    } else if lower.contains("resolve package")
+        || lower.contains("package resolution")
+        || lower.contains("dependencies could not be resolved")
+        || lower.contains("package.resolved")
+        || lower.contains("failed to clone repository")
+    {
+        DiagnosticCategory::PackageResolution
+    } else {
+        default
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::build_progress::BuildProgressParser;
+    use crate::parsing::output_parser::{replay, CompositeParser};
+
+    /// The build parsers the runner combines for every program.
+    fn build_parser() -> CompositeParser {
+        CompositeParser::new(vec![
+            Box::new(BuildProgressParser::new()),
+            Box::new(BuildDiagnosticsParser::new()),
+        ])
+    }
+
+    fn parse(line: &str) -> BuildDiagnostic {
+        parse_line(line).unwrap()
+    }
+
+    #[test]
+    fn compiler_diagnostics_have_a_location() {
+        let diagnostic =
+            parse("/Users/dev/App/Session.swift:42:16: error: cannot find 'token' in scope");
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostic.category, DiagnosticCategory::Compiler);
+        assert_eq!(
+            diagnostic.file_path.as_deref(),
+            Some("/Users/dev/App/Session.swift")
+        );
+        assert_eq!((diagnostic.line, diagnostic.column), (Some(42), Some(16)));
+        assert_eq!(diagnostic.message, "cannot find 'token' in scope");
+
+        let warning = parse("/Users/dev/App/Session.swift:18:9: warning: unused variable");
+        assert_eq!(warning.severity, DiagnosticSeverity::Warning);
+        // Notes and XCTest failures, which have no column, are not build diagnostics
+        assert!(
+            parse_line("/Users/dev/App/Store.swift:7:1: note: 'Store' declared here").is_none()
+        );
+        assert!(parse_line(
+            "/Users/dev/App/Tests.swift:42: error: -[AppTests.LoginTests testValid] : failed"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn linker_diagnostics() {
+        for (line, severity, message) in [
+            (
+                "ld: library not found for -lAnalytics",
+                DiagnosticSeverity::Error,
+                "library not found for -lAnalytics",
+            ),
+            (
+                "ld: warning: ignoring duplicate libraries: '-lc++'",
+                DiagnosticSeverity::Warning,
+                "ignoring duplicate libraries: '-lc++'",
+            ),
+            (
+                "Undefined symbols for architecture arm64:",
+                DiagnosticSeverity::Error,
+                "Undefined symbols for architecture arm64:",
+            ),
+            (
+                "clang: error: linker command failed with exit code 1 (use -v to see invocation)",
+                DiagnosticSeverity::Error,
+                "linker command failed with exit code 1 (use -v to see invocation)",
+            ),
+        ] {
+            let diagnostic = parse(line);
+            assert_eq!(diagnostic.severity, severity, "{}", line);
+            assert_eq!(diagnostic.category, DiagnosticCategory::Linker, "{}", line);
+            assert_eq!(diagnostic.message, message);
+            assert!(diagnostic.file_path.is_none());
+        }
+    }
+
+    #[test]
+    fn code_signing_diagnostics() {
+        for line in [
+            "/Users/dev/App/App.xcodeproj: error: Signing for \"App\" requires a development team.",
+            "error: No profiles for 'com.example.App' were found: Xcode couldn't find any iOS App Development provisioning profiles",
+            "error: The identity used to sign the executable is no longer valid. (errSecInternalComponent)",
+        ] {
+            let diagnostic = parse(line);
+            assert_eq!(diagnostic.severity, DiagnosticSeverity::Error, "{}", line);
+            assert_eq!(diagnostic.category, DiagnosticCategory::CodeSigning, "{}", line);
+        }
+    }
+
+    #[test]
+    fn package_resolution_diagnostics() {
+        for line in [
+            "xcodebuild: error: Could not resolve package dependencies:",
+            "error: Failed to clone repository https://github.com/example/missing.git:",
+            "swift-build: error: Dependencies could not be resolved because root depends on 'missing' 1.0.0..<2.0.0.",
+        ] {
+            let diagnostic = parse(line);
+            assert_eq!(diagnostic.severity, DiagnosticSeverity::Error, "{}", line);
+            assert_eq!(
+                diagnostic.category,
+                DiagnosticCategory::PackageResolution,
+                "{}",
+                line
+            );
+        }
+        let other = parse("xcodebuild: error: Scheme App is not currently configured");
+        assert_eq!(other.category, DiagnosticCategory::Build);
+    }
+
+    #[test]
+    fn errors_after_tests_start_are_not_build_diagnostics() {
+        let mut parser = BuildDiagnosticsParser::new();
+        assert_eq!(parser.feed("error: fatalError").len(), 1);
+        assert!(parser
+            .feed("Test Suite 'All tests' started at 2026-01-12 10:14:03.118.")
+            .is_empty());
+        assert!(parser
+            .feed("error: Exited with unexpected signal code 11")
+            .is_empty());
+        assert!(parser
+            .feed("/Users/dev/App/Session.swift:42:16: error: cannot find 'token' in scope")
+            .is_empty());
+
+        let mut parser = BuildDiagnosticsParser::new();
+        parser.feed("􀟈 Test run started.");
+        assert!(parser
+            .feed("error: Exited with unexpected signal code 11")
+            .is_empty());
+    }
+
+    #[test]
+    fn replays_xcodebuild_build_failure_log() {
+        let log = include_str!("../../tests/fixtures/logs/xcodebuild_build_failure.log");
+        let expected = include_str!("../../tests/fixtures/logs/xcodebuild_build_failure.expected");
+        assert_eq!(replay(&mut build_parser(), log), expected);
+    }
+
+    #[test]
+    fn replays_swift_build_failure_log() {
+        let log = include_str!("../../tests/fixtures/logs/swift_build_failure.log");
+        let expected = include_str!("../../tests/fixtures/logs/swift_build_failure.expected");
+        assert_eq!(replay(&mut build_parser(), log), expected);
+    }
+}
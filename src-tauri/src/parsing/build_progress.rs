@@ -0,0 +1,125 @@
+use crate::parsing::output_parser::{ParsedEvent, TestOutputParser};
+use regex::Regex;
+use std::sync::LazyLock;
+
+// Pattern: [12/340] Compiling MyFeature File.swift (swift build)
+static SWIFT_BUILD_STEP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[(\d+)/(\d+)\] (.+)$").unwrap());
+
+// Pattern: Compiling MyFeature File.swift / Linking MyFeatureTests / Emitting module MyFeature
+static SWIFT_BUILD_TARGET_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:Compiling|Linking|Emitting module|Building|Merging module|Wrapping AST for) (\S+)",
+    )
+    .unwrap()
+});
+
+// Pattern: SwiftCompile normal arm64 /path/File.swift (in target 'MyFeature' from project 'App')
+static XCODEBUILD_STEP_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([A-Z][A-Za-z]+) .*\(in target '([^']+)' from project '[^']+'\)$").unwrap()
+});
+
+// Pattern: === BUILD TARGET MyFeature OF PROJECT App WITH CONFIGURATION Debug ===
+static XCODEBUILD_TARGET_HEADER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^=== BUILD TARGET (.+) OF PROJECT .+ ===$").unwrap());
+
+/// Build steps counted towards progress; other `(in target ...)` lines (scripts, copies,
+/// directory creation) are frequent but say little about how far the build is.
+const XCODEBUILD_STEPS: &[&str] = &[
+    "CompileSwift",
+    "SwiftCompile",
+    "CompileSwiftSources",
+    "SwiftEmitModule",
+    "CompileC",
+    "CompileAssetCatalog",
+    "CompileStoryboard",
+    "CompileXIB",
+    "Ld",
+    "Libtool",
+    "CodeSign",
+    "ProcessInfoPlistFile",
+    "ProcessProductPackaging",
+    "GenerateDSYMFile",
+    "CopySwiftLibs",
+    "PhaseScriptExecution",
+];
+
+/// Snapshot of build progress after a recognized build line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildProgress {
+    /// Target being built, when the line names one.
+    pub target: Option<String>,
+    /// Step name, e.g. "SwiftCompile", "Ld" or "Resolve Package Graph".
+    pub step: String,
+    pub completed_steps: i32,
+    /// Only known for `swift build`, which numbers its steps.
+    pub total_steps: Option<i32>,
+}
+
+/// Counts build steps across one xcodebuild or swift build invocation.
+#[derive(Default)]
+pub struct BuildProgressParser {
+    completed_steps: i32,
+    total_steps: Option<i32>,
+    target: Option<String>,
+}
+
+impl BuildProgressParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a line of build output and return the updated progress if it was a build step.
+    fn parse_line(&mut self, line: &str) -> Option<BuildProgress> {
+        let line = line.trim();
+
+        if let Some(caps) = SWIFT_BUILD_STEP_RE.captures(line) {
+            self.completed_steps = caps.get(1)?.as_str().parse().ok()?;
+            self.total_steps = caps.get(2)?.as_str().parse().ok();
+            let description = caps.get(3)?.as_str();
+            if let Some(target) = SWIFT_BUILD_TARGET_RE.captures(description) {
+                self.target = Some(target.get(1)?.as_str().to_string());
+            }
+            let step = description.split_whitespace().next().unwrap_or(description);
+            return Some(self.snapshot(step));
+        }
+
+        if let Some(caps) = XCODEBUILD_TARGET_HEADER_RE.captures(line) {
+            self.target = Some(caps.get(1)?.as_str().to_string());
+            return Some(self.snapshot("BuildTarget"));
+        }
+
+        if line == "Resolve Package Graph" || line.starts_with("Resolving package graph") {
+            return Some(self.snapshot("Resolve Package Graph"));
+        }
+
+        if let Some(caps) = XCODEBUILD_STEP_RE.captures(line) {
+            let step = caps.get(1)?.as_str();
+            if XCODEBUILD_STEPS.contains(&step) {
+                self.completed_steps += 1;
+                self.target = Some(caps.get(2)?.as_str().to_string());
+                return Some(self.snapshot(step));
+            }
+        }
+
+        None
+    }
+
+    fn snapshot(&self, step: &str) -> BuildProgress {
+        BuildProgress {
+            target: self.target.clone(),
+            step: step.to_string(),
+            completed_steps: self.completed_steps,
+            total_steps: self.total_steps,
+        }
+    }
+}
+
+impl TestOutputParser for BuildProgressParser {
+    fn feed(&mut self, line: &str) -> Vec<ParsedEvent> {
+        self.parse_line(line)
+            .map(ParsedEvent::BuildProgress)
+            .into_iter()
+            .collect()
+    }
+}
@@ -0,0 +1,414 @@
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+
+// Pattern: Test Case '-[SuiteName testMethod]' started.
+static XCODEBUILD_STARTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Test Case '-\[(\S+)\s+(\S+)\]' started").unwrap());
+
+// Pattern: Test Case 'SuiteName.testMethod' started at 2024-01-01 10:00:00.000
+static SWIFT_STARTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Test Case '([^.\[]+)\.(\S+)' started").unwrap());
+
+// Pattern: Test Case '...' passed (0.001 seconds). / failed / skipped
+static FINISHED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Test Case '.+' (passed|failed|skipped) \(").unwrap());
+
+// Pattern: Restarting after unexpected exit, crash, or test timeout in SuiteName.testMethod(); summary will ...
+static RESTART_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"Restarting after unexpected exit, crash, or test timeout in (\S+)\.([^.\s(]+)(?:\(\))?;",
+    )
+    .unwrap()
+});
+
+// Pattern: The test runner exited with code 1 / Test crashed with signal kill. /
+// Exited with unexpected signal code 11 (swift-corelibs-xctest)
+static RUNNER_EXIT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(The test runner exited with code -?\d+|Test crashed with signal \w+|Exited with (?:unexpected )?signal code \d+)",
+    )
+    .unwrap()
+});
+
+// Pattern: ... encountered an error (Crash: MyApp (12345) EXC_BAD_ACCESS ...)
+static CRASH_PROCESS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Crash: (.+?) \((\d+)\)").unwrap());
+
+/// The line of a crashed test's log excerpt that reported the crash, if any.
+pub fn crash_line(excerpt: &str) -> Option<&str> {
+    excerpt.lines().map(str::trim).find(|line| {
+        RESTART_RE.is_match(line)
+            || RUNNER_EXIT_RE.is_match(line)
+            || CRASH_PROCESS_RE.is_match(line)
+    })
+}
+
+/// Whether a test named from a `-[Module.Suite test]` line and one named by a restart line
+/// (`Suite.test()`, without the module) are the same.
+fn same_test(suite: &str, name: &str, other_suite: &str, other_name: &str) -> bool {
+    name == other_name
+        && (suite == other_suite
+            || suite.ends_with(&format!(".{}", other_suite))
+            || other_suite.ends_with(&format!(".{}", suite)))
+}
+
+/// Lines kept before a crash for the log excerpt.
+const LINES_BEFORE: usize = 20;
+/// Lines collected after a crash line before its excerpt is complete.
+const LINES_AFTER: usize = 10;
+
+/// A test whose process crashed, exited or timed out while it was running.
+#[derive(Debug, Clone)]
+pub struct TestCrash {
+    pub suite: String,
+    pub name: String,
+    /// Which run of the test crashed: 1 for its first, more when it was retried or repeated.
+    pub attempt: u32,
+    /// The runner line that reported the crash.
+    pub reason: String,
+    /// Output around the crash: preceding lines, the crash line and a few following lines.
+    pub log_excerpt: String,
+    /// Crashed process name and pid when xcodebuild reports them; used to find the `.ips` report.
+    pub process: Option<String>,
+    pub pid: Option<u32>,
+}
+
+struct PendingCrash {
+    crash: TestCrash,
+    lines_after: usize,
+}
+
+/// Tracks the test currently running so that crashes and unexpected runner exits can be
+/// attributed to it; the test would otherwise just be missing from the results.
+#[derive(Default)]
+pub struct CrashDetector {
+    in_flight: Option<(String, String)>,
+    /// Times each test (suite, name) has started.
+    attempts: HashMap<(String, String), u32>,
+    recent: VecDeque<String>,
+    pending: Vec<PendingCrash>,
+    complete: Vec<TestCrash>,
+}
+
+impl CrashDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one output line. Returns the crashed test as soon as a crash is reported;
+    /// its full log excerpt is available from `take_crashes`.
+    pub fn parse_line(&mut self, line: &str) -> Option<TestCrash> {
+        self.collect_trailing(line);
+        let crash = self.detect(line);
+
+        self.recent.push_back(line.to_string());
+        if self.recent.len() > LINES_BEFORE {
+            self.recent.pop_front();
+        }
+
+        let crash = crash?;
+        // A restart line after "The test runner exited" describes the same crash; it names
+        // the suite without the module prefix that `-[Module.Suite test]` lines carry. A
+        // retried test crashing again is another crash.
+        let already_reported = self
+            .pending
+            .iter()
+            .map(|p| &p.crash)
+            .chain(self.complete.iter())
+            .any(|c| {
+                c.attempt == crash.attempt
+                    && same_test(&c.suite, &c.name, &crash.suite, &crash.name)
+            });
+        if already_reported {
+            return None;
+        }
+        self.pending.push(PendingCrash {
+            crash: crash.clone(),
+            lines_after: 0,
+        });
+        Some(crash)
+    }
+
+    /// End of output. A test still running when the process ended without being cancelled
+    /// is reported as crashed too.
+    pub fn end_of_output(&mut self, interrupted: bool) -> Option<TestCrash> {
+        if interrupted {
+            return None;
+        }
+        let (suite, name) = self.in_flight.take()?;
+        let reason = "Test process exited before the test finished";
+        let crash = self.crash_for(suite, name, reason, None, None, None);
+        self.complete.push(crash.clone());
+        Some(crash)
+    }
+
+    /// Every crash seen so far, with excerpts as complete as the output allowed.
+    pub fn take_crashes(&mut self) -> Vec<TestCrash> {
+        self.complete
+            .extend(self.pending.drain(..).map(|p| p.crash));
+        std::mem::take(&mut self.complete)
+    }
+
+    fn detect(&mut self, line: &str) -> Option<TestCrash> {
+        if let Some(caps) = XCODEBUILD_STARTED_RE
+            .captures(line)
+            .or_else(|| SWIFT_STARTED_RE.captures(line))
+        {
+            let test = (
+                caps.get(1)?.as_str().to_string(),
+                caps.get(2)?.as_str().to_string(),
+            );
+            *self.attempts.entry(test.clone()).or_default() += 1;
+            self.in_flight = Some(test);
+            return None;
+        }
+
+        if FINISHED_RE.is_match(line) {
+            self.in_flight = None;
+            return None;
+        }
+
+        let reason = line.trim();
+        let (process, pid) = CRASH_PROCESS_RE
+            .captures(line)
+            .map(|caps| {
+                (
+                    caps.get(1).map(|m| m.as_str().to_string()),
+                    caps.get(2).and_then(|m| m.as_str().parse().ok()),
+                )
+            })
+            .unwrap_or((None, None));
+
+        if let Some(caps) = RESTART_RE.captures(line) {
+            let (suite, name) = match self.in_flight.take() {
+                Some(test) => test,
+                None => (
+                    caps.get(1)?.as_str().to_string(),
+                    caps.get(2)?.as_str().to_string(),
+                ),
+            };
+            return Some(self.crash_for(suite, name, reason, Some(line), process, pid));
+        }
+
+        if RUNNER_EXIT_RE.is_match(line) || process.is_some() {
+            let (suite, name) = self.in_flight.take()?;
+            return Some(self.crash_for(suite, name, reason, Some(line), process, pid));
+        }
+
+        None
+    }
+
+    fn crash_for(
+        &self,
+        suite: String,
+        name: String,
+        reason: &str,
+        crash_line: Option<&str>,
+        process: Option<String>,
+        pid: Option<u32>,
+    ) -> TestCrash {
+        let excerpt: Vec<&str> = self
+            .recent
+            .iter()
+            .map(|l| l.as_str())
+            .chain(crash_line)
+            .collect();
+        let attempt = self
+            .attempts
+            .get(&(suite.clone(), name.clone()))
+            .or_else(|| {
+                self.attempts
+                    .iter()
+                    .find(|((s, n), _)| same_test(s, n, &suite, &name))
+                    .map(|(_, attempt)| attempt)
+            })
+            .copied()
+            .unwrap_or(0);
+        TestCrash {
+            suite,
+            name,
+            attempt,
+            reason: reason.to_string(),
+            log_excerpt: excerpt.join("\n"),
+            process,
+            pid,
+        }
+    }
+
+    fn collect_trailing(&mut self, line: &str) {
+        for p in &mut self.pending {
+            if p.lines_after < LINES_AFTER {
+                p.crash.log_excerpt.push('\n');
+                p.crash.log_excerpt.push_str(line);
+                p.lines_after += 1;
+            }
+        }
+        let (done, still_pending): (Vec<_>, Vec<_>) = self
+            .pending
+            .drain(..)
+            .partition(|p| p.lines_after >= LINES_AFTER);
+        self.pending = still_pending;
+        self.complete.extend(done.into_iter().map(|p| p.crash));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(detector: &mut CrashDetector, lines: &[&str]) -> Vec<TestCrash> {
+        lines
+            .iter()
+            .filter_map(|line| detector.parse_line(line))
+            .collect()
+    }
+
+    #[test]
+    fn crashes_are_attributed_to_the_test_in_flight() {
+        let mut detector = CrashDetector::new();
+        let crashes = feed(
+            &mut detector,
+            &[
+                "Test Case '-[AppTests.LoginTests testValid]' started.",
+                "Test Case '-[AppTests.LoginTests testValid]' passed (0.001 seconds).",
+                // Nothing in flight between tests
+                "The test runner exited with code 1",
+                "Test Case '-[AppTests.LoginTests testInvalid]' started.",
+                "The test runner exited with code -1",
+            ],
+        );
+        assert_eq!(crashes.len(), 1);
+        assert_eq!(
+            (crashes[0].suite.as_str(), crashes[0].name.as_str()),
+            ("AppTests.LoginTests", "testInvalid")
+        );
+        assert_eq!(crashes[0].reason, "The test runner exited with code -1");
+        assert_eq!(crashes[0].attempt, 1);
+        assert!(detector.end_of_output(false).is_none());
+    }
+
+    #[test]
+    fn runner_exits_name_the_crash() {
+        for exit in [
+            "The test runner exited with code 1",
+            "Test crashed with signal kill.",
+            "Exited with unexpected signal code 11",
+            "Exited with signal code 6",
+        ] {
+            let mut detector = CrashDetector::new();
+            let crashes = feed(
+                &mut detector,
+                &["Test Case 'LoginTests.testValid' started at 2026-01-12 10:00:00.000", exit],
+            );
+            assert_eq!(crashes.len(), 1, "{}", exit);
+            assert_eq!(crashes[0].suite, "LoginTests");
+            assert_eq!(crashes[0].reason, exit);
+        }
+    }
+
+    #[test]
+    fn crash_lines_name_the_process() {
+        let mut detector = CrashDetector::new();
+        let crashes = feed(
+            &mut detector,
+            &[
+                "Test Case '-[AppTests.LoginTests testValid]' started.",
+                "  LoginTests encountered an error (Crash: App (12345) EXC_BAD_ACCESS (SIGSEGV))",
+            ],
+        );
+        assert_eq!(crashes[0].process.as_deref(), Some("App"));
+        assert_eq!(crashes[0].pid, Some(12345));
+        assert_eq!(
+            crashes[0].reason,
+            "LoginTests encountered an error (Crash: App (12345) EXC_BAD_ACCESS (SIGSEGV))"
+        );
+    }
+
+    #[test]
+    fn restart_lines_name_the_test_when_none_is_in_flight() {
+        let mut detector = CrashDetector::new();
+        let crashes = feed(
+            &mut detector,
+            &["Restarting after unexpected exit, crash, or test timeout in LoginTests.testValid(); summary will include totals from previous launches."],
+        );
+        assert_eq!(
+            (crashes[0].suite.as_str(), crashes[0].name.as_str()),
+            ("LoginTests", "testValid")
+        );
+        assert_eq!(crashes[0].attempt, 0);
+    }
+
+    #[test]
+    fn a_restart_after_a_runner_exit_is_the_same_crash() {
+        let restart = "Restarting after unexpected exit, crash, or test timeout in LoginTests.testValid(); summary will include totals from previous launches.";
+        let mut detector = CrashDetector::new();
+        let crashes = feed(
+            &mut detector,
+            &[
+                "Test Case '-[AppTests.LoginTests testValid]' started.",
+                "The test runner exited with code 1",
+                restart,
+                // Retried, and crashing again
+                "Test Case '-[AppTests.LoginTests testValid]' started.",
+                "The test runner exited with code 1",
+                restart,
+            ],
+        );
+        assert_eq!(crashes.len(), 2);
+        assert_eq!(crashes[0].attempt, 1);
+        assert_eq!(crashes[1].attempt, 2);
+        assert!(crashes.iter().all(|c| c.suite == "AppTests.LoginTests"));
+        assert_eq!(detector.take_crashes().len(), 2);
+    }
+
+    #[test]
+    fn excerpts_keep_the_lines_around_the_crash() {
+        let mut detector = CrashDetector::new();
+        detector.parse_line("Test Case '-[AppTests.LoginTests testValid]' started.");
+        for i in 1..=25 {
+            detector.parse_line(&format!("before {}", i));
+        }
+        let crash = detector
+            .parse_line("The test runner exited with code 1")
+            .unwrap();
+        // Reported as soon as seen, with the preceding lines only
+        assert!(crash.log_excerpt.starts_with("before 6\n"));
+        assert!(crash
+            .log_excerpt
+            .ends_with("before 25\nThe test runner exited with code 1"));
+
+        for i in 1..=3 {
+            detector.parse_line(&format!("after {}", i));
+        }
+        let partial = detector.take_crashes();
+        assert!(partial[0].log_excerpt.ends_with("code 1\nafter 1\nafter 2\nafter 3"));
+
+        detector.parse_line("Test Case '-[AppTests.LoginTests testInvalid]' started.");
+        detector.parse_line("The test runner exited with code 1");
+        for i in 1..=12 {
+            detector.parse_line(&format!("after {}", i));
+        }
+        let crashes = detector.take_crashes();
+        let lines: Vec<&str> = crashes[0].log_excerpt.lines().collect();
+        assert_eq!(lines.len(), LINES_BEFORE + 1 + LINES_AFTER);
+        assert_eq!(lines[LINES_BEFORE], "The test runner exited with code 1");
+        assert_eq!(lines.last(), Some(&"after 10"));
+        assert_eq!(crash_line(&crashes[0].log_excerpt), Some(lines[LINES_BEFORE]));
+    }
+
+    #[test]
+    fn a_test_left_running_crashed_unless_interrupted() {
+        let mut detector = CrashDetector::new();
+        detector.parse_line("Test Case '-[AppTests.LoginTests testValid]' started.");
+        let crash = detector.end_of_output(false).unwrap();
+        assert_eq!(crash.name, "testValid");
+        assert_eq!(crash.reason, "Test process exited before the test finished");
+        assert_eq!(detector.take_crashes().len(), 1);
+
+        let mut detector = CrashDetector::new();
+        detector.parse_line("Test Case '-[AppTests.LoginTests testValid]' started.");
+        assert!(detector.end_of_output(true).is_none());
+        assert!(detector.take_crashes().is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::models::run::TestStatus;
+use crate::parsing::output_parser::ParsedEvent;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+// Lines that end a failure's continuation: test case/suite lines and Swift Testing events.
+static STRUCTURE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"Test (?:Case|Suite) '|(?:^|\s)(?:Test|Suite) .+? (?:started|passed|failed|skipped|recorded (?:an|a known) issue)",
+    )
+    .unwrap()
+});
+
+// Failure messages of a test stopped for running too long (XCTest execution time allowance,
+// Swift Testing `.timeLimit`).
+static TIMEOUT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)exceeded (?:its |the )?(?:maximum )?execution time allowance|time limit was exceeded",
+    )
+    .unwrap()
+});
+
+/// Continuation lines kept per failure; beyond this the test is likely printing its own output.
+const MAX_CONTINUATION_LINES: usize = 20;
+
+/// An assertion failure reported in the output before the test's completion line.
+#[derive(Debug, Clone)]
+pub struct TestFailure {
+    pub suite: String,
+    pub name: String,
+    pub message: String,
+    pub file_path: Option<String>,
+    pub line_number: Option<i32>,
+}
+
+/// Failures collected for one test, ready to attach to its completion.
+#[derive(Debug, Clone)]
+pub struct CollectedFailure {
+    /// Every failure message for the test, in order, separated by blank lines.
+    pub message: String,
+    /// Location of the first failure.
+    pub file_path: Option<String>,
+    pub line_number: Option<i32>,
+}
+
+/// Something a test reported about itself besides failures.
+#[derive(Debug, Clone)]
+pub enum TestNote {
+    /// `XCTSkip` message or Swift Testing `.disabled` reason.
+    Skipped(String),
+    /// Failure recorded under `XCTExpectFailure` or `withKnownIssue`.
+    ExpectedFailure(String),
+}
+
+/// Collects failure lines of one format and the continuation lines of multi-line messages
+/// for each test until its completion line.
+pub struct FailureCollector {
+    parse_failure: fn(&str) -> Option<TestFailure>,
+    failures: HashMap<String, Vec<TestFailure>>,
+    notes: HashMap<String, TestNote>,
+    /// Test whose last failure is still receiving continuation lines.
+    continuing: Option<(String, usize)>,
+}
+
+impl FailureCollector {
+    /// `parse_failure` recognizes the format's failure lines.
+    pub fn new(parse_failure: fn(&str) -> Option<TestFailure>) -> Self {
+        Self {
+            parse_failure,
+            failures: HashMap::new(),
+            notes: HashMap::new(),
+            continuing: None,
+        }
+    }
+
+    /// A test started; a retried test starts over.
+    pub fn started(&mut self, suite: &str, name: &str) {
+        self.failures.remove(&key(suite, name));
+        self.notes.remove(&key(suite, name));
+        self.continuing = None;
+    }
+
+    /// Record a skip or expected-failure reason for a test; the first one is kept.
+    pub fn note(&mut self, suite: &str, name: &str, note: TestNote) {
+        self.notes.entry(key(suite, name)).or_insert(note);
+        self.continuing = None;
+    }
+
+    /// Feed an output line that is not one of the format's test lines. Returns true if the
+    /// line belonged to a failure message.
+    pub fn feed(&mut self, line: &str) -> bool {
+        if let Some(failure) = (self.parse_failure)(line) {
+            let key = key(&failure.suite, &failure.name);
+            self.failures.entry(key.clone()).or_default().push(failure);
+            self.continuing = Some((key, 0));
+            return true;
+        }
+
+        if STRUCTURE_RE.is_match(line) {
+            self.continuing = None;
+            return false;
+        }
+
+        let Some((key, count)) = &mut self.continuing else {
+            return false;
+        };
+        let Some(failure) = self.failures.get_mut(key).and_then(|f| f.last_mut()) else {
+            return false;
+        };
+        if *count >= MAX_CONTINUATION_LINES {
+            self.continuing = None;
+            return false;
+        }
+        *count += 1;
+        failure.message.push('\n');
+        failure.message.push_str(line.trim_end());
+        true
+    }
+
+    /// Remove and return the failures collected for a test that has completed.
+    fn take(&mut self, suite: &str, name: &str) -> Option<CollectedFailure> {
+        let key = key(suite, name);
+        self.continuing = None;
+        let failures = self.failures.remove(&key)?;
+        let first = failures.first()?;
+        Some(CollectedFailure {
+            file_path: first.file_path.clone(),
+            line_number: first.line_number,
+            message: failures
+                .iter()
+                .map(|f| f.message.as_str())
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+        })
+    }
+
+    /// Build the completion event for a test from its reported status, attaching collected
+    /// failures and notes. A passed test with an expected failure becomes `expected_failure`
+    /// and a failed test stopped by its time limit becomes `timed_out`.
+    pub fn complete(
+        &mut self,
+        suite: &str,
+        name: &str,
+        status: &str,
+        duration_ms: i64,
+    ) -> ParsedEvent {
+        let failure = self.take(suite, name);
+        let note = self.notes.remove(&key(suite, name));
+        let timeout = failure.as_ref().and_then(|f| timeout_reason(&f.message));
+
+        let (status, reason) = match (TestStatus::from_str(status), note) {
+            (TestStatus::Failed, _) if timeout.is_some() => (TestStatus::TimedOut, timeout),
+            (TestStatus::Passed, Some(TestNote::ExpectedFailure(reason))) => {
+                (TestStatus::ExpectedFailure, Some(reason))
+            }
+            (TestStatus::Skipped, Some(TestNote::Skipped(reason))) => {
+                (TestStatus::Skipped, Some(reason))
+            }
+            (status, _) => (status, None),
+        };
+        let reason = reason.filter(|r| !r.is_empty());
+
+        ParsedEvent::TestCompleted {
+            suite: suite.to_string(),
+            name: name.to_string(),
+            status: status.to_string(),
+            duration_ms,
+            failure,
+            reason,
+        }
+    }
+}
+
+/// The line of a failure message saying the test ran past its time limit, if any.
+pub fn timeout_reason(message: &str) -> Option<String> {
+    message
+        .lines()
+        .find(|line| TIMEOUT_RE.is_match(line))
+        .map(|line| line.trim().to_string())
+}
+
+fn key(suite: &str, name: &str) -> String {
+    format!("{}.{}", suite, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsing::output_parser::replay;
+    use crate::parsing::xctest_parser::XCTestParser;
+
+    #[test]
+    fn collects_multi_line_failures_from_recorded_log() {
+        let log = include_str!("../../tests/fixtures/logs/xcodebuild_multiline_failures.log");
+        let expected =
+            include_str!("../../tests/fixtures/logs/xcodebuild_multiline_failures.expected");
+        assert_eq!(replay(&mut XCTestParser::xcodebuild(), log), expected);
+    }
+}
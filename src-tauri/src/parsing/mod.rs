@@ -1,3 +1,9 @@
+pub mod build_diagnostics;
+pub mod build_progress;
+pub mod crash_detector;
+pub mod failure_collector;
+pub mod output_parser;
+pub mod performance;
 pub mod xcresult;
-pub mod stdout_parser;
-pub mod swift_test_parser;
+pub mod swift_testing_parser;
+pub mod xctest_parser;
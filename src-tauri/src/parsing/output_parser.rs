@@ -0,0 +1,149 @@
+use crate::models::run::{BuildDiagnostic, PerformanceMetric};
+use crate::parsing::build_progress::BuildProgress;
+use crate::parsing::failure_collector::CollectedFailure;
+
+/// Something recognized in test or build output.
+#[derive(Debug, Clone)]
+pub enum ParsedEvent {
+    TestStarted {
+        suite: String,
+        name: String,
+    },
+    TestCompleted {
+        suite: String,
+        name: String,
+        /// A `TestStatus` string
+        status: String,
+        duration_ms: i64,
+        failure: Option<CollectedFailure>,
+        /// Skip reason, expected-failure reason or time limit message.
+        reason: Option<String>,
+    },
+    BuildProgress(BuildProgress),
+    BuildDiagnostic(BuildDiagnostic),
+    PerformanceMetric(PerformanceMetric),
+}
+
+/// A line-oriented parser for one output format. Parsers keep whatever state the format needs
+/// (started tests, multi-line failure messages, counters) across lines of one process.
+pub trait TestOutputParser: Send {
+    fn feed(&mut self, line: &str) -> Vec<ParsedEvent>;
+
+    /// Called once the output has ended.
+    fn finish(&mut self) -> Vec<ParsedEvent> {
+        Vec::new()
+    }
+}
+
+/// Runs several parsers over the same output, e.g. `swift test`, which can print XCTest and
+/// Swift Testing results alongside build output. Every parser sees every line.
+pub struct CompositeParser {
+    parsers: Vec<Box<dyn TestOutputParser>>,
+}
+
+impl CompositeParser {
+    pub fn new(parsers: Vec<Box<dyn TestOutputParser>>) -> Self {
+        Self { parsers }
+    }
+}
+
+impl TestOutputParser for CompositeParser {
+    fn feed(&mut self, line: &str) -> Vec<ParsedEvent> {
+        self.parsers.iter_mut().flat_map(|p| p.feed(line)).collect()
+    }
+
+    fn finish(&mut self) -> Vec<ParsedEvent> {
+        self.parsers.iter_mut().flat_map(|p| p.finish()).collect()
+    }
+}
+
+/// Feed a recorded log through `parser` and render the events it produced, one per line,
+/// for comparison with a golden file.
+#[cfg(test)]
+pub fn replay(parser: &mut dyn TestOutputParser, log: &str) -> String {
+    let mut events: Vec<ParsedEvent> = log.lines().flat_map(|line| parser.feed(line)).collect();
+    events.extend(parser.finish());
+
+    let mut rendered = String::new();
+    for event in events {
+        match event {
+            ParsedEvent::TestStarted { suite, name } => {
+                rendered.push_str(&format!("started {}/{}\n", suite, name));
+            }
+            ParsedEvent::TestCompleted {
+                suite,
+                name,
+                status,
+                duration_ms,
+                failure,
+                reason,
+            } => {
+                rendered.push_str(&format!(
+                    "{} {}/{} {}ms\n",
+                    status, suite, name, duration_ms
+                ));
+                if let Some(reason) = reason {
+                    rendered.push_str(&format!("  reason: {}\n", reason));
+                }
+                if let Some(failure) = failure {
+                    rendered.push_str(&format!(
+                        "  at {}:{}\n",
+                        failure.file_path.as_deref().unwrap_or("?"),
+                        failure
+                            .line_number
+                            .map_or("?".to_string(), |l| l.to_string())
+                    ));
+                    for line in failure.message.lines() {
+                        rendered.push_str(format!("  | {}", line).trim_end());
+                        rendered.push('\n');
+                    }
+                }
+            }
+            ParsedEvent::BuildProgress(progress) => {
+                rendered.push_str(&format!(
+                    "build {} {}/{}",
+                    progress.step,
+                    progress.completed_steps,
+                    progress
+                        .total_steps
+                        .map_or("?".to_string(), |t| t.to_string())
+                ));
+                if let Some(target) = progress.target {
+                    rendered.push_str(&format!(" [{}]", target));
+                }
+                rendered.push('\n');
+            }
+            ParsedEvent::BuildDiagnostic(diagnostic) => {
+                let location = [
+                    diagnostic.file_path,
+                    diagnostic.line.map(|l| l.to_string()),
+                    diagnostic.column.map(|c| c.to_string()),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(":");
+                rendered.push_str(
+                    format!(
+                        "{} {} {}",
+                        diagnostic.severity, diagnostic.category, location
+                    )
+                    .trim_end(),
+                );
+                rendered.push_str(&format!("\n  | {}\n", diagnostic.message));
+            }
+            ParsedEvent::PerformanceMetric(metric) => {
+                rendered.push_str(&format!(
+                    "measured {}/{} {} {} {} ±{:.1}%\n",
+                    metric.suite_name,
+                    metric.test_name,
+                    metric.metric,
+                    metric.average,
+                    metric.unit,
+                    metric.relative_std_dev
+                ));
+            }
+        }
+    }
+    rendered
+}
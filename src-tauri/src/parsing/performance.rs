@@ -0,0 +1,33 @@
+use crate::models::run::PerformanceMetric;
+use regex::Regex;
+use std::sync::LazyLock;
+
+// Pattern: measured [Time, seconds] average: 0.123, relative standard deviation: 4.000%,
+//          values: [0.121, 0.125, ...], performanceMetricID:com.apple.XCTPerformanceMetric_WallClockTime, ...
+static MEASURED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"measured \[(.+?), (.+?)\] average: (-?[\d.]+), relative standard deviation: ([\d.]+)%, values: \[([^\]]*)\](?:, performanceMetricID:([^,\s]+))?",
+    )
+    .unwrap()
+});
+
+/// Parse the `measured [...]` part of an XCTest performance line for the test `suite`/`name`.
+pub fn parse_measurement(suite: &str, name: &str, text: &str) -> Option<PerformanceMetric> {
+    let caps = MEASURED_RE.captures(text)?;
+    let values = caps
+        .get(5)?
+        .as_str()
+        .split(',')
+        .filter_map(|v| v.trim().parse::<f64>().ok())
+        .collect();
+    Some(PerformanceMetric {
+        suite_name: suite.to_string(),
+        test_name: name.to_string(),
+        metric: caps.get(1)?.as_str().to_string(),
+        unit: caps.get(2)?.as_str().to_string(),
+        identifier: caps.get(6).map(|m| m.as_str().to_string()),
+        average: caps.get(3)?.as_str().parse().ok()?,
+        relative_std_dev: caps.get(4)?.as_str().parse().ok()?,
+        values,
+    })
+}
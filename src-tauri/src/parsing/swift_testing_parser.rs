@@ -0,0 +1,269 @@
+use crate::parsing::failure_collector::{FailureCollector, TestFailure, TestNote};
+use crate::parsing::output_parser::{ParsedEvent, TestOutputParser};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+// Pattern: ◇ Test myTest() started. (􀟈 in place of ◇ on macOS)
+// Anchored to the marker: XCTest's `Test Case '-[Suite test]' started.` reads the same otherwise.
+static STARTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\x{25C7}\x{1007C8}] Test (.+?) started\.").unwrap());
+
+// Pattern: ◇ Suite MatrixTests started.
+static SUITE_STARTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\x{25C7}\x{1007C8}] Suite (.+?) started\.").unwrap());
+
+// Pattern: ✔ Suite MatrixTests passed after 0.005 seconds. / ✘ Suite MatrixTests failed after ...
+static SUITE_ENDED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|\s)Suite (.+?) (?:passed|failed) after \d+(?:\.\d+)? seconds").unwrap()
+});
+
+// Pattern: ✔ Test myTest() passed after 0.001 seconds. / ✘ Test myTest() failed after ...
+//          ✔ Test myTest() passed after 0.001 seconds with 1 known issue.
+// (`Test run with N tests ...` is the run summary, not a test)
+static RESULT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|\s)Test (.+?) (passed|failed) after (\d+(?:\.\d+)?) seconds(?: with (\d+ known issues?))?",
+    )
+    .unwrap()
+});
+
+// Pattern: ➜ Test skippedTest() skipped. / ➜ Test skippedTest() skipped: "reason"
+static SKIPPED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?:^|\s)Test (.+?) skipped(?:[.:]|$)(?:\s*"(.*)")?"#).unwrap());
+
+// Pattern: ✘ Test myTest() recorded an issue at MyTests.swift:12:5: Expectation failed: ...
+//          ✘ Test myTest() recorded an issue: Time limit was exceeded: 60.000 seconds
+static ISSUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|\s)Test (.+?) recorded an issue(?: at (.+?):(\d+):\d+)?: (.+)$").unwrap()
+});
+
+// Pattern: ✘ Test myTest() recorded a known issue at MyTests.swift:12:5: Expectation failed: ...
+static KNOWN_ISSUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|\s)Test (.+?) recorded a known issue(?: at .+?:\d+:\d+)?: (.+)$").unwrap()
+});
+
+/// Parse a Swift Testing issue line. Swift Testing lines do not name the suite; the parser
+/// collects issues by test name alone.
+fn parse_issue_line(line: &str) -> Option<TestFailure> {
+    let caps = ISSUE_RE.captures(line)?;
+    Some(TestFailure {
+        suite: String::new(),
+        name: caps.get(1)?.as_str().to_string(),
+        file_path: caps.get(2).map(|m| m.as_str().to_string()),
+        line_number: caps.get(3).and_then(|m| m.as_str().parse().ok()),
+        message: caps.get(4)?.as_str().to_string(),
+    })
+}
+
+/// Swift Testing output from `swift test`.
+///
+/// Test lines do not name their suite. A test is reported in the suite running when it
+/// started if only one was, the way result bundles list it; otherwise, as for top-level
+/// functions, with an empty suite.
+pub struct SwiftTestingParser {
+    issues: FailureCollector,
+    /// Suites started and not yet finished.
+    active_suites: Vec<String>,
+    /// Suite of each test in progress, by test name.
+    test_suites: HashMap<String, String>,
+}
+
+impl SwiftTestingParser {
+    pub fn new() -> Self {
+        Self {
+            issues: FailureCollector::new(parse_issue_line),
+            active_suites: Vec::new(),
+            test_suites: HashMap::new(),
+        }
+    }
+
+    /// The only suite running, unless it is known by a display name (`Suite "Matrix math"`)
+    /// rather than its type.
+    fn current_suite(&self) -> String {
+        match self.active_suites.as_slice() {
+            [suite] if !suite.starts_with('"') => suite.clone(),
+            _ => String::new(),
+        }
+    }
+
+    fn completed(&mut self, name: &str, status: &str, duration_ms: i64) -> Vec<ParsedEvent> {
+        let suite = self
+            .test_suites
+            .remove(name)
+            .unwrap_or_else(|| self.current_suite());
+        let mut event = self.issues.complete("", name, status, duration_ms);
+        if let ParsedEvent::TestCompleted {
+            suite: reported, ..
+        } = &mut event
+        {
+            *reported = suite;
+        }
+        vec![event]
+    }
+}
+
+impl Default for SwiftTestingParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestOutputParser for SwiftTestingParser {
+    fn feed(&mut self, line: &str) -> Vec<ParsedEvent> {
+        // Issue lines come first: their messages may contain "passed" or "skipped"
+        if self.issues.feed(line) {
+            return Vec::new();
+        }
+
+        if let Some(caps) = KNOWN_ISSUE_RE.captures(line) {
+            if let (Some(name), Some(message)) = (caps.get(1), caps.get(2)) {
+                let note = TestNote::ExpectedFailure(message.as_str().to_string());
+                self.issues.note("", name.as_str(), note);
+            }
+            return Vec::new();
+        }
+
+        if let Some(suite) = SUITE_STARTED_RE.captures(line).and_then(|caps| caps.get(1)) {
+            self.active_suites.push(suite.as_str().to_string());
+            return Vec::new();
+        }
+
+        if let Some(suite) = SUITE_ENDED_RE.captures(line).and_then(|caps| caps.get(1)) {
+            if let Some(index) = self.active_suites.iter().position(|s| s == suite.as_str()) {
+                self.active_suites.remove(index);
+            }
+            return Vec::new();
+        }
+
+        if let Some(name) = STARTED_RE.captures(line).and_then(|caps| caps.get(1)) {
+            // `Test run started.` opens the run, not a test
+            if name.as_str() == "run" {
+                return Vec::new();
+            }
+            self.issues.started("", name.as_str());
+            let suite = self.current_suite();
+            self.test_suites
+                .insert(name.as_str().to_string(), suite.clone());
+            return vec![ParsedEvent::TestStarted {
+                suite,
+                name: name.as_str().to_string(),
+            }];
+        }
+
+        if let Some(caps) = RESULT_RE.captures(line) {
+            let (Some(name), Some(status), Some(seconds)) = (caps.get(1), caps.get(2), caps.get(3))
+            else {
+                return Vec::new();
+            };
+            if name.as_str().starts_with("run with ") {
+                return Vec::new();
+            }
+            if let Some(known_issues) = caps.get(4) {
+                let note = TestNote::ExpectedFailure(known_issues.as_str().to_string());
+                self.issues.note("", name.as_str(), note);
+            }
+            let seconds: f64 = seconds.as_str().parse().unwrap_or(0.0);
+            return self.completed(name.as_str(), status.as_str(), (seconds * 1000.0) as i64);
+        }
+
+        if let Some(caps) = SKIPPED_RE.captures(line) {
+            let Some(name) = caps.get(1) else {
+                return Vec::new();
+            };
+            if let Some(reason) = caps.get(2) {
+                let note = TestNote::Skipped(reason.as_str().to_string());
+                self.issues.note("", name.as_str(), note);
+            }
+            return self.completed(name.as_str(), "skipped", 0);
+        }
+
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsing::output_parser::{replay, CompositeParser};
+    use crate::parsing::xctest_parser::XCTestParser;
+
+    /// The test parsers the runner combines for `swift test`.
+    fn swift_test_parser() -> CompositeParser {
+        CompositeParser::new(vec![
+            Box::new(XCTestParser::xcodebuild()),
+            Box::new(XCTestParser::linux()),
+            Box::new(SwiftTestingParser::new()),
+        ])
+    }
+
+    #[test]
+    fn replays_macos_swift_test_log() {
+        let log = include_str!("../../tests/fixtures/logs/swift_test_macos.log");
+        let expected = include_str!("../../tests/fixtures/logs/swift_test_macos.expected");
+        assert_eq!(replay(&mut swift_test_parser(), log), expected);
+    }
+
+    #[test]
+    fn replays_linux_swift_test_log() {
+        let log = include_str!("../../tests/fixtures/logs/swift_test_linux.log");
+        let expected = include_str!("../../tests/fixtures/logs/swift_test_linux.expected");
+        assert_eq!(replay(&mut swift_test_parser(), log), expected);
+    }
+
+    fn started(event: &ParsedEvent) -> (&str, &str) {
+        match event {
+            ParsedEvent::TestStarted { suite, name } => (suite, name),
+            other => panic!("expected TestStarted, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn tests_take_the_suite_only_when_one_is_running() {
+        let mut parser = SwiftTestingParser::new();
+        assert_eq!(
+            started(&parser.feed("◇ Test topLevel() started.")[0]),
+            ("", "topLevel()")
+        );
+
+        parser.feed("◇ Suite MatrixTests started.");
+        assert_eq!(
+            started(&parser.feed("◇ Test identity() started.")[0]),
+            ("MatrixTests", "identity()")
+        );
+        parser.feed("◇ Suite VectorTests started.");
+        assert_eq!(
+            started(&parser.feed("◇ Test length() started.")[0]),
+            ("", "length()")
+        );
+
+        // A test keeps the suite it started in
+        parser.feed("✔ Suite VectorTests passed after 0.001 seconds.");
+        let completed = parser.feed("✔ Test identity() passed after 0.001 seconds.");
+        assert!(matches!(
+            &completed[0],
+            ParsedEvent::TestCompleted { suite, .. } if suite == "MatrixTests"
+        ));
+        let completed = parser.feed("✔ Test length() passed after 0.001 seconds.");
+        assert!(matches!(
+            &completed[0],
+            ParsedEvent::TestCompleted { suite, .. } if suite.is_empty()
+        ));
+
+        parser.feed("✔ Suite MatrixTests passed after 0.002 seconds.");
+        parser.feed("◇ Suite \"Matrix math\" started.");
+        assert_eq!(
+            started(&parser.feed("◇ Test inverse() started.")[0]),
+            ("", "inverse()")
+        );
+    }
+
+    #[test]
+    fn xctest_started_lines_are_not_swift_testing_tests() {
+        let mut parser = SwiftTestingParser::new();
+        assert!(parser
+            .feed("Test Case '-[AppTests.LoginTests testValid]' started.")
+            .is_empty());
+        assert!(parser.feed("◇ Test run started.").is_empty());
+    }
+}
@@ -1,17 +1,84 @@
-use crate::models::run::{TestCase, TestStatus};
+use crate::models::run::{PerformanceMetric, TestAttachment, TestCase, TestStatus};
+use crate::parsing::failure_collector::timeout_reason;
+use regex::Regex;
+use serde::Deserialize;
+use std::path::Path;
 use std::process::Command;
+use std::sync::{LazyLock, OnceLock};
 
-/// Parse an xcresult bundle into structured test cases using xcresulttool
+/// First xcresulttool version (Xcode 16) with `get test-results`; from this version on the
+/// old `get --format json` form is deprecated and only works with `--legacy`.
+const TEST_RESULTS_API_VERSION: u32 = 23000;
+
+// Pattern: xcresulttool version 23021, format version 3.53 (current)
+static VERSION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"xcresulttool version (\d+)").unwrap());
+
+// Pattern: MyTests.swift:42: XCTAssertTrue failed (test-results failure message)
+static FAILURE_LOCATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([^:\s]+\.\w+):(\d+): (.+)$").unwrap());
+
+// Pattern: file:///path/MyTests.swift#CharacterRangeLen=0&EndingLineNumber=41&StartingLineNumber=41
+static DOCUMENT_LOCATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^file://([^#]+)#.*StartingLineNumber=(\d+)").unwrap());
+
+static XCRESULTTOOL_VERSION: OnceLock<Option<u32>> = OnceLock::new();
+
+/// Installed xcresulttool version, detected once per process.
+pub fn xcresulttool_version() -> Option<u32> {
+    *XCRESULTTOOL_VERSION.get_or_init(|| parse_version(&xcresulttool(&["version"]).ok()?))
+}
+
+fn parse_version(output: &str) -> Option<u32> {
+    VERSION_RE.captures(output)?.get(1)?.as_str().parse().ok()
+}
+
+fn has_test_results_api(version: Option<u32>) -> bool {
+    version.is_some_and(|v| v >= TEST_RESULTS_API_VERSION)
+}
+
+/// Parse an xcresult bundle into structured test cases using xcresulttool.
+/// Uses the `test-results` API when the installed tool supports it and falls back to the
+/// legacy object graph otherwise.
 pub fn parse_xcresult(bundle_path: &str) -> Result<Vec<TestCase>, String> {
+    let bundle = ResultBundle::open(bundle_path);
+    if has_test_results_api(xcresulttool_version()) {
+        parse_test_results(&bundle).or_else(|_| parse_legacy(&bundle, true))
+    } else {
+        parse_legacy(&bundle, false)
+    }
+}
+
+/// Read the performance metrics (`measure {}` blocks) recorded in a bundle.
+pub fn parse_performance_metrics(bundle_path: &str) -> Result<Vec<PerformanceMetric>, String> {
+    if has_test_results_api(xcresulttool_version()) {
+        parse_test_results_metrics(bundle_path).or_else(|_| parse_legacy_metrics(bundle_path, true))
+    } else {
+        parse_legacy_metrics(bundle_path, false)
+    }
+}
+
+/// Export the attachments of a bundle (or of the test `test_id`, e.g. "MyTests/testFoo()")
+/// into `output_dir`.
+pub fn export_attachments(
+    bundle_path: &str,
+    output_dir: &Path,
+    test_id: Option<&str>,
+) -> Result<Vec<TestAttachment>, String> {
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| format!("Failed to create attachments dir: {}", e))?;
+    if has_test_results_api(xcresulttool_version()) {
+        export_attachments_modern(bundle_path, output_dir, test_id)
+            .or_else(|_| export_attachments_legacy(bundle_path, output_dir, test_id, true))
+    } else {
+        export_attachments_legacy(bundle_path, output_dir, test_id, false)
+    }
+}
+
+fn xcresulttool(args: &[&str]) -> Result<String, String> {
     let output = Command::new("xcrun")
-        .args([
-            "xcresulttool",
-            "get",
-            "--format",
-            "json",
-            "--path",
-            bundle_path,
-        ])
+        .arg("xcresulttool")
+        .args(args)
         .output()
         .map_err(|e| format!("Failed to run xcresulttool: {}", e))?;
 
@@ -20,117 +87,1111 @@ pub fn parse_xcresult(bundle_path: &str) -> Result<Vec<TestCase>, String> {
         return Err(format!("xcresulttool failed: {}", stderr));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    parse_xcresult_json(&stdout)
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Runs xcresulttool with the given arguments and returns its stdout.
+type RunTool<'a> = Box<dyn Fn(&[&str]) -> Result<String, String> + 'a>;
+
+/// Runs xcresulttool against one bundle; tests substitute recorded output for the tool.
+struct ResultBundle<'a> {
+    run: RunTool<'a>,
+}
+
+impl<'a> ResultBundle<'a> {
+    fn open(path: &'a str) -> Self {
+        ResultBundle {
+            run: Box::new(move |args| {
+                let mut args = args.to_vec();
+                args.extend(["--path", path]);
+                xcresulttool(&args)
+            }),
+        }
+    }
+
+    /// Run `xcresulttool <args> --path <bundle>`.
+    fn xcresulttool(&self, args: &[&str]) -> Result<String, String> {
+        (self.run)(args)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// `xcresulttool get test-results` (Xcode 16+)
+// ---------------------------------------------------------------------------
+
+/// A node of the `test-results tests` tree: test plan → bundle → suite → test case, with
+/// failure messages, repetitions and arguments as children of test cases.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestNode {
+    node_type: String,
+    name: String,
+    node_identifier: Option<String>,
+    result: Option<String>,
+    duration: Option<String>,
+    duration_in_seconds: Option<f64>,
+    #[serde(default)]
+    children: Vec<TestNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestResultsTests {
+    #[serde(default)]
+    test_nodes: Vec<TestNode>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestResultsSummary {
+    #[serde(default)]
+    test_failures: Vec<SummaryFailure>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryFailure {
+    test_name: String,
+    /// e.g. "MyTests/testFoo()"; not written by early Xcode 16 releases
+    test_identifier_string: Option<String>,
+    target_name: Option<String>,
+    failure_text: String,
+}
+
+impl SummaryFailure {
+    /// Whether this failure belongs to `case`, whose node identifier is `identifier`. The same
+    /// test name can appear in several suites and targets.
+    fn is_for(&self, case: &TestCase, identifier: Option<&str>) -> bool {
+        if test_name(&self.test_name) != case.test_name {
+            return false;
+        }
+        if let (Some(target), Some(case_target)) = (&self.target_name, &case.target_name) {
+            if target != case_target {
+                return false;
+            }
+        }
+        match (self.test_identifier_string.as_deref(), identifier) {
+            (Some(id), Some(case_id)) => id == case_id,
+            (Some(id), None) => id
+                .rsplit_once('/')
+                .and_then(|(suite, _)| suite.rsplit('/').next())
+                .is_some_and(|suite| suite == case.suite_name),
+            // Without an identifier only the name and target can be compared
+            (None, _) => true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestDetails {
+    #[serde(default)]
+    test_runs: Vec<TestNode>,
+}
+
+/// `test-results metrics` lists the tests that recorded metrics, per test run (configuration,
+/// device, repetition).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestWithMetrics {
+    test_identifier: String,
+    #[serde(default)]
+    test_runs: Vec<TestRunWithMetrics>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestRunWithMetrics {
+    #[serde(default)]
+    metrics: Vec<TestMetric>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestMetric {
+    display_name: String,
+    unit_of_measurement: String,
+    identifier: Option<String>,
+    #[serde(default)]
+    measurements: Vec<f64>,
+}
+
+/// `export attachments` writes the files plus a manifest.json grouping them by test.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttachmentManifestEntry {
+    test_identifier: String,
+    #[serde(default)]
+    attachments: Vec<ManifestAttachment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestAttachment {
+    exported_file_name: String,
+    suggested_human_readable_name: Option<String>,
+    #[serde(default)]
+    is_associated_with_failure: bool,
+    /// Seconds since 1970
+    timestamp: Option<f64>,
+}
+
+fn export_attachments_modern(
+    bundle_path: &str,
+    output_dir: &Path,
+    test_id: Option<&str>,
+) -> Result<Vec<TestAttachment>, String> {
+    let output_path = output_dir.to_string_lossy();
+    let mut args = vec![
+        "export",
+        "attachments",
+        "--path",
+        bundle_path,
+        "--output-path",
+        &output_path,
+    ];
+    if let Some(id) = test_id {
+        args.extend(["--test-id", id]);
+    }
+    xcresulttool(&args)?;
+
+    let manifest = std::fs::read_to_string(output_dir.join("manifest.json"))
+        .map_err(|e| format!("Failed to read attachments manifest: {}", e))?;
+    let entries: Vec<AttachmentManifestEntry> = serde_json::from_str(&manifest)
+        .map_err(|e| format!("Failed to parse attachments manifest: {}", e))?;
+
+    Ok(entries
+        .into_iter()
+        .flat_map(|entry| {
+            let test_identifier = entry.test_identifier;
+            entry.attachments.into_iter().map(move |a| TestAttachment {
+                test_identifier: test_identifier.clone(),
+                // The manifest has no UTI; derive it from the exported file's extension
+                uniform_type_identifier: uti_for_file(&a.exported_file_name).to_string(),
+                name: a
+                    .suggested_human_readable_name
+                    .unwrap_or_else(|| a.exported_file_name.clone()),
+                timestamp: a
+                    .timestamp
+                    .and_then(|t| chrono::DateTime::from_timestamp_millis((t * 1000.0) as i64))
+                    .map(|t| t.to_rfc3339()),
+                path: output_dir
+                    .join(&a.exported_file_name)
+                    .to_string_lossy()
+                    .to_string(),
+                associated_with_failure: a.is_associated_with_failure,
+            })
+        })
+        .collect())
+}
+
+fn uti_for_file(file_name: &str) -> &'static str {
+    let ext = Path::new(file_name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "public.png",
+        "jpg" | "jpeg" => "public.jpeg",
+        "heic" => "public.heic",
+        "txt" | "log" => "public.plain-text",
+        "json" => "public.json",
+        "plist" => "com.apple.property-list",
+        "mp4" => "public.mpeg-4",
+        "mov" => "com.apple.quicktime-movie",
+        "zip" => "public.zip-archive",
+        _ => "public.data",
+    }
+}
+
+fn parse_test_results(bundle: &ResultBundle) -> Result<Vec<TestCase>, String> {
+    let tests = bundle.xcresulttool(&["get", "test-results", "tests"])?;
+    let mut cases = parse_test_results_json(&tests)?;
+
+    // The tests tree normally carries failure messages; fall back to the summary and then to
+    // per-test details for failed tests without one.
+    let mut summary: Option<Option<TestResultsSummary>> = None;
+    for (case, identifier) in cases
+        .iter_mut()
+        .filter(|(c, _)| c.status == TestStatus::Failed && c.failure_message.is_none())
+    {
+        // Summary failure texts carry no location; test-details messages do.
+        let summary = summary.get_or_insert_with(|| {
+            bundle
+                .xcresulttool(&["get", "test-results", "summary"])
+                .ok()
+                .and_then(|json| serde_json::from_str(&json).ok())
+        });
+        case.failure_message = summary.as_ref().and_then(|s| {
+            s.test_failures
+                .iter()
+                .find(|f| f.is_for(case, identifier.as_deref()))
+                .map(|f| f.failure_text.clone())
+        });
+        if let (None, Some(id)) = (&case.failure_message, identifier.as_deref()) {
+            case.failure_message = bundle
+                .xcresulttool(&["get", "test-results", "test-details", "--test-id", id])
+                .ok()
+                .and_then(|json| serde_json::from_str::<TestDetails>(&json).ok())
+                .and_then(|details| failure_message(&details.test_runs));
+            if let Some(message) = case.failure_message.take() {
+                (case.failure_message, case.file_path, case.line_number) =
+                    split_failure_location(message);
+            }
+        }
+    }
+
+    Ok(cases.into_iter().map(|(case, _)| case).collect())
+}
+
+fn parse_test_results_metrics(bundle_path: &str) -> Result<Vec<PerformanceMetric>, String> {
+    let json = xcresulttool(&["get", "test-results", "metrics", "--path", bundle_path])?;
+    let tests: Vec<TestWithMetrics> = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse test-results metrics JSON: {}", e))?;
+
+    let mut metrics = Vec::new();
+    for test in &tests {
+        let (suite, name) = match test.test_identifier.rsplit_once('/') {
+            Some((suite, name)) => (suite, test_name(name)),
+            None => ("Unknown", test_name(&test.test_identifier)),
+        };
+        for metric in test.test_runs.iter().flat_map(|run| &run.metrics) {
+            metrics.extend(PerformanceMetric::from_values(
+                suite,
+                name,
+                &metric.display_name,
+                &metric.unit_of_measurement,
+                metric.identifier.clone(),
+                metric.measurements.clone(),
+            ));
+        }
+    }
+    Ok(metrics)
 }
 
-fn parse_xcresult_json(json_str: &str) -> Result<Vec<TestCase>, String> {
-    let value: serde_json::Value =
-        serde_json::from_str(json_str).map_err(|e| format!("Failed to parse xcresult JSON: {}", e))?;
+/// Test cases paired with their node identifier (e.g. "MyTests/testFoo()"), which
+/// `test-details` takes as `--test-id`.
+fn parse_test_results_json(json_str: &str) -> Result<Vec<(TestCase, Option<String>)>, String> {
+    let tests: TestResultsTests = serde_json::from_str(json_str)
+        .map_err(|e| format!("Failed to parse test-results JSON: {}", e))?;
 
-    let mut test_cases = Vec::new();
-    extract_test_cases(&value, &mut test_cases, "");
-    Ok(test_cases)
+    let mut cases = Vec::new();
+    for node in &tests.test_nodes {
+        collect_test_cases(node, None, "Unknown", &mut cases);
+    }
+    Ok(cases)
 }
 
-fn extract_test_cases(value: &serde_json::Value, cases: &mut Vec<TestCase>, run_id: &str) {
-    // xcresulttool output structure varies by Xcode version
-    // This handles the common nested actions → testPlanRunSummaries → testableSummaries pattern
-    if let Some(actions) = value.get("actions").and_then(|a| a.get("_values")).and_then(|v| v.as_array()) {
-        for action in actions {
-            if let Some(test_ref) = action.get("actionResult").and_then(|r| r.get("testsRef")) {
-                let _ = test_ref; // Would need to follow the reference with another xcresulttool call
+fn collect_test_cases(
+    node: &TestNode,
+    target_name: Option<&str>,
+    suite_name: &str,
+    cases: &mut Vec<(TestCase, Option<String>)>,
+) {
+    match node.node_type.as_str() {
+        "Test Case" => {
+            // Skipped and expected-failure tests carry their reason as a message child
+            let message = failure_message(&node.children);
+            let (status, failure, status_reason) = match node.result.as_deref() {
+                Some("Passed") => (TestStatus::Passed, None, None),
+                Some("Failed") => match message.as_deref().and_then(timeout_reason) {
+                    Some(reason) => (TestStatus::TimedOut, message, Some(reason)),
+                    None => (TestStatus::Failed, message, None),
+                },
+                Some("Skipped") => (TestStatus::Skipped, None, message),
+                Some("Expected Failure") => (TestStatus::ExpectedFailure, None, message),
+                other => (
+                    TestStatus::Failed,
+                    message,
+                    Some(format!(
+                        "Result reported as \"{}\"",
+                        other.unwrap_or("unknown")
+                    )),
+                ),
+            };
+            let (failure_message, file_path, line_number) = match failure {
+                Some(message) => split_failure_location(message),
+                None => (None, None, None),
+            };
+            let case = TestCase {
+                id: None,
+                run_id: String::new(),
+                suite_name: suite_name.to_string(),
+                test_name: test_name(&node.name).to_string(),
+                status,
+                duration_ms: node
+                    .duration_in_seconds
+                    .or_else(|| node.duration.as_deref().and_then(parse_duration))
+                    .map(|s| (s * 1000.0) as i64),
+                failure_message,
+                file_path,
+                line_number,
+                crash_report_path: None,
+                status_reason,
+                target_key: None,
+                test_id: None,
+                target_name: target_name.map(|t| t.to_string()),
+                quarantined: false,
+            };
+            cases.push((case, node.node_identifier.clone()));
+        }
+        "Test Suite" => {
+            for child in &node.children {
+                collect_test_cases(child, target_name, &node.name, cases);
+            }
+        }
+        // Tests outside any suite (top-level Swift Testing functions) get an empty suite, as
+        // their output lines do
+        "Unit test bundle" | "UI test bundle" => {
+            for child in &node.children {
+                collect_test_cases(child, Some(&node.name), "", cases);
+            }
+        }
+        _ => {
+            for child in &node.children {
+                collect_test_cases(child, target_name, suite_name, cases);
             }
         }
     }
+}
 
-    // Direct test summaries parsing
-    if let Some(summaries) = value.get("testPlanRunSummaries").and_then(|s| s.get("_values")).and_then(|v| v.as_array()) {
-        for summary in summaries {
-            if let Some(testable_summaries) = summary.get("testableSummaries").and_then(|t| t.get("_values")).and_then(|v| v.as_array()) {
-                for testable in testable_summaries {
-                    extract_from_testable(testable, cases, run_id);
+/// First failure message below `nodes`, e.g. "MyTests.swift:42: XCTAssertTrue failed".
+fn failure_message(nodes: &[TestNode]) -> Option<String> {
+    nodes.iter().find_map(|node| {
+        if node.node_type == "Failure Message" {
+            Some(node.name.clone())
+        } else {
+            failure_message(&node.children)
+        }
+    })
+}
+
+/// Split "MyTests.swift:42: message" into message, file and line. test-results only names the
+/// file; the full path comes from the output's failure line when there is one.
+fn split_failure_location(message: String) -> (Option<String>, Option<String>, Option<i32>) {
+    match FAILURE_LOCATION_RE.captures(&message) {
+        Some(caps) => (
+            caps.get(3).map(|m| m.as_str().to_string()),
+            caps.get(1).map(|m| m.as_str().to_string()),
+            caps.get(2).and_then(|m| m.as_str().parse().ok()),
+        ),
+        None => (Some(message), None, None),
+    }
+}
+
+/// XCTest methods are listed as `testFoo()`; output lines name them `testFoo`.
+fn test_name(name: &str) -> &str {
+    name.strip_suffix("()").unwrap_or(name)
+}
+
+/// Parse durations such as "0.12s", "12s" or "1m 3s" into seconds.
+fn parse_duration(s: &str) -> Option<f64> {
+    s.split_whitespace().try_fold(0.0, |total, part| {
+        if let Some(minutes) = part.strip_suffix('m') {
+            Some(total + minutes.parse::<f64>().ok()? * 60.0)
+        } else {
+            Some(total + part.strip_suffix('s')?.parse::<f64>().ok()?)
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Legacy object graph (`xcresulttool get --format json`)
+// ---------------------------------------------------------------------------
+
+/// A legacy bundle's object graph. The root record only references test results: each
+/// action's `testsRef` points at an `ActionTestPlanRunSummaries` object and each test's
+/// `summaryRef` at its `ActionTestSummary`, both fetched with `--id`.
+struct LegacyBundle<'a> {
+    bundle: &'a ResultBundle<'a>,
+    /// Needed from Xcode 16 on, where the plain form is deprecated.
+    legacy_flag: bool,
+}
+
+impl LegacyBundle<'_> {
+    fn get(&self, id: Option<&str>) -> Result<serde_json::Value, String> {
+        let mut args = vec!["get"];
+        if self.legacy_flag {
+            args.push("--legacy");
+        }
+        args.extend(["--format", "json"]);
+        if let Some(id) = id {
+            args.extend(["--id", id]);
+        }
+        let stdout = self.bundle.xcresulttool(&args)?;
+        serde_json::from_str(&stdout).map_err(|e| format!("Failed to parse xcresult JSON: {}", e))
+    }
+}
+
+/// Export every attachment below the bundle's tests (or the test `test_id`): activity
+/// attachments, nested activities included, and failure attachments.
+fn export_attachments_legacy(
+    bundle_path: &str,
+    output_dir: &Path,
+    test_id: Option<&str>,
+    legacy_flag: bool,
+) -> Result<Vec<TestAttachment>, String> {
+    let bundle = ResultBundle::open(bundle_path);
+    let bundle = LegacyBundle {
+        bundle: &bundle,
+        legacy_flag,
+    };
+    let root = bundle.get(None)?;
+
+    let mut tests = Vec::new();
+    for action in values(&root, "actions") {
+        let tests_ref = action
+            .get("actionResult")
+            .and_then(|r| r.get("testsRef"))
+            .and_then(|r| value_str(r, "id"));
+        if let Some(id) = tests_ref {
+            let summaries = bundle.get(Some(id))?;
+            for summary in values(&summaries, "summaries") {
+                for testable in values(summary, "testableSummaries") {
+                    for group in values(testable, "tests") {
+                        collect_test_metadata(group, &mut tests);
+                    }
                 }
             }
         }
     }
+
+    let mut attachments = Vec::new();
+    for (identifier, summary_id) in tests {
+        if test_id.is_some_and(|id| id != identifier) {
+            continue;
+        }
+        let summary = bundle.get(Some(&summary_id))?;
+        let mut found = Vec::new();
+        collect_activity_attachments(values(&summary, "activitySummaries"), &mut found);
+        for failure in values(&summary, "failureSummaries") {
+            for attachment in values(failure, "attachments") {
+                found.push((attachment.clone(), true));
+            }
+        }
+
+        for (attachment, associated_with_failure) in found {
+            let Some(payload_id) = attachment
+                .get("payloadRef")
+                .and_then(|r| value_str(r, "id"))
+            else {
+                continue;
+            };
+            let file_name = value_str(&attachment, "filename").unwrap_or(payload_id);
+            let path = output_dir.join(file_name);
+            let path_str = path.to_string_lossy().to_string();
+            let mut args = vec!["export"];
+            if legacy_flag {
+                args.push("--legacy");
+            }
+            args.extend([
+                "--type",
+                "file",
+                "--path",
+                bundle_path,
+                "--id",
+                payload_id,
+                "--output-path",
+                &path_str,
+            ]);
+            xcresulttool(&args)?;
+
+            attachments.push(TestAttachment {
+                test_identifier: identifier.clone(),
+                name: value_str(&attachment, "name")
+                    .unwrap_or(file_name)
+                    .to_string(),
+                uniform_type_identifier: value_str(&attachment, "uniformTypeIdentifier")
+                    .unwrap_or_else(|| uti_for_file(file_name))
+                    .to_string(),
+                timestamp: value_str(&attachment, "timestamp").map(|s| s.to_string()),
+                path: path_str,
+                associated_with_failure,
+            });
+        }
+    }
+    Ok(attachments)
 }
 
-fn extract_from_testable(testable: &serde_json::Value, cases: &mut Vec<TestCase>, run_id: &str) {
-    let suite_name = testable
-        .get("targetName")
-        .and_then(|n| n.get("_value"))
-        .and_then(|v| v.as_str())
-        .unwrap_or("Unknown")
-        .to_string();
+/// (identifier, summaryRef id) of every test below `item`.
+fn collect_test_metadata(item: &serde_json::Value, tests: &mut Vec<(String, String)>) {
+    if item.get("subtests").is_some() {
+        for subtest in values(item, "subtests") {
+            collect_test_metadata(subtest, tests);
+        }
+        return;
+    }
+    let identifier = value_str(item, "identifier");
+    let summary_id = item.get("summaryRef").and_then(|r| value_str(r, "id"));
+    if let (Some(identifier), Some(summary_id)) = (identifier, summary_id) {
+        tests.push((identifier.to_string(), summary_id.to_string()));
+    }
+}
 
-    if let Some(tests) = testable.get("tests").and_then(|t| t.get("_values")).and_then(|v| v.as_array()) {
-        for test_group in tests {
-            extract_test_items(test_group, cases, run_id, &suite_name);
+fn collect_activity_attachments(
+    activities: &[serde_json::Value],
+    found: &mut Vec<(serde_json::Value, bool)>,
+) {
+    for activity in activities {
+        for attachment in values(activity, "attachments") {
+            found.push((attachment.clone(), false));
         }
+        collect_activity_attachments(values(activity, "subactivities"), found);
     }
 }
 
+/// Metrics live in each test's `ActionTestSummary`; only tests whose metadata counts
+/// performance metrics are fetched.
+fn parse_legacy_metrics(
+    bundle_path: &str,
+    legacy_flag: bool,
+) -> Result<Vec<PerformanceMetric>, String> {
+    let bundle = ResultBundle::open(bundle_path);
+    let bundle = LegacyBundle {
+        bundle: &bundle,
+        legacy_flag,
+    };
+    let root = bundle.get(None)?;
+
+    let mut tests = Vec::new();
+    for action in values(&root, "actions") {
+        let tests_ref = action
+            .get("actionResult")
+            .and_then(|r| r.get("testsRef"))
+            .and_then(|r| value_str(r, "id"));
+        if let Some(id) = tests_ref {
+            let summaries = bundle.get(Some(id))?;
+            for summary in values(&summaries, "summaries") {
+                for testable in values(summary, "testableSummaries") {
+                    for group in values(testable, "tests") {
+                        collect_performance_tests(group, "Unknown", &mut tests);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut metrics = Vec::new();
+    for (suite, name, summary_id) in tests {
+        let summary = bundle.get(Some(&summary_id))?;
+        for metric in values(&summary, "performanceMetrics") {
+            let measurements = values(metric, "measurements")
+                .iter()
+                .filter_map(|m| m.get("_value")?.as_str()?.parse().ok())
+                .collect();
+            metrics.extend(PerformanceMetric::from_values(
+                &suite,
+                &name,
+                value_str(metric, "displayName").unwrap_or("Unknown"),
+                value_str(metric, "unitOfMeasurement").unwrap_or(""),
+                value_str(metric, "identifier").map(|s| s.to_string()),
+                measurements,
+            ));
+        }
+    }
+    Ok(metrics)
+}
+
+/// (suite, test name, summaryRef id) of every test below `item` that recorded metrics.
+fn collect_performance_tests(
+    item: &serde_json::Value,
+    suite_name: &str,
+    tests: &mut Vec<(String, String, String)>,
+) {
+    let Some(name) = value_str(item, "name") else {
+        return;
+    };
+    if item.get("subtests").is_some() {
+        for subtest in values(item, "subtests") {
+            collect_performance_tests(subtest, name, tests);
+        }
+        return;
+    }
+    let metric_count = value_str(item, "performanceMetricsCount")
+        .and_then(|c| c.parse::<i64>().ok())
+        .unwrap_or(0);
+    let summary_id = item.get("summaryRef").and_then(|r| value_str(r, "id"));
+    if let Some(summary_id) = summary_id.filter(|_| metric_count > 0) {
+        tests.push((
+            suite_name.to_string(),
+            test_name(name).to_string(),
+            summary_id.to_string(),
+        ));
+    }
+}
+
+fn parse_legacy(bundle: &ResultBundle, legacy_flag: bool) -> Result<Vec<TestCase>, String> {
+    let bundle = LegacyBundle {
+        bundle,
+        legacy_flag,
+    };
+    let root = bundle.get(None)?;
+
+    let mut cases = Vec::new();
+    for action in values(&root, "actions") {
+        let tests_ref = action
+            .get("actionResult")
+            .and_then(|r| r.get("testsRef"))
+            .and_then(|r| value_str(r, "id"));
+        if let Some(id) = tests_ref {
+            let summaries = bundle.get(Some(id))?;
+            extract_test_cases(&bundle, &summaries, &mut cases);
+        }
+    }
+    Ok(cases)
+}
+
+/// Walk `ActionTestPlanRunSummaries` → summaries → testableSummaries → tests.
+fn extract_test_cases(
+    bundle: &LegacyBundle,
+    summaries: &serde_json::Value,
+    cases: &mut Vec<TestCase>,
+) {
+    for summary in values(summaries, "summaries") {
+        for testable in values(summary, "testableSummaries") {
+            let target_name =
+                value_str(testable, "targetName").or_else(|| value_str(testable, "name"));
+            for test_group in values(testable, "tests") {
+                let suite_name = target_name.unwrap_or("Unknown");
+                extract_test_items(bundle, test_group, cases, target_name, suite_name);
+            }
+        }
+    }
+}
+
+/// Groups (`ActionTestSummaryGroup`) carry `subtests`; leaves (`ActionTestMetadata`) are
+/// reported under the name of the group that contains them, i.e. their test class.
 fn extract_test_items(
+    bundle: &LegacyBundle,
     item: &serde_json::Value,
     cases: &mut Vec<TestCase>,
-    run_id: &str,
+    target_name: Option<&str>,
     suite_name: &str,
 ) {
-    // Check if this is a leaf test case
-    if let Some(name) = item.get("name").and_then(|n| n.get("_value")).and_then(|v| v.as_str()) {
-        let status_str = item
-            .get("testStatus")
-            .and_then(|s| s.get("_value"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-
-        let duration = item
-            .get("duration")
-            .and_then(|d| d.get("_value"))
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse::<f64>().ok())
-            .map(|d| (d * 1000.0) as i64);
-
-        let status = match status_str {
-            "Success" => TestStatus::Passed,
-            "Failure" => TestStatus::Failed,
-            _ => TestStatus::Skipped,
-        };
+    let Some(name) = value_str(item, "name") else {
+        return;
+    };
 
-        let failure_message = if status == TestStatus::Failed {
-            item.get("failureSummaries")
-                .and_then(|f| f.get("_values"))
-                .and_then(|v| v.as_array())
-                .and_then(|arr| arr.first())
-                .and_then(|f| f.get("message"))
-                .and_then(|m| m.get("_value"))
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string())
-        } else {
-            None
-        };
+    if item.get("subtests").is_some() {
+        for subtest in values(item, "subtests") {
+            extract_test_items(bundle, subtest, cases, target_name, name);
+        }
+        return;
+    }
 
-        // Only add leaf tests (not groups)
-        if item.get("subtests").is_none() {
-            cases.push(TestCase {
-                id: None,
-                run_id: run_id.to_string(),
-                suite_name: suite_name.to_string(),
-                test_name: name.to_string(),
-                status,
-                duration_ms: duration,
-                failure_message,
-                file_path: None,
-                line_number: None,
-            });
+    let test_status = value_str(item, "testStatus").unwrap_or("unknown");
+    let status = match test_status {
+        "Success" => TestStatus::Passed,
+        "Failure" => TestStatus::Failed,
+        "Skipped" => TestStatus::Skipped,
+        "Expected Failure" => TestStatus::ExpectedFailure,
+        _ => TestStatus::Failed,
+    };
+
+    let duration = item
+        .get("duration")
+        .and_then(|d| d.get("_value"))
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<f64>().ok())
+        .map(|d| (d * 1000.0) as i64);
+
+    // Only the test summary (`ActionTestSummary`) has failures, skip notices and expected
+    // failures; it is fetched for tests that did not simply pass.
+    let summary = if status != TestStatus::Passed {
+        item.get("summaryRef")
+            .and_then(|r| value_str(r, "id"))
+            .and_then(|id| bundle.get(Some(id)).ok())
+    } else {
+        None
+    };
+    let failure = summary
+        .as_ref()
+        .filter(|_| status == TestStatus::Failed)
+        .and_then(|summary| values(summary, "failureSummaries").first().cloned());
+    let failure_message = failure
+        .as_ref()
+        .and_then(|f| value_str(f, "message"))
+        .map(|s| s.to_string());
+    let (file_path, line_number) = failure
+        .as_ref()
+        .map(failure_location)
+        .unwrap_or((None, None));
+
+    let (status, status_reason) = match status {
+        TestStatus::Skipped => (
+            status,
+            summary
+                .as_ref()
+                .and_then(|s| s.get("skipNoticeSummary"))
+                .and_then(|notice| value_str(notice, "message"))
+                .map(|s| s.to_string()),
+        ),
+        TestStatus::ExpectedFailure => {
+            let expected = summary
+                .as_ref()
+                .and_then(|s| values(s, "expectedFailures").first());
+            let reason = expected
+                .and_then(|e| value_str(e, "failureReason"))
+                .or_else(|| {
+                    expected
+                        .and_then(|e| e.get("failureSummary"))
+                        .and_then(|f| value_str(f, "message"))
+                });
+            (status, reason.map(|s| s.to_string()))
         }
+        TestStatus::Failed => match failure_message.as_deref().and_then(timeout_reason) {
+            Some(reason) => (TestStatus::TimedOut, Some(reason)),
+            None if test_status != "Failure" => (
+                status,
+                Some(format!("Result reported as \"{}\"", test_status)),
+            ),
+            None => (status, None),
+        },
+        _ => (status, None),
+    };
+
+    cases.push(TestCase {
+        id: None,
+        run_id: String::new(),
+        suite_name: suite_name.to_string(),
+        test_name: test_name(name).to_string(),
+        status,
+        duration_ms: duration,
+        failure_message,
+        file_path,
+        line_number,
+        crash_report_path: None,
+        status_reason,
+        target_key: None,
+        test_id: None,
+        target_name: target_name.map(|t| t.to_string()),
+        quarantined: false,
+    });
+}
+
+/// Source location of an `ActionTestFailureSummary`: the workspace document URL when present
+/// (full path), else the source code context, else the bare file name and line.
+fn failure_location(failure: &serde_json::Value) -> (Option<String>, Option<i32>) {
+    let from_url = failure
+        .get("documentLocationInCreatingWorkspace")
+        .and_then(|loc| value_str(loc, "url"))
+        .and_then(|url| DOCUMENT_LOCATION_RE.captures(url))
+        .and_then(|caps| {
+            let path = caps.get(1)?.as_str().replace("%20", " ");
+            // StartingLineNumber is zero-based
+            let line = caps.get(2)?.as_str().parse::<i32>().ok()? + 1;
+            Some((Some(path), Some(line)))
+        });
+    if let Some(location) = from_url {
+        return location;
+    }
+
+    let context = failure
+        .get("sourceCodeContext")
+        .and_then(|c| c.get("location"));
+    if let Some(path) = context.and_then(|loc| value_str(loc, "filePath")) {
+        let line = context
+            .and_then(|loc| value_str(loc, "lineNumber"))
+            .and_then(|l| l.parse().ok());
+        return (Some(path.to_string()), line);
     }
 
-    // Recurse into subtests
-    if let Some(subtests) = item.get("subtests").and_then(|s| s.get("_values")).and_then(|v| v.as_array()) {
-        for subtest in subtests {
-            extract_test_items(subtest, cases, run_id, suite_name);
+    (
+        value_str(failure, "fileName").map(|s| s.to_string()),
+        value_str(failure, "lineNumber").and_then(|l| l.parse().ok()),
+    )
+}
+
+/// `{"key": {"_value": "..."}}` → `"..."`
+fn value_str<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    value.get(key)?.get("_value")?.as_str()
+}
+
+/// `{"key": {"_values": [...]}}` → `[...]`
+fn values<'a>(value: &'a serde_json::Value, key: &str) -> &'a [serde_json::Value] {
+    value
+        .get(key)
+        .and_then(|v| v.get("_values"))
+        .and_then(|v| v.as_array())
+        .map(|v| v.as_slice())
+        .unwrap_or(&[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::history::TestInfo;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_str!(concat!("../../tests/fixtures/xcresult/", $name))
+        };
+    }
+
+    /// A bundle answering xcresulttool calls with recorded output, keyed by the object id
+    /// (legacy) or the test-results subcommand and test id.
+    fn recorded_bundle(outputs: &[(&'static str, &'static str)]) -> ResultBundle<'static> {
+        let outputs = outputs.to_vec();
+        ResultBundle {
+            run: Box::new(move |args| {
+                let key = match args.iter().position(|a| *a == "--id" || *a == "--test-id") {
+                    Some(i) => args[i + 1],
+                    None if args.contains(&"test-results") => args[2],
+                    None => "root",
+                };
+                outputs
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, json)| json.to_string())
+                    .ok_or_else(|| format!("no recorded output for {:?}", args))
+            }),
         }
     }
+
+    fn find<'a>(cases: &'a [TestCase], suite: &str, name: &str) -> &'a TestCase {
+        cases
+            .iter()
+            .find(|c| c.suite_name == suite && c.test_name == name)
+            .unwrap_or_else(|| panic!("{}.{} not parsed", suite, name))
+    }
+
+    #[test]
+    fn detects_test_results_api_from_version() {
+        let xcode16 = parse_version("xcresulttool version 23021, format version 3.53 (current)");
+        let xcode15 = parse_version("xcresulttool version 22608, format version 3.49 (current)");
+        assert_eq!(xcode16, Some(23021));
+        assert_eq!(xcode15, Some(22608));
+        assert!(has_test_results_api(xcode16));
+        assert!(!has_test_results_api(xcode15));
+        assert!(!has_test_results_api(parse_version(
+            "xcrun: error: unable to find utility"
+        )));
+    }
+
+    #[test]
+    fn legacy_follows_tests_and_summary_refs() {
+        let bundle = recorded_bundle(&[
+            ("root", fixture!("legacy_root.json")),
+            ("0~tests-ref", fixture!("legacy_tests.json")),
+            (
+                "0~invalid-password",
+                fixture!("legacy_invalid_password.json"),
+            ),
+            ("0~biometrics", fixture!("legacy_biometrics.json")),
+            (
+                "0~email-validation",
+                fixture!("legacy_email_validation.json"),
+            ),
+        ]);
+        let cases = parse_legacy(&bundle, true).unwrap();
+        assert_eq!(cases.len(), 4);
+        assert!(cases
+            .iter()
+            .all(|c| c.target_name.as_deref() == Some("AppTests")));
+
+        let passed = find(&cases, "LoginTests", "testValidPassword");
+        assert_eq!(passed.status, TestStatus::Passed);
+        assert_eq!(passed.duration_ms, Some(12));
+
+        let failed = find(&cases, "LoginTests", "testInvalidPassword");
+        assert_eq!(failed.status, TestStatus::Failed);
+        assert!(failed
+            .failure_message
+            .as_deref()
+            .is_some_and(|m| m.starts_with("XCTAssertEqual failed")));
+        assert_eq!(
+            failed.file_path.as_deref(),
+            Some("/Users/dev/My App/AppTests/LoginTests.swift")
+        );
+        assert_eq!(failed.line_number, Some(42));
+
+        let skipped = find(&cases, "LoginTests", "testBiometrics");
+        assert_eq!(skipped.status, TestStatus::Skipped);
+        assert_eq!(
+            skipped.status_reason.as_deref(),
+            Some("Test skipped - Face ID is not available on this device")
+        );
+
+        let failed = find(&cases, "SignupTests", "testEmailValidation");
+        assert_eq!(
+            failed.file_path.as_deref(),
+            Some("/Users/dev/My App/AppTests/SignupTests.swift")
+        );
+        assert_eq!(failed.line_number, Some(18));
+    }
+
+    #[test]
+    fn legacy_without_tests_ref_has_no_cases() {
+        let bundle = recorded_bundle(&[(
+            "root",
+            r#"{"actions": {"_values": [{"actionResult": {}}]}}"#,
+        )]);
+        assert!(parse_legacy(&bundle, false).unwrap().is_empty());
+    }
+
+    #[test]
+    fn failure_location_sources() {
+        let url = serde_json::json!({
+            "documentLocationInCreatingWorkspace": {"url": {"_value":
+                "file:///Users/dev/My%20App/FooTests.swift#CharacterRangeLen=0&EndingLineNumber=9&StartingLineNumber=9"}},
+            "fileName": {"_value": "FooTests.swift"},
+            "lineNumber": {"_value": "99"}
+        });
+        assert_eq!(
+            failure_location(&url),
+            (
+                Some("/Users/dev/My App/FooTests.swift".to_string()),
+                Some(10)
+            )
+        );
+
+        let context = serde_json::json!({
+            "sourceCodeContext": {"location": {
+                "filePath": {"_value": "/src/FooTests.swift"},
+                "lineNumber": {"_value": "7"}
+            }},
+            "fileName": {"_value": "FooTests.swift"},
+            "lineNumber": {"_value": "99"}
+        });
+        assert_eq!(
+            failure_location(&context),
+            (Some("/src/FooTests.swift".to_string()), Some(7))
+        );
+
+        let bare = serde_json::json!({
+            "fileName": {"_value": "FooTests.swift"},
+            "lineNumber": {"_value": "3"}
+        });
+        assert_eq!(
+            failure_location(&bare),
+            (Some("FooTests.swift".to_string()), Some(3))
+        );
+        assert_eq!(failure_location(&serde_json::json!({})), (None, None));
+    }
+
+    #[test]
+    fn test_results_reads_tree_summary_and_details() {
+        let bundle = recorded_bundle(&[
+            ("tests", fixture!("test_results_tests.json")),
+            ("summary", fixture!("test_results_summary.json")),
+            (
+                "SignupTests/testServerError()",
+                fixture!("test_results_details_server_error.json"),
+            ),
+        ]);
+        let cases = parse_test_results(&bundle).unwrap();
+        assert_eq!(cases.len(), 7);
+
+        let failed = find(&cases, "LoginTests", "testInvalidPassword");
+        assert_eq!(failed.target_name.as_deref(), Some("AppTests"));
+        assert_eq!(failed.file_path.as_deref(), Some("LoginTests.swift"));
+        assert_eq!(failed.line_number, Some(42));
+        assert_eq!(failed.duration_ms, Some(500));
+
+        // Same test name in two suites: each gets its own summary failure
+        let login = find(&cases, "LoginTests", "testSessionRefresh");
+        assert_eq!(
+            login.failure_message.as_deref(),
+            Some("XCTAssertNotNil failed - refreshed token")
+        );
+        assert_eq!(login.duration_ms, Some(62_000));
+        let signup = find(&cases, "SignupTests", "testSessionRefresh");
+        assert!(signup
+            .failure_message
+            .as_deref()
+            .is_some_and(|m| m.starts_with("Asynchronous wait failed")));
+
+        let details = find(&cases, "SignupTests", "testServerError");
+        assert_eq!(
+            details.failure_message.as_deref(),
+            Some("failed - Server returned 500")
+        );
+        assert_eq!(details.file_path.as_deref(), Some("SignupTests.swift"));
+        assert_eq!(details.line_number, Some(33));
+
+        let skipped = find(&cases, "SignupTests", "testInviteLink");
+        assert_eq!(skipped.status, TestStatus::Skipped);
+        assert_eq!(
+            skipped.status_reason.as_deref(),
+            Some("Test skipped - Requires network access")
+        );
+
+        // Top-level Swift Testing functions are in their bundle's target, outside any suite
+        let top_level = find(&cases, "", "parsesEmptyConfig");
+        assert_eq!(top_level.status, TestStatus::Passed);
+        assert_eq!(top_level.target_name.as_deref(), Some("AppTests"));
+    }
+
+    /// Skip arguments for the bundle's tests, as their `tests` rows would be once the
+    /// output named the Swift Testing function `parsesEmptyConfig()`.
+    #[test]
+    fn skip_testing_args_name_bundle_tests() {
+        let bundle = recorded_bundle(&[
+            ("tests", fixture!("test_results_tests.json")),
+            ("summary", fixture!("test_results_summary.json")),
+        ]);
+        let tests: Vec<TestInfo> = parse_test_results(&bundle)
+            .unwrap()
+            .into_iter()
+            .filter(|c| ["testValidPassword", "parsesEmptyConfig"].contains(&c.test_name.as_str()))
+            .map(|c| TestInfo {
+                id: 1,
+                project_id: None,
+                target_name: c.target_name,
+                swift_testing: c.test_name == "parsesEmptyConfig",
+                suite_name: c.suite_name,
+                test_name: c.test_name,
+            })
+            .collect();
+        assert_eq!(
+            crate::execution::xcodebuild::skip_testing_args(&tests, None),
+            [
+                "-skip-testing:AppTests/LoginTests/testValidPassword",
+                "-skip-testing:AppTests/parsesEmptyConfig()",
+            ]
+        );
+
+        let in_suite = TestInfo {
+            suite_name: "ConfigTests".to_string(),
+            ..tests[1].clone()
+        };
+        assert_eq!(
+            crate::execution::xcodebuild::skip_testing_args(&[in_suite], Some("AppTests")),
+            ["-skip-testing:AppTests/ConfigTests/parsesEmptyConfig()"]
+        );
+        assert!(
+            crate::execution::xcodebuild::skip_testing_args(&tests, Some("UITests")).is_empty()
+        );
+    }
+
+    #[test]
+    fn summary_failure_without_identifier_matches_by_suite() {
+        let failure: SummaryFailure = serde_json::from_str(
+            r#"{"testName": "testFoo()", "testIdentifierString": "Outer/Inner/testFoo()",
+                "targetName": "AppTests", "failureText": "failed"}"#,
+        )
+        .unwrap();
+        let case = |suite: &str, target: &str| TestCase {
+            id: None,
+            run_id: String::new(),
+            suite_name: suite.to_string(),
+            test_name: "testFoo".to_string(),
+            status: TestStatus::Failed,
+            duration_ms: None,
+            failure_message: None,
+            file_path: None,
+            line_number: None,
+            crash_report_path: None,
+            status_reason: None,
+            target_key: None,
+            test_id: None,
+            target_name: Some(target.to_string()),
+            quarantined: false,
+        };
+        assert!(failure.is_for(&case("Inner", "AppTests"), None));
+        assert!(!failure.is_for(&case("Outer", "AppTests"), None));
+        assert!(!failure.is_for(&case("Inner", "OtherTests"), None));
+        assert!(failure.is_for(&case("Inner", "AppTests"), Some("Outer/Inner/testFoo()")));
+        assert!(!failure.is_for(&case("Inner", "AppTests"), Some("Inner/testFoo()")));
+    }
 }
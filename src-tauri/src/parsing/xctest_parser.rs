@@ -0,0 +1,188 @@
+use crate::parsing::failure_collector::{FailureCollector, TestFailure, TestNote};
+use crate::parsing::output_parser::{ParsedEvent, TestOutputParser};
+use crate::parsing::performance::parse_measurement;
+use regex::Regex;
+use std::sync::LazyLock;
+
+// xcodebuild and `swift test` on macOS name tests `-[Suite test]`.
+
+// Pattern: Test Case '-[SuiteName testMethod]' started.
+static XCODEBUILD_STARTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Test Case '-\[(\S+)\s+(\S+)\]' started").unwrap());
+
+// Pattern: Test Case '-[SuiteName testMethod]' passed (0.001 seconds).
+static XCODEBUILD_COMPLETED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"Test Case '-\[(\S+)\s+(\S+)\]' (passed|failed|skipped) \((\d+\.\d+) seconds\)\.")
+        .unwrap()
+});
+
+// Pattern: /path/File.swift:42: Test Case '-[SuiteName testMethod]' measured [Time, seconds] average: ...
+static XCODEBUILD_MEASURED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Test Case '-\[(\S+)\s+(\S+)\]' (measured .+)$").unwrap());
+
+// Pattern: /path/File.swift:42: error: -[SuiteName testMethod] : XCTAssertEqual failed: ...
+static XCODEBUILD_FAILURE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?):(\d+): error: -\[(\S+) (\S+)\] : (.+)$").unwrap());
+
+// Pattern: /path/File.swift:42: -[SuiteName testMethod] : Test skipped - reason
+//          /path/File.swift:42: error: -[SuiteName testMethod] : Expected failure: reason
+static XCODEBUILD_NOTE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:.+?:\d+: )?(?:error: )?-\[(\S+) (\S+)\] : (Test skipped|Expected failure)(?:\s*[-:]\s*(.+))?$",
+    )
+    .unwrap()
+});
+
+// swift-corelibs-xctest (`swift test` on Linux) names tests `Suite.test`.
+
+// Pattern: Test Case 'SuiteName.testMethod' started at 2024-01-01 10:00:00.000
+static LINUX_STARTED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Test Case '([^.\[]+)\.(\S+)' started").unwrap());
+
+// Pattern: Test Case 'SuiteName.testMethod' passed (0.001 seconds)
+static LINUX_COMPLETED_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"Test Case '([^.]+)\.(\S+)' (passed|failed|skipped) \((\d+\.\d+) seconds\)")
+        .unwrap()
+});
+
+// Pattern: /path/File.swift:42: Test Case 'SuiteName.testMethod' measured [Time, seconds] average: ...
+static LINUX_MEASURED_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Test Case '([^.\[]+)\.(\S+)' (measured .+)$").unwrap());
+
+// Pattern: /path/File.swift:42: error: SuiteName.testMethod : XCTAssertEqual failed: ...
+static LINUX_FAILURE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?):(\d+): error: ([^.\s]+)\.(\S+) : (.+)$").unwrap());
+
+// Pattern: /path/File.swift:42: SuiteName.testMethod : Test skipped - reason
+static LINUX_NOTE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:.+?:\d+: )?([^.\s]+)\.(\S+) : (Test skipped)(?:\s*[-:]\s*(.+))?$").unwrap()
+});
+
+/// The lines of one XCTest output format. Every pattern captures the suite and test name
+/// first.
+struct XCTestFormat {
+    /// Then nothing.
+    started: &'static LazyLock<Regex>,
+    /// Then the status and the duration in seconds.
+    completed: &'static LazyLock<Regex>,
+    /// Then the measurement.
+    measured: &'static LazyLock<Regex>,
+    /// Then `Test skipped` or `Expected failure`, and the reason.
+    note: &'static LazyLock<Regex>,
+    parse_failure: fn(&str) -> Option<TestFailure>,
+}
+
+static XCODEBUILD: XCTestFormat = XCTestFormat {
+    started: &XCODEBUILD_STARTED_RE,
+    completed: &XCODEBUILD_COMPLETED_RE,
+    measured: &XCODEBUILD_MEASURED_RE,
+    note: &XCODEBUILD_NOTE_RE,
+    parse_failure: |line| parse_failure_line(&XCODEBUILD_FAILURE_RE, line),
+};
+
+static LINUX: XCTestFormat = XCTestFormat {
+    started: &LINUX_STARTED_RE,
+    completed: &LINUX_COMPLETED_RE,
+    measured: &LINUX_MEASURED_RE,
+    note: &LINUX_NOTE_RE,
+    parse_failure: |line| parse_failure_line(&LINUX_FAILURE_RE, line),
+};
+
+/// Parse an XCTest failure line, e.g. `/path/File.swift:42: error: -[Suite test] : message`,
+/// with the format's `failure_re` capturing file, line, suite, test and message.
+fn parse_failure_line(failure_re: &Regex, line: &str) -> Option<TestFailure> {
+    let caps = failure_re.captures(line.trim())?;
+    // XCTest reports failures it cannot place, e.g. an exceeded time allowance, at `<unknown>:0`
+    let (file_path, line_number) = match (caps.get(1)?.as_str(), caps.get(2)?.as_str().parse()) {
+        ("<unknown>", _) | (_, Ok(0)) => (None, None),
+        (file, line) => (Some(file.to_string()), line.ok()),
+    };
+    Some(TestFailure {
+        file_path,
+        line_number,
+        suite: caps.get(3)?.as_str().to_string(),
+        name: caps.get(4)?.as_str().to_string(),
+        message: caps.get(5)?.as_str().to_string(),
+    })
+}
+
+/// XCTest output: test starts and completions, measurements, skip and expected-failure
+/// notes, and multi-line failure messages.
+pub struct XCTestParser {
+    format: &'static XCTestFormat,
+    failures: FailureCollector,
+}
+
+impl XCTestParser {
+    /// XCTest output as printed by xcodebuild and by `swift test` on macOS (`-[Suite test]`).
+    pub fn xcodebuild() -> Self {
+        Self::with_format(&XCODEBUILD)
+    }
+
+    /// XCTest output from swift-corelibs-xctest (`swift test` on Linux), which names tests
+    /// `Suite.test`.
+    pub fn linux() -> Self {
+        Self::with_format(&LINUX)
+    }
+
+    fn with_format(format: &'static XCTestFormat) -> Self {
+        Self {
+            format,
+            failures: FailureCollector::new(format.parse_failure),
+        }
+    }
+}
+
+impl TestOutputParser for XCTestParser {
+    fn feed(&mut self, line: &str) -> Vec<ParsedEvent> {
+        if let Some(caps) = self.format.started.captures(line) {
+            let (Some(suite), Some(name)) = (caps.get(1), caps.get(2)) else {
+                return Vec::new();
+            };
+            self.failures.started(suite.as_str(), name.as_str());
+            return vec![ParsedEvent::TestStarted {
+                suite: suite.as_str().to_string(),
+                name: name.as_str().to_string(),
+            }];
+        }
+
+        if let Some(caps) = self.format.completed.captures(line) {
+            let (Some(suite), Some(name), Some(status), Some(seconds)) =
+                (caps.get(1), caps.get(2), caps.get(3), caps.get(4))
+            else {
+                return Vec::new();
+            };
+            let seconds: f64 = seconds.as_str().parse().unwrap_or(0.0);
+            return vec![self.failures.complete(
+                suite.as_str(),
+                name.as_str(),
+                status.as_str(),
+                (seconds * 1000.0) as i64,
+            )];
+        }
+
+        if let Some(caps) = self.format.measured.captures(line) {
+            if let (Some(suite), Some(name), Some(text)) = (caps.get(1), caps.get(2), caps.get(3)) {
+                return parse_measurement(suite.as_str(), name.as_str(), text.as_str())
+                    .map(ParsedEvent::PerformanceMetric)
+                    .into_iter()
+                    .collect();
+            }
+        }
+
+        if let Some(caps) = self.format.note.captures(line.trim()) {
+            if let (Some(suite), Some(name), Some(kind)) = (caps.get(1), caps.get(2), caps.get(3)) {
+                let reason = caps.get(4).map_or("", |m| m.as_str()).trim().to_string();
+                let note = match kind.as_str() {
+                    "Test skipped" => TestNote::Skipped(reason),
+                    _ => TestNote::ExpectedFailure(reason),
+                };
+                self.failures.note(suite.as_str(), name.as_str(), note);
+                return Vec::new();
+            }
+        }
+
+        self.failures.feed(line);
+        Vec::new()
+    }
+}
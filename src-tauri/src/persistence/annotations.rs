@@ -0,0 +1,484 @@
+use crate::analysis::duration;
+use crate::models::history::{AnnotationKind, DurationRegression, RunAnnotation, TestInfo};
+use crate::models::run::TargetResult;
+use crate::persistence::history::TestRow;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use std::collections::{BTreeMap, HashMap};
+
+/// Earlier finished runs of the project that a run's durations are compared against.
+const DURATION_HISTORY_RUNS: i64 = 20;
+
+#[derive(sqlx::FromRow)]
+struct AnnotationRow {
+    id: i64,
+    run_id: String,
+    run_started_at: String,
+    kind: String,
+    test_id: Option<i64>,
+    project_id: Option<String>,
+    target_name: Option<String>,
+    suite_name: Option<String>,
+    test_name: Option<String>,
+    swift_testing: Option<bool>,
+    target_key: Option<String>,
+    message: String,
+    data: Option<String>,
+    created_at: String,
+}
+
+impl AnnotationRow {
+    /// None for annotation kinds this version does not know.
+    fn into_annotation(self) -> Option<RunAnnotation> {
+        let kind = AnnotationKind::from_str(&self.kind)?;
+        let test = match (self.test_id, self.suite_name, self.test_name) {
+            (Some(id), Some(suite_name), Some(test_name)) => Some(TestInfo::from(TestRow {
+                id,
+                project_id: self.project_id.unwrap_or_default(),
+                target_name: self.target_name.unwrap_or_default(),
+                suite_name,
+                test_name,
+                swift_testing: self.swift_testing.unwrap_or_default(),
+            })),
+            _ => None,
+        };
+        let data = self.data.as_deref().unwrap_or_default();
+        Some(RunAnnotation {
+            id: self.id,
+            run_id: self.run_id,
+            run_started_at: self.run_started_at,
+            test,
+            target_key: self.target_key,
+            message: self.message,
+            duration: match kind {
+                AnnotationKind::DurationRegression => serde_json::from_str(data).ok(),
+            },
+            kind,
+            created_at: self.created_at,
+        })
+    }
+}
+
+const SELECT_ANNOTATIONS: &str = "SELECT a.id, a.run_id, r.started_at AS run_started_at, a.kind, a.test_id,
+        t.project_id, t.target_name, t.suite_name, t.test_name, t.swift_testing, a.target_key, a.message, a.data, a.created_at
+    FROM run_annotations a JOIN test_runs r ON r.id = a.run_id LEFT JOIN tests t ON t.id = a.test_id";
+
+async fn insert_regression(
+    conn: &mut SqliteConnection,
+    run_id: &str,
+    test_id: Option<i64>,
+    target_key: Option<&str>,
+    message: &str,
+    regression: &DurationRegression,
+    created_at: &str,
+) -> Result<(), String> {
+    let data = serde_json::to_string(regression).map_err(|e| e.to_string())?;
+    sqlx::query(
+        "INSERT INTO run_annotations (run_id, kind, test_id, target_key, message, data, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )
+    .bind(run_id)
+    .bind(AnnotationKind::DurationRegression.to_string())
+    .bind(test_id)
+    .bind(target_key)
+    .bind(message)
+    .bind(data)
+    .bind(created_at)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("Failed to record annotation: {}", e))?;
+    Ok(())
+}
+
+/// Annotate run `run_id` with the passed tests and successful targets that took much longer
+/// than in the project's earlier runs, replacing earlier duration annotations of the run.
+/// Returns how many regressions were found.
+pub async fn annotate_duration_regressions(
+    pool: &SqlitePool,
+    run_id: &str,
+) -> Result<usize, String> {
+    let Some((project_id, started_at)): Option<(String, String)> =
+        sqlx::query_as("SELECT COALESCE(project_id, ''), started_at FROM test_runs WHERE id = $1")
+            .bind(run_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?
+    else {
+        return Err(format!("Run {} not found", run_id));
+    };
+    let earlier_runs =
+        "SELECT id FROM test_runs WHERE COALESCE(project_id, '') = $1 AND started_at < $2
+        AND status IN ('passed', 'failed') ORDER BY started_at DESC LIMIT $3";
+
+    // Tests: the run's passed durations against earlier passed durations
+    let current: Vec<(i64, String, i64)> = sqlx::query_as(
+        "SELECT tc.test_id, t.test_name, tc.duration_ms FROM test_cases tc JOIN tests t ON t.id = tc.test_id
+         WHERE tc.run_id = $1 AND tc.status = 'passed' AND tc.duration_ms IS NOT NULL ORDER BY tc.id",
+    )
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    // One duration per earlier run: SQLite takes the bare columns from the MAX(id) row
+    let earlier: Vec<(i64, i64, i64)> = sqlx::query_as(&format!(
+        "SELECT test_id, duration_ms, MAX(id) FROM test_cases
+         WHERE run_id IN ({earlier_runs}) AND status = 'passed' AND duration_ms IS NOT NULL AND test_id IS NOT NULL
+         GROUP BY run_id, test_id"
+    ))
+    .bind(&project_id)
+    .bind(&started_at)
+    .bind(DURATION_HISTORY_RUNS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut test_history: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (test_id, duration_ms, _) in earlier {
+        test_history.entry(test_id).or_default().push(duration_ms);
+    }
+
+    // Targets: successful target durations stored with each run
+    let target_json: Vec<(Option<String>,)> = sqlx::query_as(&format!(
+        "SELECT target_results FROM test_runs WHERE id IN ({earlier_runs})"
+    ))
+    .bind(&project_id)
+    .bind(&started_at)
+    .bind(DURATION_HISTORY_RUNS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let parse = |json: Option<String>| -> Vec<TargetResult> {
+        json.and_then(|j| serde_json::from_str(&j).ok())
+            .unwrap_or_default()
+    };
+    let mut target_history: HashMap<String, Vec<i64>> = HashMap::new();
+    for (json,) in target_json {
+        for result in parse(json).into_iter().filter(|r| r.success) {
+            if let Some(duration_ms) = result.duration_ms {
+                target_history
+                    .entry(result.key)
+                    .or_default()
+                    .push(duration_ms);
+            }
+        }
+    }
+    let (run_targets,): (Option<String>,) =
+        sqlx::query_as("SELECT target_results FROM test_runs WHERE id = $1")
+            .bind(run_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let created_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let mut found = 0;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM run_annotations WHERE run_id = $1 AND kind = $2")
+        .bind(run_id)
+        .bind(AnnotationKind::DurationRegression.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // A test recorded twice in the run (a retry, or two targets) is judged by its last result
+    let current: BTreeMap<i64, (String, i64)> = current
+        .into_iter()
+        .map(|(test_id, name, duration_ms)| (test_id, (name, duration_ms)))
+        .collect();
+    for (test_id, (test_name, duration_ms)) in &current {
+        let history = test_history.get(test_id).map(Vec::as_slice).unwrap_or(&[]);
+        if let Some(regression) = duration::regression(history, *duration_ms) {
+            let message = format!(
+                "{} took {} ms, {:.0}% above its median of {} ms over {} runs",
+                test_name,
+                regression.duration_ms,
+                regression.change_percent,
+                regression.median_ms,
+                regression.history_runs
+            );
+            insert_regression(
+                &mut tx,
+                run_id,
+                Some(*test_id),
+                None,
+                &message,
+                &regression,
+                &created_at,
+            )
+            .await?;
+            found += 1;
+        }
+    }
+    for result in parse(run_targets).into_iter().filter(|r| r.success) {
+        let (Some(duration_ms), Some(history)) =
+            (result.duration_ms, target_history.get(&result.key))
+        else {
+            continue;
+        };
+        if let Some(regression) = duration::regression(history, duration_ms) {
+            let message = format!(
+                "Target {} took {} ms, {:.0}% above its median of {} ms over {} runs",
+                result.key,
+                regression.duration_ms,
+                regression.change_percent,
+                regression.median_ms,
+                regression.history_runs
+            );
+            insert_regression(
+                &mut tx,
+                run_id,
+                None,
+                Some(&result.key),
+                &message,
+                &regression,
+                &created_at,
+            )
+            .await?;
+            found += 1;
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(found)
+}
+
+pub async fn get_run_annotations(
+    pool: &SqlitePool,
+    run_id: &str,
+) -> Result<Vec<RunAnnotation>, String> {
+    let rows: Vec<AnnotationRow> = sqlx::query_as(&format!(
+        "{SELECT_ANNOTATIONS} WHERE a.run_id = $1 ORDER BY a.id"
+    ))
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter_map(AnnotationRow::into_annotation)
+        .collect())
+}
+
+/// Duration regressions of recent runs, newest first, optionally only those of one project.
+pub async fn list_duration_regressions(
+    pool: &SqlitePool,
+    project_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<RunAnnotation>, String> {
+    let rows: Vec<AnnotationRow> = sqlx::query_as(&format!(
+        "{SELECT_ANNOTATIONS} WHERE a.kind = $1 AND ($2 IS NULL OR r.project_id = $2)
+         ORDER BY r.started_at DESC, a.id LIMIT $3"
+    ))
+    .bind(AnnotationKind::DurationRegression.to_string())
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .filter_map(AnnotationRow::into_annotation)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::run::{RunStatus, TestCase, TestRun, TestStatus};
+    use crate::persistence::runs;
+    use crate::persistence::testing::{self, TestDb};
+
+    fn case(run_id: &str, status: TestStatus, duration_ms: i64) -> TestCase {
+        TestCase {
+            id: None,
+            run_id: run_id.to_string(),
+            suite_name: "AppTests.LoginTests".to_string(),
+            test_name: "testValid".to_string(),
+            status,
+            duration_ms: Some(duration_ms),
+            failure_message: None,
+            file_path: None,
+            line_number: None,
+            crash_report_path: None,
+            status_reason: None,
+            target_key: Some("App".to_string()),
+            test_id: None,
+            target_name: None,
+            quarantined: false,
+        }
+    }
+
+    /// Run `index` (started on day `index` of 2026), ended with `status`, in which testValid
+    /// took `test_ms` and target App `target_ms`.
+    async fn record_run(
+        pool: &SqlitePool,
+        index: u32,
+        status: RunStatus,
+        test: (TestStatus, i64),
+        target_ms: i64,
+    ) -> String {
+        let id = format!("run-{}", index);
+        let started = chrono::NaiveDate::from_yo_opt(2026, index).unwrap();
+        let run = testing::run(&id, &format!("{}T10:00:00.000Z", started));
+        runs::insert_run(pool, &run).await.unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        runs::save_test_case(&mut conn, "", None, &case(&id, test.0, test.1))
+            .await
+            .unwrap();
+        runs::complete_run(
+            pool,
+            &TestRun {
+                status,
+                target_results: Some(vec![TargetResult {
+                    key: "App".to_string(),
+                    success: true,
+                    tests_run: Some(1),
+                    duration_ms: Some(target_ms),
+                }]),
+                ..run
+            },
+        )
+        .await
+        .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn annotates_tests_and_targets_slower_than_their_history() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        for index in 1..=5 {
+            let test = (TestStatus::Passed, 1000 + index as i64);
+            record_run(pool, index, RunStatus::Passed, test, 10_000).await;
+        }
+        // Cancelled runs and failed results are not history
+        record_run(
+            pool,
+            6,
+            RunStatus::Cancelled,
+            (TestStatus::Passed, 9000),
+            90_000,
+        )
+        .await;
+        record_run(
+            pool,
+            7,
+            RunStatus::Failed,
+            (TestStatus::Failed, 9000),
+            10_000,
+        )
+        .await;
+        let run = record_run(
+            pool,
+            8,
+            RunStatus::Passed,
+            (TestStatus::Passed, 1500),
+            15_000,
+        )
+        .await;
+
+        assert_eq!(annotate_duration_regressions(pool, &run).await.unwrap(), 2);
+        // Annotating again replaces the run's annotations
+        assert_eq!(annotate_duration_regressions(pool, &run).await.unwrap(), 2);
+
+        let annotations = get_run_annotations(pool, &run).await.unwrap();
+        assert_eq!(annotations.len(), 2);
+        let test = &annotations[0];
+        assert_eq!(test.kind, AnnotationKind::DurationRegression);
+        assert_eq!(test.test.as_ref().unwrap().test_name, "testValid");
+        assert_eq!(
+            test.message,
+            "testValid took 1500 ms, 50% above its median of 1003 ms over 5 runs"
+        );
+        let regression = test.duration.as_ref().unwrap();
+        assert_eq!((regression.median_ms, regression.history_runs), (1003, 5));
+
+        let target = &annotations[1];
+        assert!(target.test.is_none());
+        assert_eq!(target.target_key.as_deref(), Some("App"));
+        // The failed run's target duration counts; the cancelled run's does not
+        assert_eq!(
+            target.message,
+            "Target App took 15000 ms, 50% above its median of 10000 ms over 6 runs"
+        );
+
+        let listed = list_duration_regressions(pool, None, 10).await.unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(listed.iter().all(|a| a.run_id == run));
+    }
+
+    #[tokio::test]
+    async fn a_short_history_is_not_judged() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        for index in 1..=4 {
+            record_run(
+                pool,
+                index,
+                RunStatus::Passed,
+                (TestStatus::Passed, 1000),
+                10_000,
+            )
+            .await;
+        }
+        let run = record_run(
+            pool,
+            5,
+            RunStatus::Passed,
+            (TestStatus::Passed, 5000),
+            50_000,
+        )
+        .await;
+        assert_eq!(annotate_duration_regressions(pool, &run).await.unwrap(), 0);
+        assert!(annotate_duration_regressions(pool, "run-missing")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn history_covers_the_latest_runs_before_the_run() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        // Older runs were slow; the latest window is fast
+        for index in 1..=DURATION_HISTORY_RUNS as u32 {
+            record_run(
+                pool,
+                index,
+                RunStatus::Passed,
+                (TestStatus::Passed, 1500),
+                10_000,
+            )
+            .await;
+        }
+        for index in 21..=20 + DURATION_HISTORY_RUNS as u32 {
+            record_run(
+                pool,
+                index,
+                RunStatus::Passed,
+                (TestStatus::Passed, 1000),
+                10_000,
+            )
+            .await;
+        }
+        let run = record_run(
+            pool,
+            41,
+            RunStatus::Passed,
+            (TestStatus::Passed, 1400),
+            10_000,
+        )
+        .await;
+        // A later run does not count either
+        record_run(
+            pool,
+            42,
+            RunStatus::Passed,
+            (TestStatus::Passed, 1400),
+            10_000,
+        )
+        .await;
+
+        assert_eq!(annotate_duration_regressions(pool, &run).await.unwrap(), 1);
+        let annotations = get_run_annotations(pool, &run).await.unwrap();
+        let regression = annotations[0].duration.as_ref().unwrap();
+        assert_eq!(
+            (regression.median_ms, regression.history_runs),
+            (1000, DURATION_HISTORY_RUNS as i32)
+        );
+    }
+}
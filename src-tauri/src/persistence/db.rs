@@ -1,5 +1,53 @@
+use crate::persistence::tests;
+use sqlx::migrate::{Migration as SqlxMigration, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use std::borrow::Cow;
+use std::path::Path;
 use tauri_plugin_sql::{Migration, MigrationKind};
 
+/// File name of the database in the app config directory, where the SQL plugin also opens it.
+pub const DB_FILE_NAME: &str = "xcode_test_runner.db";
+
+/// Open the app database, creating it if needed, apply pending migrations and link test cases
+/// recorded before test identities existed.
+pub async fn connect(app_config_dir: &Path) -> Result<SqlitePool, String> {
+    std::fs::create_dir_all(app_config_dir)
+        .map_err(|e| format!("Failed to create app config dir: {}", e))?;
+    let options = SqliteConnectOptions::new()
+        .filename(app_config_dir.join(DB_FILE_NAME))
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    migrator()
+        .run(&pool)
+        .await
+        .map_err(|e| format!("Failed to migrate database: {}", e))?;
+    tests::backfill_test_ids(&pool).await?;
+    Ok(pool)
+}
+
+/// Migrator over `get_migrations()`. Versions and checksums match the ones the SQL plugin
+/// recorded, so databases it migrated are picked up as is.
+fn migrator() -> Migrator {
+    let migrations = get_migrations()
+        .into_iter()
+        .map(|m| {
+            SqlxMigration::new(
+                m.version,
+                m.description.into(),
+                m.kind.into(),
+                m.sql.into(),
+                false,
+            )
+        })
+        .collect();
+    Migrator {
+        migrations: Cow::Owned(migrations),
+        ..Migrator::DEFAULT
+    }
+}
+
 pub fn get_migrations() -> Vec<Migration> {
     vec![
         Migration {
@@ -50,5 +98,77 @@ pub fn get_migrations() -> Vec<Migration> {
             sql: include_str!("../../migrations/008_run_target_results.sql"),
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 9,
+            description: "add sim_prep_profiles table",
+            sql: include_str!("../../migrations/009_sim_prep_profiles.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "add build_diagnostics table",
+            sql: include_str!("../../migrations/010_build_diagnostics.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "add crash_report_path to test_cases",
+            sql: include_str!("../../migrations/011_test_case_crash_report.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "add status_reason to test_cases",
+            sql: include_str!("../../migrations/012_test_case_status_reason.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "add performance_metrics table",
+            sql: include_str!("../../migrations/013_performance_metrics.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "add target key to test_cases",
+            sql: include_str!("../../migrations/014_test_case_target_key.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "add tests and performance_baselines tables and test ids",
+            sql: include_str!("../../migrations/015_tests.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "add git commit and branch to test_runs",
+            sql: include_str!("../../migrations/016_run_git_revision.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add flaky_scores table",
+            sql: include_str!("../../migrations/017_flaky_scores.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "add quarantined_tests table and quarantine counts",
+            sql: include_str!("../../migrations/018_quarantine.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "add run_annotations table",
+            sql: include_str!("../../migrations/019_run_annotations.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "add run pinning and retention limits",
+            sql: include_str!("../../migrations/020_retention.sql"),
+            kind: MigrationKind::Up,
+        },
     ]
 }
@@ -0,0 +1,278 @@
+use crate::analysis::flaky::{self, RunOutcome};
+use crate::models::history::{FlakyTest, TestInfo};
+use crate::models::run::TestStatus;
+use crate::persistence::history::TestRow;
+use sqlx::sqlite::SqlitePool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Most recent finished runs of a project that flakiness is scored over.
+const SCORE_WINDOW_RUNS: i64 = 30;
+
+#[derive(sqlx::FromRow)]
+struct FlakyRow {
+    test_id: i64,
+    project_id: String,
+    target_name: String,
+    suite_name: String,
+    test_name: String,
+    swift_testing: bool,
+    score: f64,
+    runs: i32,
+    failures: i32,
+    flips: i32,
+    retry_passes: i32,
+    isolated_failures: i32,
+    evidence: String,
+    computed_at: String,
+}
+
+impl From<FlakyRow> for FlakyTest {
+    fn from(row: FlakyRow) -> Self {
+        Self {
+            test: TestInfo::from(TestRow {
+                id: row.test_id,
+                project_id: row.project_id,
+                target_name: row.target_name,
+                suite_name: row.suite_name,
+                test_name: row.test_name,
+                swift_testing: row.swift_testing,
+            }),
+            score: row.score,
+            runs: row.runs,
+            failures: row.failures,
+            flips: row.flips,
+            retry_passes: row.retry_passes,
+            isolated_failures: row.isolated_failures,
+            evidence: serde_json::from_str(&row.evidence).unwrap_or_default(),
+            computed_at: row.computed_at,
+        }
+    }
+}
+
+/// Rescore the tests of project `project_id` ('' for runs without a project) over its recent
+/// finished runs, replacing its stored scores.
+pub async fn refresh_scores(pool: &SqlitePool, project_id: &str) -> Result<(), String> {
+    let rows: Vec<(i64, String, String, String, Option<String>)> = sqlx::query_as(
+        "SELECT tc.test_id, tc.run_id, r.started_at, tc.status, tc.failure_message
+         FROM test_cases tc JOIN test_runs r ON r.id = tc.run_id
+         WHERE tc.test_id IS NOT NULL AND tc.run_id IN (
+             SELECT id FROM test_runs WHERE COALESCE(project_id, '') = $1 AND status != 'running'
+             ORDER BY started_at DESC LIMIT $2)
+         ORDER BY r.started_at, tc.id",
+    )
+    .bind(project_id)
+    .bind(SCORE_WINDOW_RUNS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Tests failing in each run, to tell failures on otherwise green runs
+    let mut failing_by_run: HashMap<&str, HashSet<i64>> = HashMap::new();
+    for (test_id, run_id, _, status, _) in &rows {
+        if TestStatus::from_str(status).is_failure() {
+            failing_by_run.entry(run_id).or_default().insert(*test_id);
+        }
+    }
+
+    let mut outcomes: BTreeMap<i64, Vec<RunOutcome>> = BTreeMap::new();
+    for (test_id, run_id, started_at, status, failure_message) in &rows {
+        let status = TestStatus::from_str(status);
+        let test_outcomes = outcomes.entry(*test_id).or_default();
+        if test_outcomes.last().is_none_or(|o| o.run_id != *run_id) {
+            test_outcomes.push(RunOutcome {
+                run_id: run_id.clone(),
+                started_at: started_at.clone(),
+                passed: false,
+                failed: false,
+                failure_message: None,
+                only_failure: failing_by_run
+                    .get(run_id.as_str())
+                    .is_some_and(|f| f.len() == 1),
+            });
+        }
+        let outcome = test_outcomes.last_mut().expect("pushed above");
+        if status == TestStatus::Passed {
+            outcome.passed = true;
+        } else if status.is_failure() {
+            outcome.failed = true;
+            if outcome.failure_message.is_none() {
+                outcome.failure_message = failure_message.clone();
+            }
+        }
+    }
+
+    let computed_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM flaky_scores WHERE project_id = $1")
+        .bind(project_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (test_id, test_outcomes) in outcomes {
+        let Some(score) = flaky::score(&test_outcomes) else {
+            continue;
+        };
+        let evidence = serde_json::to_string(&score.evidence).map_err(|e| e.to_string())?;
+        sqlx::query(
+            "INSERT INTO flaky_scores (test_id, project_id, score, runs, failures, flips, retry_passes, isolated_failures, evidence, computed_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+        )
+        .bind(test_id)
+        .bind(project_id)
+        .bind(score.score)
+        .bind(score.runs)
+        .bind(score.failures)
+        .bind(score.flips)
+        .bind(score.retry_passes)
+        .bind(score.isolated_failures)
+        .bind(evidence)
+        .bind(&computed_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to record flaky score: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// Rescore every project that has recorded tests.
+pub async fn refresh_all_scores(pool: &SqlitePool) -> Result<(), String> {
+    let projects: Vec<(String,)> = sqlx::query_as("SELECT DISTINCT project_id FROM tests")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (project_id,) in projects {
+        refresh_scores(pool, &project_id).await?;
+    }
+    Ok(())
+}
+
+/// Flakiest tests first, optionally only those of one project ('' for runs without one).
+pub async fn list_flaky_tests(
+    pool: &SqlitePool,
+    project_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<FlakyTest>, String> {
+    let rows: Vec<FlakyRow> = sqlx::query_as(
+        "SELECT fs.*, t.target_name, t.suite_name, t.test_name, t.swift_testing
+         FROM flaky_scores fs JOIN tests t ON t.id = fs.test_id
+         WHERE ($1 IS NULL OR fs.project_id = $1)
+         ORDER BY fs.score DESC, fs.failures DESC LIMIT $2",
+    )
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(FlakyTest::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::run::TestCase;
+    use crate::persistence::runs;
+    use crate::persistence::testing::{self, TestDb};
+
+    fn case(run_id: &str, test_name: &str, status: TestStatus) -> TestCase {
+        let failure_message = status
+            .is_failure()
+            .then(|| format!("{} failed in {}", test_name, run_id));
+        TestCase {
+            id: None,
+            run_id: run_id.to_string(),
+            suite_name: "AppTests.LoginTests".to_string(),
+            test_name: test_name.to_string(),
+            status,
+            duration_ms: Some(100),
+            failure_message,
+            file_path: None,
+            line_number: None,
+            crash_report_path: None,
+            status_reason: None,
+            target_key: None,
+            test_id: None,
+            target_name: None,
+            quarantined: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn scores_tests_over_finished_runs() {
+        use TestStatus::{Failed, Passed};
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        let mut conn = pool.acquire().await.unwrap();
+        let recorded = [
+            ("run-1", vec![("testLogin", Passed), ("testLogout", Passed)]),
+            // testLogin is the only failure of the run
+            ("run-2", vec![("testLogin", Failed)]),
+            ("run-3", vec![("testLogin", Passed), ("testLogout", Failed)]),
+            // testLogin passes on retry
+            (
+                "run-4",
+                vec![
+                    ("testLogin", Failed),
+                    ("testLogin", Passed),
+                    ("testLogout", Failed),
+                ],
+            ),
+            // Still running: not scored
+            ("run-5", vec![("testLogin", Failed)]),
+        ];
+        for (i, (run_id, cases)) in recorded.iter().enumerate() {
+            let started_at = format!("2026-01-1{}T10:00:00.000Z", i);
+            runs::insert_run(pool, &testing::run(run_id, &started_at))
+                .await
+                .unwrap();
+            if *run_id != "run-5" {
+                sqlx::query("UPDATE test_runs SET status = 'failed' WHERE id = $1")
+                    .bind(run_id)
+                    .execute(pool)
+                    .await
+                    .unwrap();
+            }
+            for (name, status) in cases {
+                runs::save_test_case(&mut conn, "", None, &case(run_id, name, status.clone()))
+                    .await
+                    .unwrap();
+            }
+        }
+        drop(conn);
+
+        refresh_scores(pool, "").await.unwrap();
+        // Refreshing replaces the stored scores
+        refresh_scores(pool, "").await.unwrap();
+        let flaky = list_flaky_tests(pool, Some(""), 10).await.unwrap();
+        assert_eq!(flaky.len(), 2);
+
+        let login = &flaky[0];
+        assert_eq!(login.test.test_name, "testLogin");
+        assert_eq!(
+            (login.runs, login.failures, login.flips),
+            (4, 2, 2),
+            "a pass on retry is a pass"
+        );
+        assert_eq!((login.retry_passes, login.isolated_failures), (1, 1));
+        let evidence: Vec<(&str, bool, bool)> = login
+            .evidence
+            .iter()
+            .map(|e| (e.run_id.as_str(), e.passed_on_retry, e.only_failure))
+            .collect();
+        assert_eq!(evidence, [("run-4", true, false), ("run-2", false, true)]);
+        assert_eq!(
+            login.evidence[0].failure_message.as_deref(),
+            Some("testLogin failed in run-4")
+        );
+
+        let logout = &flaky[1];
+        assert_eq!(logout.test.test_name, "testLogout");
+        assert_eq!((logout.runs, logout.failures, logout.flips), (3, 2, 1));
+        // Alone in failing run-3, not in run-4
+        assert_eq!(logout.isolated_failures, 1);
+        assert!(logout.score < login.score);
+
+        assert!(list_flaky_tests(pool, Some("project-1"), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,488 @@
+use crate::analysis::compare::TestResult;
+use crate::execution::progress::TargetEstimate;
+use crate::models::history::{ClusterFailure, TestHistory, TestHistoryEntry, TestInfo, TestStats};
+use crate::models::run::{TargetResult, TestStatus};
+use sqlx::sqlite::SqlitePool;
+
+#[derive(sqlx::FromRow)]
+pub(super) struct TestRow {
+    pub(super) id: i64,
+    pub(super) project_id: String,
+    pub(super) target_name: String,
+    pub(super) suite_name: String,
+    pub(super) test_name: String,
+    pub(super) swift_testing: bool,
+}
+
+impl From<TestRow> for TestInfo {
+    fn from(row: TestRow) -> Self {
+        // Tests of runs without a project, or without a known target, store ''
+        Self {
+            id: row.id,
+            project_id: Some(row.project_id).filter(|p| !p.is_empty()),
+            target_name: Some(row.target_name).filter(|t| !t.is_empty()),
+            suite_name: row.suite_name,
+            test_name: row.test_name,
+            swift_testing: row.swift_testing,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EntryRow {
+    run_id: String,
+    started_at: String,
+    status: String,
+    duration_ms: Option<i64>,
+    failure_message: Option<String>,
+    git_commit: Option<String>,
+    git_branch: Option<String>,
+}
+
+impl From<EntryRow> for TestHistoryEntry {
+    fn from(row: EntryRow) -> Self {
+        Self {
+            run_id: row.run_id,
+            started_at: row.started_at,
+            status: TestStatus::from_str(&row.status),
+            duration_ms: row.duration_ms,
+            failure_message: row.failure_message,
+            git_commit: row.git_commit,
+            git_branch: row.git_branch,
+        }
+    }
+}
+
+/// Nearest-rank percentile of ascending `sorted` values.
+fn percentile(sorted: &[i64], p: f64) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn summarize(
+    entries: &[TestHistoryEntry],
+    first_seen_at: Option<String>,
+    last_failed_at: Option<String>,
+) -> TestStats {
+    let count = |f: fn(&TestStatus) -> bool| entries.iter().filter(|e| f(&e.status)).count() as i32;
+    let passed = count(|s| *s == TestStatus::Passed);
+    let failed = count(TestStatus::is_failure);
+    let skipped = count(|s| *s == TestStatus::Skipped);
+    let mut runs: Vec<&str> = entries.iter().map(|e| e.run_id.as_str()).collect();
+    runs.sort_unstable();
+    runs.dedup();
+    // Skipped tests report no meaningful duration
+    let mut durations: Vec<i64> = entries
+        .iter()
+        .filter(|e| e.status != TestStatus::Skipped)
+        .filter_map(|e| e.duration_ms)
+        .collect();
+    durations.sort_unstable();
+
+    TestStats {
+        runs: runs.len() as i32,
+        passed,
+        failed,
+        skipped,
+        pass_rate: (passed + failed > 0).then(|| passed as f64 / (passed + failed) as f64),
+        p50_duration_ms: percentile(&durations, 0.5),
+        p90_duration_ms: percentile(&durations, 0.9),
+        max_duration_ms: durations.last().copied(),
+        first_seen_at,
+        last_failed_at,
+    }
+}
+
+/// Results of test `test_id` in the last `limit` runs that recorded it, with their summary.
+/// None if there is no such test.
+pub async fn get_test_history(
+    pool: &SqlitePool,
+    test_id: i64,
+    limit: i64,
+) -> Result<Option<TestHistory>, String> {
+    let test: Option<TestRow> = sqlx::query_as("SELECT * FROM tests WHERE id = $1")
+        .bind(test_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some(test) = test else {
+        return Ok(None);
+    };
+
+    let rows: Vec<EntryRow> = sqlx::query_as(
+        "SELECT tc.run_id, r.started_at, tc.status, tc.duration_ms, tc.failure_message, r.git_commit, r.git_branch
+         FROM test_cases tc JOIN test_runs r ON r.id = tc.run_id
+         WHERE tc.test_id = $1 AND tc.run_id IN (
+             SELECT r2.id FROM test_runs r2 JOIN test_cases tc2 ON tc2.run_id = r2.id
+             WHERE tc2.test_id = $1 GROUP BY r2.id ORDER BY r2.started_at DESC LIMIT $2)
+         ORDER BY r.started_at DESC, tc.id DESC",
+    )
+    .bind(test_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let entries: Vec<TestHistoryEntry> = rows.into_iter().map(TestHistoryEntry::from).collect();
+
+    let (first_seen_at, last_failed_at): (Option<String>, Option<String>) = sqlx::query_as(
+        "SELECT MIN(r.started_at), MAX(CASE WHEN tc.status IN ('failed', 'crashed', 'timed_out') THEN r.started_at END)
+         FROM test_cases tc JOIN test_runs r ON r.id = tc.run_id WHERE tc.test_id = $1",
+    )
+    .bind(test_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(Some(TestHistory {
+        test: test.into(),
+        stats: summarize(&entries, first_seen_at, last_failed_at),
+        entries,
+    }))
+}
+
+#[derive(sqlx::FromRow)]
+struct ResultRow {
+    #[sqlx(flatten)]
+    test: TestRow,
+    target_key: Option<String>,
+    status: String,
+    duration_ms: Option<i64>,
+    failure_message: Option<String>,
+}
+
+/// Results of run `run_id` linked to their tests, in recording order.
+pub async fn get_run_results(pool: &SqlitePool, run_id: &str) -> Result<Vec<TestResult>, String> {
+    let rows: Vec<ResultRow> = sqlx::query_as(
+        "SELECT t.*, tc.target_key, tc.status, tc.duration_ms, tc.failure_message
+         FROM test_cases tc JOIN tests t ON t.id = tc.test_id
+         WHERE tc.run_id = $1 ORDER BY tc.id",
+    )
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|row| TestResult {
+            test: row.test.into(),
+            target_key: row.target_key,
+            status: TestStatus::from_str(&row.status),
+            duration_ms: row.duration_ms,
+            failure_message: row.failure_message,
+        })
+        .collect())
+}
+
+#[derive(sqlx::FromRow)]
+struct FailureRow {
+    #[sqlx(flatten)]
+    test: TestRow,
+    case_id: i64,
+    run_id: String,
+    run_started_at: String,
+    status: String,
+    failure_message: Option<String>,
+    file_path: Option<String>,
+    line_number: Option<i32>,
+    quarantined: bool,
+}
+
+impl From<FailureRow> for ClusterFailure {
+    fn from(row: FailureRow) -> Self {
+        Self {
+            case_id: row.case_id,
+            run_id: row.run_id,
+            run_started_at: row.run_started_at,
+            test: row.test.into(),
+            status: TestStatus::from_str(&row.status),
+            failure_message: row.failure_message,
+            file_path: row.file_path,
+            line_number: row.line_number,
+            quarantined: row.quarantined,
+        }
+    }
+}
+
+const SELECT_FAILURES: &str =
+    "SELECT t.*, tc.id AS case_id, tc.run_id, r.started_at AS run_started_at, tc.status,
+        tc.failure_message, tc.file_path, tc.line_number, tc.quarantined
+    FROM test_cases tc JOIN tests t ON t.id = tc.test_id JOIN test_runs r ON r.id = tc.run_id
+    WHERE tc.status IN ('failed', 'crashed', 'timed_out')";
+
+/// Failed, crashed and timed-out cases of run `run_id`.
+pub async fn get_run_failures(
+    pool: &SqlitePool,
+    run_id: &str,
+) -> Result<Vec<ClusterFailure>, String> {
+    let rows: Vec<FailureRow> = sqlx::query_as(&format!(
+        "{SELECT_FAILURES} AND tc.run_id = $1 ORDER BY tc.id"
+    ))
+    .bind(run_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(ClusterFailure::from).collect())
+}
+
+/// Failed, crashed and timed-out cases of the last `runs` finished runs, optionally only those
+/// of one project.
+pub async fn get_recent_failures(
+    pool: &SqlitePool,
+    project_id: Option<&str>,
+    runs: i64,
+) -> Result<Vec<ClusterFailure>, String> {
+    let rows: Vec<FailureRow> = sqlx::query_as(&format!(
+        "{SELECT_FAILURES} AND tc.run_id IN (
+             SELECT id FROM test_runs WHERE ($1 IS NULL OR project_id = $1) AND status != 'running'
+             ORDER BY started_at DESC LIMIT $2)
+         ORDER BY r.started_at DESC, tc.id"
+    ))
+    .bind(project_id)
+    .bind(runs)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(ClusterFailure::from).collect())
+}
+
+/// Test count and duration of each target key the last time it ran tests, within the last
+/// `runs` runs, optionally only those of one project.
+pub async fn target_estimates(
+    pool: &SqlitePool,
+    project_id: Option<&str>,
+    runs: i64,
+) -> Result<Vec<TargetEstimate>, String> {
+    let rows: Vec<(Option<String>,)> = sqlx::query_as(
+        "SELECT target_results FROM test_runs WHERE ($1 IS NULL OR project_id = $1) ORDER BY started_at DESC LIMIT $2",
+    )
+    .bind(project_id)
+    .bind(runs)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut estimates: Vec<TargetEstimate> = Vec::new();
+    for (json,) in rows {
+        // Unreadable JSON is treated as missing, as when listing runs
+        let results: Vec<TargetResult> = json
+            .and_then(|j| serde_json::from_str(&j).ok())
+            .unwrap_or_default();
+        for result in results {
+            let Some(test_count) = result.tests_run.filter(|&n| n > 0) else {
+                continue;
+            };
+            if estimates.iter().any(|e| e.key == result.key) {
+                continue;
+            }
+            estimates.push(TargetEstimate {
+                key: result.key,
+                test_count,
+                duration_ms: result.duration_ms,
+            });
+        }
+    }
+    Ok(estimates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::run::{RunStatus, TestCase, TestRun};
+    use crate::persistence::runs;
+    use crate::persistence::testing::{self, TestDb};
+
+    fn case(run_id: &str, status: TestStatus, duration_ms: i64) -> TestCase {
+        TestCase {
+            id: None,
+            run_id: run_id.to_string(),
+            suite_name: "AppTests.LoginTests".to_string(),
+            test_name: "testValid".to_string(),
+            status,
+            duration_ms: Some(duration_ms),
+            failure_message: None,
+            file_path: None,
+            line_number: None,
+            crash_report_path: None,
+            status_reason: None,
+            target_key: None,
+            test_id: None,
+            target_name: None,
+            quarantined: false,
+        }
+    }
+
+    fn target(key: &str, tests_run: Option<i32>, duration_ms: Option<i64>) -> TargetResult {
+        TargetResult {
+            key: key.to_string(),
+            success: true,
+            tests_run,
+            duration_ms,
+        }
+    }
+
+    /// A finished run of `project_id` with `targets` results.
+    async fn finished_run(
+        pool: &SqlitePool,
+        id: &str,
+        started_at: &str,
+        project_id: &str,
+        targets: Vec<TargetResult>,
+    ) {
+        let run = TestRun {
+            project_id: Some(project_id.to_string()),
+            ..testing::run(id, started_at)
+        };
+        runs::insert_run(pool, &run).await.unwrap();
+        runs::complete_run(
+            pool,
+            &TestRun {
+                status: RunStatus::Passed,
+                target_results: Some(targets),
+                ..run
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        assert_eq!(percentile(&[10, 20, 30, 40], 0.5), Some(20));
+        assert_eq!(percentile(&[10, 20, 30, 40], 0.9), Some(40));
+        assert_eq!(percentile(&[10], 0.0), Some(10));
+        assert_eq!(percentile(&[], 0.5), None);
+    }
+
+    #[tokio::test]
+    async fn test_history_covers_the_latest_runs_that_recorded_the_test() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        let mut conn = pool.acquire().await.unwrap();
+        let mut test_id = 0;
+        for (run_id, started_at, status, duration_ms) in [
+            ("run-1", "2026-01-10T10:00:00.000Z", TestStatus::Failed, 100),
+            ("run-2", "2026-01-11T10:00:00.000Z", TestStatus::Passed, 200),
+            ("run-3", "2026-01-12T10:00:00.000Z", TestStatus::Skipped, 0),
+        ] {
+            runs::insert_run(pool, &testing::run(run_id, started_at))
+                .await
+                .unwrap();
+            let case_id =
+                runs::save_test_case(&mut conn, "", None, &case(run_id, status, duration_ms))
+                    .await
+                    .unwrap();
+            test_id = sqlx::query_scalar("SELECT test_id FROM test_cases WHERE id = $1")
+                .bind(case_id)
+                .fetch_one(&mut *conn)
+                .await
+                .unwrap();
+        }
+        // A run that did not record the test does not count towards the limit
+        runs::insert_run(pool, &testing::run("run-4", "2026-01-13T10:00:00.000Z"))
+            .await
+            .unwrap();
+
+        let history = get_test_history(pool, test_id, 2).await.unwrap().unwrap();
+        assert_eq!(history.test.suite_name, "LoginTests");
+        assert_eq!(history.test.target_name.as_deref(), Some("AppTests"));
+        let runs: Vec<&str> = history.entries.iter().map(|e| e.run_id.as_str()).collect();
+        assert_eq!(runs, ["run-3", "run-2"]);
+
+        let stats = &history.stats;
+        assert_eq!(stats.runs, 2);
+        assert_eq!((stats.passed, stats.failed, stats.skipped), (1, 0, 1));
+        assert_eq!(stats.pass_rate, Some(1.0));
+        // The skipped case's duration is left out
+        assert_eq!(stats.p50_duration_ms, Some(200));
+        assert_eq!(stats.max_duration_ms, Some(200));
+        // First seen and last failed look past the limit
+        assert_eq!(
+            stats.first_seen_at.as_deref(),
+            Some("2026-01-10T10:00:00.000Z")
+        );
+        assert_eq!(
+            stats.last_failed_at.as_deref(),
+            Some("2026-01-10T10:00:00.000Z")
+        );
+
+        let all = get_test_history(pool, test_id, 10).await.unwrap().unwrap();
+        assert_eq!(all.stats.pass_rate, Some(0.5));
+        assert!(get_test_history(pool, test_id + 1, 10)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn target_estimates_come_from_the_latest_run_of_each_target() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        for id in ["project-1", "project-2"] {
+            sqlx::query(
+                "INSERT INTO projects (id, name, path, created_at) VALUES ($1, $1, $1, '2026-01-01T00:00:00.000Z')",
+            )
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+        finished_run(
+            pool,
+            "run-1",
+            "2026-01-10T10:00:00.000Z",
+            "project-1",
+            vec![
+                target("App", Some(5), Some(1_000)),
+                target("Kit", Some(2), Some(300)),
+                target("Pkg", Some(3), None),
+            ],
+        )
+        .await;
+        finished_run(
+            pool,
+            "run-2",
+            "2026-01-11T10:00:00.000Z",
+            "project-1",
+            // A target that ran no tests (or before counts were kept) says nothing
+            vec![
+                target("App", Some(6), Some(1_200)),
+                target("Kit", Some(0), Some(10)),
+                target("Pkg", None, Some(50)),
+            ],
+        )
+        .await;
+        finished_run(
+            pool,
+            "run-3",
+            "2026-01-12T10:00:00.000Z",
+            "project-2",
+            vec![target("App", Some(99), Some(9_900))],
+        )
+        .await;
+
+        let estimate = |key: &str, test_count: i32, duration_ms: Option<i64>| TargetEstimate {
+            key: key.to_string(),
+            test_count,
+            duration_ms,
+        };
+        assert_eq!(
+            target_estimates(pool, Some("project-1"), 20).await.unwrap(),
+            [
+                estimate("App", 6, Some(1_200)),
+                estimate("Kit", 2, Some(300)),
+                estimate("Pkg", 3, None),
+            ]
+        );
+        assert_eq!(
+            target_estimates(pool, Some("project-1"), 1).await.unwrap(),
+            [estimate("App", 6, Some(1_200))]
+        );
+        assert_eq!(
+            target_estimates(pool, None, 1).await.unwrap(),
+            [estimate("App", 99, Some(9_900))]
+        );
+    }
+}
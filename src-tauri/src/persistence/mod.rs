@@ -1,3 +1,12 @@
+pub mod annotations;
 pub mod db;
+pub mod flaky;
+pub mod history;
+pub mod performance;
+pub mod quarantine;
+pub mod retention;
 pub mod runs;
 pub mod settings;
+pub mod tests;
+#[cfg(test)]
+pub mod testing;
@@ -0,0 +1,195 @@
+use crate::analysis::performance::{Baseline, BaselineComparison, DEFAULT_REGRESSION_THRESHOLD};
+use crate::models::run::PerformanceMetric;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+
+/// Regression threshold from settings, in percent; missing or unreadable means the default.
+pub async fn load_regression_threshold(conn: &mut SqliteConnection) -> Result<f64, String> {
+    let value: Option<String> =
+        sqlx::query_scalar("SELECT value FROM settings WHERE key = 'perf_regression_threshold'")
+            .fetch_optional(conn)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(value
+        .and_then(|v| v.trim().parse::<f64>().ok())
+        .unwrap_or(DEFAULT_REGRESSION_THRESHOLD))
+}
+
+/// Baseline for metric `metric` of test `test_id` in run `run_id`: the pinned one, else the
+/// test's average in the latest other run.
+pub async fn find_baseline(
+    conn: &mut SqliteConnection,
+    test_id: i64,
+    run_id: &str,
+    metric: &str,
+) -> Result<Option<Baseline>, String> {
+    let pinned: Option<(f64, Option<String>)> = sqlx::query_as(
+        "SELECT average, run_id FROM performance_baselines WHERE test_id = $1 AND metric = $2",
+    )
+    .bind(test_id)
+    .bind(metric)
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to load performance baseline: {}", e))?;
+    if let Some((average, run_id)) = pinned {
+        return Ok(Some(Baseline {
+            average,
+            run_id,
+            pinned: true,
+        }));
+    }
+    let previous: Option<(f64, String)> = sqlx::query_as(
+        "SELECT pm.average, pm.run_id
+         FROM performance_metrics pm JOIN test_runs r ON r.id = pm.run_id
+         WHERE pm.test_id = $1 AND pm.metric = $2 AND pm.run_id != $3
+         ORDER BY r.started_at DESC, pm.id DESC
+         LIMIT 1",
+    )
+    .bind(test_id)
+    .bind(metric)
+    .bind(run_id)
+    .fetch_optional(conn)
+    .await
+    .map_err(|e| format!("Failed to load performance baseline: {}", e))?;
+    Ok(previous.map(|(average, run_id)| Baseline {
+        average,
+        run_id: Some(run_id),
+        pinned: false,
+    }))
+}
+
+/// Record `metric` of test `test_id` in run `run_id` with how it compared to its baseline.
+pub async fn save_metric(
+    conn: &mut SqliteConnection,
+    run_id: &str,
+    test_id: i64,
+    metric: &PerformanceMetric,
+    comparison: &BaselineComparison,
+) -> Result<(), String> {
+    let measurements = serde_json::to_string(&metric.values).map_err(|e| e.to_string())?;
+    let baseline = comparison.baseline.as_ref();
+    sqlx::query(
+        "INSERT INTO performance_metrics (run_id, test_id, suite_name, test_name, metric, unit, identifier, average, relative_std_dev, measurements, baseline_average, baseline_run_id, baseline_pinned, change_percent, regressed) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)",
+    )
+    .bind(run_id)
+    .bind(test_id)
+    .bind(&metric.suite_name)
+    .bind(&metric.test_name)
+    .bind(&metric.metric)
+    .bind(&metric.unit)
+    .bind(&metric.identifier)
+    .bind(metric.average)
+    .bind(metric.relative_std_dev)
+    .bind(measurements)
+    .bind(baseline.map(|b| b.average))
+    .bind(baseline.and_then(|b| b.run_id.as_deref()))
+    .bind(baseline.is_some_and(|b| b.pinned))
+    .bind(comparison.change_percent)
+    .bind(comparison.regressed)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("Failed to record performance metric: {}", e))?;
+    Ok(())
+}
+
+/// Pin the average of recorded metric `metric_id` as the baseline its test's later runs are
+/// compared against, replacing any pinned before.
+pub async fn pin_baseline(pool: &SqlitePool, metric_id: i64) -> Result<(), String> {
+    let pinned_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let result = sqlx::query(
+        "INSERT OR REPLACE INTO performance_baselines (test_id, metric, average, run_id, pinned_at)
+         SELECT test_id, metric, average, run_id, $2 FROM performance_metrics
+         WHERE id = $1 AND test_id IS NOT NULL",
+    )
+    .bind(metric_id)
+    .bind(pinned_at)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to pin performance baseline: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err(format!("Performance metric {} not found", metric_id));
+    }
+    Ok(())
+}
+
+/// Remove the baseline pinned for the test and metric of recorded metric `metric_id`, so later
+/// runs are compared against the previous run again.
+pub async fn unpin_baseline(pool: &SqlitePool, metric_id: i64) -> Result<(), String> {
+    sqlx::query(
+        "DELETE FROM performance_baselines WHERE (test_id, metric) IN (
+             SELECT test_id, metric FROM performance_metrics WHERE id = $1)",
+    )
+    .bind(metric_id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to unpin performance baseline: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::runs;
+    use crate::persistence::testing::{self, TestDb};
+    use crate::persistence::tests::{resolve_test_id, TestKey};
+
+    async fn record(pool: &SqlitePool, run_id: &str, day: u32, suite: &str, average: f64) -> i64 {
+        let started_at = format!("2026-01-{:02}T10:00:00.000Z", day);
+        runs::insert_run(pool, &testing::run(run_id, &started_at))
+            .await
+            .unwrap();
+        let mut conn = pool.acquire().await.unwrap();
+        let key = TestKey::new(None, suite, "testSpeed");
+        let test_id = resolve_test_id(&mut conn, "", &key).await.unwrap();
+        let metric =
+            PerformanceMetric::from_values(suite, "testSpeed", "Time", "s", None, vec![average])
+                .unwrap();
+        save_metric(&mut conn, run_id, test_id, &metric, &Default::default())
+            .await
+            .unwrap();
+        sqlx::query_scalar("SELECT MAX(id) FROM performance_metrics")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap()
+    }
+
+    async fn baseline(pool: &SqlitePool, run_id: &str) -> Option<(f64, Option<String>, bool)> {
+        let mut conn = pool.acquire().await.unwrap();
+        let key = TestKey::new(None, "AppTests.LoginTests", "testSpeed");
+        let test_id = resolve_test_id(&mut conn, "", &key).await.unwrap();
+        find_baseline(&mut conn, test_id, run_id, "Time")
+            .await
+            .unwrap()
+            .map(|b| (b.average, b.run_id, b.pinned))
+    }
+
+    #[tokio::test]
+    async fn baselines_follow_the_test() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        let first = record(pool, "run-1", 1, "AppTests.LoginTests", 1.0).await;
+        assert_eq!(baseline(pool, "run-1").await, None);
+        // The bundle's suite name without the module is the same test
+        record(pool, "run-2", 2, "LoginTests", 2.0).await;
+        assert_eq!(
+            baseline(pool, "run-3").await,
+            Some((2.0, Some("run-2".to_string()), false))
+        );
+        // The run itself is not its own baseline
+        assert_eq!(
+            baseline(pool, "run-2").await,
+            Some((1.0, Some("run-1".to_string()), false))
+        );
+
+        pin_baseline(pool, first).await.unwrap();
+        assert_eq!(
+            baseline(pool, "run-3").await,
+            Some((1.0, Some("run-1".to_string()), true))
+        );
+        unpin_baseline(pool, first).await.unwrap();
+        assert_eq!(
+            baseline(pool, "run-3").await,
+            Some((2.0, Some("run-2".to_string()), false))
+        );
+        assert!(pin_baseline(pool, 999).await.is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::models::history::TestInfo;
+use crate::models::quarantine::{QuarantineMode, QuarantinedTest};
+use crate::persistence::history::TestRow;
+use sqlx::sqlite::SqlitePool;
+
+/// SQL condition on `quarantined_tests q` for a quarantine that still applies today.
+pub(super) const ACTIVE: &str =
+    "(q.expires_at IS NULL OR q.expires_at >= date('now', 'localtime'))";
+
+#[derive(sqlx::FromRow)]
+struct QuarantineRow {
+    test_id: i64,
+    project_id: String,
+    target_name: String,
+    suite_name: String,
+    test_name: String,
+    swift_testing: bool,
+    mode: String,
+    reason: String,
+    owner: Option<String>,
+    expires_at: Option<String>,
+    created_at: String,
+    expired: bool,
+}
+
+impl From<QuarantineRow> for QuarantinedTest {
+    fn from(row: QuarantineRow) -> Self {
+        Self {
+            test: TestInfo::from(TestRow {
+                id: row.test_id,
+                project_id: row.project_id,
+                target_name: row.target_name,
+                suite_name: row.suite_name,
+                test_name: row.test_name,
+                swift_testing: row.swift_testing,
+            }),
+            mode: QuarantineMode::from_str(&row.mode),
+            reason: row.reason,
+            owner: row.owner,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+            expired: row.expired,
+        }
+    }
+}
+
+/// Quarantined tests, expired ones first so they get reviewed, optionally only those of one
+/// project ('' for runs without one).
+pub async fn list_quarantined(
+    pool: &SqlitePool,
+    project_id: Option<&str>,
+) -> Result<Vec<QuarantinedTest>, String> {
+    let query = format!(
+        "SELECT q.*, t.target_name, t.suite_name, t.test_name, t.swift_testing, NOT {ACTIVE} AS expired
+         FROM quarantined_tests q JOIN tests t ON t.id = q.test_id
+         WHERE ($1 IS NULL OR q.project_id = $1)
+         ORDER BY expired DESC, t.suite_name, t.test_name"
+    );
+    let rows: Vec<QuarantineRow> = sqlx::query_as(&query)
+        .bind(project_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(QuarantinedTest::from).collect())
+}
+
+/// Quarantine test `test_id` in its project, replacing any earlier quarantine of it.
+pub async fn quarantine_test(
+    pool: &SqlitePool,
+    test_id: i64,
+    mode: QuarantineMode,
+    reason: &str,
+    owner: Option<&str>,
+    expires_at: Option<&str>,
+) -> Result<(), String> {
+    let created_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let result = sqlx::query(
+        "INSERT OR REPLACE INTO quarantined_tests (test_id, project_id, mode, reason, owner, expires_at, created_at)
+         SELECT id, project_id, $2, $3, $4, $5, $6 FROM tests WHERE id = $1",
+    )
+    .bind(test_id)
+    .bind(mode.to_string())
+    .bind(reason)
+    .bind(owner)
+    .bind(expires_at)
+    .bind(created_at)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to quarantine test: {}", e))?;
+    if result.rows_affected() == 0 {
+        return Err(format!("Test {} not found", test_id));
+    }
+    Ok(())
+}
+
+pub async fn release_test(pool: &SqlitePool, test_id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM quarantined_tests WHERE test_id = $1")
+        .bind(test_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use crate::execution::artifacts;
+use crate::models::settings::{RetentionPolicy, RetentionReport};
+use sqlx::sqlite::SqlitePool;
+use std::collections::HashSet;
+
+/// Retention limits from settings; missing, unreadable and non-positive values mean no limit.
+pub async fn load_policy(pool: &SqlitePool) -> Result<RetentionPolicy, String> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT key, value FROM settings
+         WHERE key IN ('retain_last_runs', 'retain_max_age_days', 'retain_max_disk_mb')",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let limit = |key: &str| {
+        rows.iter()
+            .find(|(k, _)| k == key)
+            .and_then(|(_, value)| value.trim().parse::<i64>().ok())
+            .filter(|n| *n > 0)
+    };
+    Ok(RetentionPolicy {
+        max_runs: limit("retain_last_runs"),
+        max_age_days: limit("retain_max_age_days"),
+        max_disk_bytes: limit("retain_max_disk_mb").map(|mb| mb as u64 * 1024 * 1024),
+    })
+}
+
+/// Delete the row of run `run_id`; test cases, diagnostics, metrics and annotations cascade.
+/// Returns whether the run was recorded.
+async fn delete_run_row(pool: &SqlitePool, run_id: &str) -> Result<bool, String> {
+    let result = sqlx::query("DELETE FROM test_runs WHERE id = $1")
+        .bind(run_id)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to delete run: {}", e))?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete run `run_id`: its row and everything recorded with it, then its artifacts directory.
+/// Nothing is removed for a run that is not recorded.
+pub async fn delete_run(pool: &SqlitePool, run_id: &str) -> Result<(), String> {
+    if !delete_run_row(pool, run_id).await? {
+        return Err(format!("Run {} not found", run_id));
+    }
+    artifacts::remove_run_dir(run_id)
+}
+
+pub async fn set_pinned(pool: &SqlitePool, run_id: &str, pinned: bool) -> Result<(), String> {
+    let result = sqlx::query("UPDATE test_runs SET pinned = $1 WHERE id = $2")
+        .bind(pinned)
+        .bind(run_id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    if result.rows_affected() == 0 {
+        return Err(format!("Run {} not found", run_id));
+    }
+    Ok(())
+}
+
+/// Delete finished, unpinned runs beyond the count, age and disk limits in settings, and
+/// artifacts directories of runs no longer recorded. Runs are kept newest first until a limit
+/// is reached; limits count only unpinned runs, and the most recent of them is always kept.
+pub async fn apply_retention(pool: &SqlitePool) -> Result<RetentionReport, String> {
+    let policy = load_policy(pool).await?;
+    let cutoff = policy.max_age_days.map(|days| {
+        (chrono::Utc::now() - chrono::Duration::days(days))
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+    });
+    let runs: Vec<(String, String)> = sqlx::query_as(
+        "SELECT id, started_at FROM test_runs WHERE status != 'running' AND pinned = 0
+         ORDER BY started_at DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut report = RetentionReport::default();
+    let mut kept_runs = 0;
+    let mut kept_bytes = 0;
+    for (index, (run_id, started_at)) in runs.iter().enumerate() {
+        let bytes = artifacts::disk_usage(&artifacts::run_dir(run_id));
+        let expired = cutoff.as_ref().is_some_and(|c| started_at < c);
+        let over_count = policy.max_runs.is_some_and(|max| kept_runs >= max);
+        let over_disk = policy
+            .max_disk_bytes
+            .is_some_and(|max| kept_bytes + bytes > max);
+        if index == 0 || !(expired || over_count || over_disk) {
+            kept_runs += 1;
+            kept_bytes += bytes;
+            continue;
+        }
+        delete_run_row(pool, run_id).await?;
+        report.deleted_runs.push(run_id.clone());
+        // A directory that cannot be removed now is retried as an orphan next time
+        match artifacts::remove_run_dir(run_id) {
+            Ok(()) => report.freed_bytes += bytes,
+            Err(message) => report.errors.push(message),
+        }
+    }
+
+    let recorded: HashSet<String> = sqlx::query_scalar("SELECT id FROM test_runs")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    for run_id in artifacts::run_dir_ids() {
+        if recorded.contains(&run_id) {
+            continue;
+        }
+        let bytes = artifacts::disk_usage(&artifacts::run_dir(&run_id));
+        match artifacts::remove_run_dir(&run_id) {
+            Ok(()) => {
+                report.removed_orphans += 1;
+                report.freed_bytes += bytes;
+            }
+            Err(message) => report.errors.push(message),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::runs;
+    use crate::persistence::testing::{self, TestDb};
+
+    /// A finished run started `days_ago`, with `artifact_bytes` of artifacts.
+    async fn finished_run(pool: &SqlitePool, days_ago: i64, artifact_bytes: usize) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        let started_at = (chrono::Utc::now() - chrono::Duration::days(days_ago))
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        runs::insert_run(pool, &testing::run(&id, &started_at))
+            .await
+            .unwrap();
+        sqlx::query("UPDATE test_runs SET status = 'passed' WHERE id = $1")
+            .bind(&id)
+            .execute(pool)
+            .await
+            .unwrap();
+        if artifact_bytes > 0 {
+            let dir = artifacts::run_dir(&id);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("Run.xcresult"), vec![0u8; artifact_bytes]).unwrap();
+        }
+        id
+    }
+
+    async fn set_limit(pool: &SqlitePool, key: &str, value: i64) {
+        sqlx::query("INSERT OR REPLACE INTO settings (key, value) VALUES ($1, $2)")
+            .bind(key)
+            .bind(value.to_string())
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn recorded(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_scalar("SELECT id FROM test_runs ORDER BY started_at DESC")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn keeps_the_newest_runs_up_to_the_count_besides_pinned_and_running_ones() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        set_limit(pool, "retain_last_runs", 2).await;
+        let running = uuid::Uuid::new_v4().to_string();
+        runs::insert_run(pool, &testing::run(&running, "2020-01-01T00:00:00.000Z"))
+            .await
+            .unwrap();
+        let oldest = finished_run(pool, 4, 0).await;
+        set_pinned(pool, &oldest, true).await.unwrap();
+        let old = finished_run(pool, 3, 0).await;
+        let newer = finished_run(pool, 2, 0).await;
+        let newest = finished_run(pool, 1, 0).await;
+
+        let report = apply_retention(pool).await.unwrap();
+        assert_eq!(report.deleted_runs, [old]);
+        assert_eq!(recorded(pool).await, [newest, newer, oldest, running]);
+    }
+
+    #[tokio::test]
+    async fn deletes_runs_past_the_age_limit_except_the_newest() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        set_limit(pool, "retain_max_age_days", 30).await;
+        let expired = finished_run(pool, 60, 0).await;
+        let newest_expired = finished_run(pool, 45, 0).await;
+
+        let report = apply_retention(pool).await.unwrap();
+        assert_eq!(report.deleted_runs, [expired]);
+        assert_eq!(recorded(pool).await, [newest_expired]);
+
+        let recent = finished_run(pool, 1, 0).await;
+        apply_retention(pool).await.unwrap();
+        assert_eq!(recorded(pool).await, [recent]);
+    }
+
+    #[tokio::test]
+    async fn deletes_runs_past_the_disk_limit_with_their_artifacts() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        set_limit(pool, "retain_max_disk_mb", 1).await;
+        let over = finished_run(pool, 3, 400 * 1024).await;
+        let between = finished_run(pool, 2, 400 * 1024).await;
+        // The newest run is kept even when it alone is over the limit
+        let newest = finished_run(pool, 1, 2 * 1024 * 1024).await;
+
+        let report = apply_retention(pool).await.unwrap();
+        assert_eq!(report.deleted_runs, [between, over.clone()]);
+        assert_eq!(report.freed_bytes, 800 * 1024);
+        assert!(report.errors.is_empty());
+        assert!(!artifacts::run_dir(&over).exists());
+        assert!(artifacts::run_dir(&newest).exists());
+        assert_eq!(recorded(pool).await, [newest]);
+    }
+
+    #[tokio::test]
+    async fn sweeps_artifacts_of_runs_no_longer_recorded() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        let run = finished_run(pool, 1, 10).await;
+        let orphan = uuid::Uuid::new_v4().to_string();
+        std::fs::create_dir_all(artifacts::test_output_dir(&orphan)).unwrap();
+        let other = artifacts::runs_root().join("not-a-run");
+        std::fs::create_dir_all(&other).unwrap();
+
+        let report = apply_retention(pool).await.unwrap();
+        assert!(report.deleted_runs.is_empty());
+        assert_eq!(report.removed_orphans, 1);
+        assert!(!artifacts::run_dir(&orphan).exists());
+        assert!(artifacts::run_dir(&run).exists());
+        assert!(other.exists());
+    }
+
+    #[tokio::test]
+    async fn deleting_an_unknown_run_touches_nothing() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        let run = finished_run(pool, 1, 10).await;
+        let unknown = uuid::Uuid::new_v4().to_string();
+        assert!(delete_run(pool, &unknown).await.is_err());
+        assert!(delete_run(pool, "..").await.is_err());
+        assert!(artifacts::run_dir(&run).exists());
+
+        delete_run(pool, &run).await.unwrap();
+        assert!(!artifacts::run_dir(&run).exists());
+        assert!(recorded(pool).await.is_empty());
+    }
+}
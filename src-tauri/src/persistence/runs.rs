@@ -1,2 +1,287 @@
-// Run persistence is handled by the frontend via @tauri-apps/plugin-sql.
-// SQL query strings are kept here as reference for the frontend db.ts module.
+use crate::models::run::{
+    BuildDiagnostic, RunStatus, TargetResult, TestCase, TestRun, TestStatus,
+};
+use crate::persistence::quarantine::ACTIVE;
+use crate::persistence::tests::{self, TestKey};
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+
+#[derive(sqlx::FromRow)]
+struct RunRow {
+    id: String,
+    status: String,
+    project_path: String,
+    project_id: Option<String>,
+    scope: String,
+    started_at: String,
+    finished_at: Option<String>,
+    duration_ms: Option<i64>,
+    total_tests: Option<i32>,
+    passed_tests: Option<i32>,
+    failed_tests: Option<i32>,
+    skipped_tests: Option<i32>,
+    raw_log: Option<String>,
+    target_results: Option<String>,
+    git_commit: Option<String>,
+    git_branch: Option<String>,
+    quarantined_failures: i32,
+    pinned: bool,
+}
+
+impl From<RunRow> for TestRun {
+    fn from(row: RunRow) -> Self {
+        Self {
+            id: row.id,
+            status: RunStatus::from_str(&row.status),
+            project_path: row.project_path,
+            project_id: row.project_id,
+            scope: row.scope,
+            started_at: row.started_at,
+            finished_at: row.finished_at,
+            duration_ms: row.duration_ms,
+            total_tests: row.total_tests.unwrap_or(0),
+            passed_tests: row.passed_tests.unwrap_or(0),
+            failed_tests: row.failed_tests.unwrap_or(0),
+            skipped_tests: row.skipped_tests.unwrap_or(0),
+            raw_log: row.raw_log,
+            // Unreadable JSON is treated as missing, like runs recorded before target results
+            target_results: row
+                .target_results
+                .filter(|json| !json.is_empty())
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            git_commit: row.git_commit,
+            git_branch: row.git_branch,
+            quarantined_failures: row.quarantined_failures,
+            pinned: row.pinned,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TestCaseRow {
+    id: i64,
+    run_id: String,
+    suite_name: String,
+    test_name: String,
+    status: String,
+    duration_ms: Option<i64>,
+    failure_message: Option<String>,
+    file_path: Option<String>,
+    line_number: Option<i32>,
+    crash_report_path: Option<String>,
+    status_reason: Option<String>,
+    target_key: Option<String>,
+    test_id: Option<i64>,
+    target_name: Option<String>,
+    quarantined: bool,
+}
+
+impl From<TestCaseRow> for TestCase {
+    fn from(row: TestCaseRow) -> Self {
+        Self {
+            id: Some(row.id),
+            run_id: row.run_id,
+            suite_name: row.suite_name,
+            test_name: row.test_name,
+            status: TestStatus::from_str(&row.status),
+            duration_ms: row.duration_ms,
+            failure_message: row.failure_message,
+            file_path: row.file_path,
+            line_number: row.line_number,
+            crash_report_path: row.crash_report_path,
+            status_reason: row.status_reason,
+            target_key: row.target_key,
+            test_id: row.test_id,
+            // Cases recorded without a target have an empty one on their test
+            target_name: row.target_name.filter(|t| !t.is_empty()),
+            quarantined: row.quarantined,
+        }
+    }
+}
+
+fn target_results_json(results: Option<&[TargetResult]>) -> Result<Option<String>, String> {
+    results
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())
+}
+
+/// Record a run as it starts.
+pub async fn insert_run(pool: &SqlitePool, run: &TestRun) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO test_runs (id, status, project_path, project_id, scope, started_at, git_commit, git_branch) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(&run.id)
+    .bind(run.status.to_string())
+    .bind(&run.project_path)
+    .bind(&run.project_id)
+    .bind(&run.scope)
+    .bind(&run.started_at)
+    .bind(&run.git_commit)
+    .bind(&run.git_branch)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to record run: {}", e))?;
+    Ok(())
+}
+
+/// Store a finished run's status, counts, log and target results.
+pub async fn complete_run(pool: &SqlitePool, run: &TestRun) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE test_runs SET status=$1, finished_at=$2, duration_ms=$3, total_tests=$4, passed_tests=$5, failed_tests=$6, skipped_tests=$7, raw_log=$8, target_results=$9, quarantined_failures=$10 WHERE id=$11",
+    )
+    .bind(run.status.to_string())
+    .bind(&run.finished_at)
+    .bind(run.duration_ms)
+    .bind(run.total_tests)
+    .bind(run.passed_tests)
+    .bind(run.failed_tests)
+    .bind(run.skipped_tests)
+    .bind(&run.raw_log)
+    .bind(target_results_json(run.target_results.as_deref())?)
+    .bind(run.quarantined_failures)
+    .bind(&run.id)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to record run completion: {}", e))?;
+    Ok(())
+}
+
+/// Insert a test case, or update row `id` when the case was written before (a result bundle
+/// or crash report filled it in since), linked to its test in `project_id` and flagged if that
+/// test is quarantined. Returns the row id.
+pub async fn save_test_case(
+    conn: &mut SqliteConnection,
+    project_id: &str,
+    id: Option<i64>,
+    tc: &TestCase,
+) -> Result<i64, String> {
+    let key = TestKey::new(tc.target_name.as_deref(), &tc.suite_name, &tc.test_name);
+    let test_id = tests::resolve_test_id(conn, project_id, &key).await?;
+    // Only Swift Testing output names tests `func()`
+    if tc.test_name.ends_with("()") {
+        tests::mark_swift_testing(conn, test_id).await?;
+    }
+    let quarantined =
+        format!("EXISTS (SELECT 1 FROM quarantined_tests q WHERE q.test_id = $12 AND {ACTIVE})");
+    let query = match id {
+        Some(_) => format!("UPDATE test_cases SET run_id=$1, suite_name=$2, test_name=$3, status=$4, duration_ms=$5, failure_message=$6, file_path=$7, line_number=$8, crash_report_path=$9, status_reason=$10, target_key=$11, test_id=$12, quarantined={quarantined} WHERE id=$13"),
+        // id is NULL here, so SQLite assigns one
+        None => format!("INSERT INTO test_cases (run_id, suite_name, test_name, status, duration_ms, failure_message, file_path, line_number, crash_report_path, status_reason, target_key, test_id, id, quarantined) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, {quarantined})"),
+    };
+    let result = sqlx::query(&query)
+        .bind(&tc.run_id)
+        .bind(&tc.suite_name)
+        .bind(&tc.test_name)
+        .bind(tc.status.to_string())
+        .bind(tc.duration_ms)
+        .bind(&tc.failure_message)
+        .bind(&tc.file_path)
+        .bind(tc.line_number)
+        .bind(&tc.crash_report_path)
+        .bind(&tc.status_reason)
+        .bind(&tc.target_key)
+        .bind(test_id)
+        .bind(id)
+        .execute(conn)
+        .await
+        .map_err(|e| format!("Failed to record test case: {}", e))?;
+    Ok(id.unwrap_or_else(|| result.last_insert_rowid()))
+}
+
+/// Record a diagnostic reported while building target `target_key` of run `run_id`.
+pub async fn save_build_diagnostic(
+    conn: &mut SqliteConnection,
+    run_id: &str,
+    target_key: &str,
+    diagnostic: &BuildDiagnostic,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO build_diagnostics (run_id, target_key, severity, category, file_path, line_number, column_number, message) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+    )
+    .bind(run_id)
+    .bind(target_key)
+    .bind(diagnostic.severity.to_string())
+    .bind(diagnostic.category.to_string())
+    .bind(&diagnostic.file_path)
+    .bind(diagnostic.line)
+    .bind(diagnostic.column)
+    .bind(&diagnostic.message)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("Failed to record build diagnostic: {}", e))?;
+    Ok(())
+}
+
+/// Store a running run's target results so far and append `log_lines` to its log; `None`
+/// keeps the stored value.
+pub async fn save_run_progress(
+    conn: &mut SqliteConnection,
+    run_id: &str,
+    target_results: Option<&[TargetResult]>,
+    log_lines: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        "UPDATE test_runs SET target_results = COALESCE($1, target_results), raw_log = CASE WHEN $2 IS NULL THEN raw_log ELSE COALESCE(raw_log, '') || $2 END WHERE id = $3",
+    )
+    .bind(target_results_json(target_results)?)
+    .bind(log_lines)
+    .bind(run_id)
+    .execute(conn)
+    .await
+    .map_err(|e| format!("Failed to record run progress: {}", e))?;
+    Ok(())
+}
+
+/// Mark runs left `running` by an app that quit or crashed as interrupted, with counts from
+/// the test cases they recorded. Returns how many runs were marked.
+pub async fn mark_interrupted_runs(pool: &SqlitePool) -> Result<u64, String> {
+    let result = sqlx::query(
+        "UPDATE test_runs SET status = 'interrupted',
+            total_tests = (SELECT COUNT(*) FROM test_cases WHERE run_id = test_runs.id),
+            passed_tests = (SELECT COUNT(*) FROM test_cases WHERE run_id = test_runs.id AND status = 'passed'),
+            failed_tests = (SELECT COUNT(*) FROM test_cases WHERE run_id = test_runs.id AND status IN ('failed', 'crashed', 'timed_out') AND quarantined = 0),
+            quarantined_failures = (SELECT COUNT(*) FROM test_cases WHERE run_id = test_runs.id AND status IN ('failed', 'crashed', 'timed_out') AND quarantined = 1),
+            skipped_tests = (SELECT COUNT(*) FROM test_cases WHERE run_id = test_runs.id AND status = 'skipped')
+         WHERE status = 'running'",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to mark interrupted runs: {}", e))?;
+    Ok(result.rows_affected())
+}
+
+/// Most recent runs first, optionally only those of one project.
+pub async fn list_runs(
+    pool: &SqlitePool,
+    limit: i64,
+    project_id: Option<&str>,
+) -> Result<Vec<TestRun>, String> {
+    let rows: Vec<RunRow> = sqlx::query_as(
+        "SELECT * FROM test_runs WHERE ($1 IS NULL OR project_id = $1) ORDER BY started_at DESC LIMIT $2",
+    )
+    .bind(project_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(TestRun::from).collect())
+}
+
+pub async fn get_run(pool: &SqlitePool, id: &str) -> Result<Option<TestRun>, String> {
+    let row: Option<RunRow> = sqlx::query_as("SELECT * FROM test_runs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(row.map(TestRun::from))
+}
+
+pub async fn get_test_cases(pool: &SqlitePool, run_id: &str) -> Result<Vec<TestCase>, String> {
+    let rows: Vec<TestCaseRow> =
+        sqlx::query_as("SELECT tc.*, t.target_name FROM test_cases tc LEFT JOIN tests t ON t.id = tc.test_id WHERE tc.run_id = $1 ORDER BY tc.suite_name, tc.test_name")
+            .bind(run_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(rows.into_iter().map(TestCase::from).collect())
+}
@@ -0,0 +1,53 @@
+use crate::execution::artifacts;
+use crate::models::run::{RunStatus, TestRun};
+use crate::persistence::db;
+use sqlx::sqlite::SqlitePool;
+use std::path::PathBuf;
+
+/// A migrated database in its own temporary directory, removed when dropped. Artifacts of
+/// the current thread's runs go to the same directory.
+pub struct TestDb {
+    pub pool: SqlitePool,
+    dir: PathBuf,
+}
+
+impl TestDb {
+    pub async fn new() -> Self {
+        let dir =
+            std::env::temp_dir().join(format!("xcode-test-runner-test-{}", uuid::Uuid::new_v4()));
+        let pool = db::connect(&dir).await.unwrap();
+        artifacts::set_test_root(Some(dir.join("artifacts")));
+        Self { pool, dir }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        artifacts::set_test_root(None);
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A run of no project that started at `started_at`, not yet finished.
+pub fn run(id: &str, started_at: &str) -> TestRun {
+    TestRun {
+        id: id.to_string(),
+        status: RunStatus::Running,
+        project_path: "/tmp/App.xcodeproj".to_string(),
+        project_id: None,
+        scope: "all".to_string(),
+        started_at: started_at.to_string(),
+        finished_at: None,
+        duration_ms: None,
+        total_tests: 0,
+        passed_tests: 0,
+        failed_tests: 0,
+        skipped_tests: 0,
+        raw_log: None,
+        target_results: None,
+        git_commit: None,
+        git_branch: None,
+        quarantined_failures: 0,
+        pinned: false,
+    }
+}
@@ -0,0 +1,285 @@
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+
+/// A test's identity across runs. Output and result bundles name the same test differently:
+/// xcodebuild prints Swift classes as `Module.Class` and methods as `testFoo`, bundles report
+/// target `Module`, class `Class` and `testFoo()`. Both normalize to the same key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestKey {
+    /// Test target (module); empty when neither the bundle nor the suite name revealed it.
+    pub target_name: String,
+    pub suite_name: String,
+    pub test_name: String,
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+impl TestKey {
+    pub fn new(target_name: Option<&str>, suite_name: &str, test_name: &str) -> Self {
+        let test_name = test_name.strip_suffix("()").unwrap_or(test_name);
+        let (target_name, suite_name) = match target_name.filter(|t| !t.is_empty()) {
+            Some(target) => {
+                let suite = suite_name
+                    .strip_prefix(target)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .unwrap_or(suite_name);
+                (target, suite)
+            }
+            None => match suite_name.split_once('.') {
+                Some((module, suite)) if is_identifier(module) && !suite.is_empty() => {
+                    (module, suite)
+                }
+                _ => ("", suite_name),
+            },
+        };
+        Self {
+            target_name: target_name.to_string(),
+            suite_name: suite_name.to_string(),
+            test_name: test_name.to_string(),
+        }
+    }
+}
+
+/// Row id of the test `key` in project `project_id` ('' for runs without a project), created
+/// if needed. A key without a target matches the project's only test of that suite and name;
+/// a key with one takes over the target-less row recorded before the target was known.
+pub async fn resolve_test_id(
+    conn: &mut SqliteConnection,
+    project_id: &str,
+    key: &TestKey,
+) -> Result<i64, String> {
+    let candidates: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT id, target_name FROM tests WHERE project_id = $1 AND suite_name = $2 AND test_name = $3",
+    )
+    .bind(project_id)
+    .bind(&key.suite_name)
+    .bind(&key.test_name)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if let Some((id, _)) = candidates.iter().find(|(_, t)| *t == key.target_name) {
+        return Ok(*id);
+    }
+    if key.target_name.is_empty() {
+        if let [(id, _)] = candidates.as_slice() {
+            return Ok(*id);
+        }
+    } else if let Some((id, _)) = candidates.iter().find(|(_, t)| t.is_empty()) {
+        sqlx::query("UPDATE tests SET target_name = $1 WHERE id = $2")
+            .bind(&key.target_name)
+            .bind(id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        return Ok(*id);
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO tests (project_id, target_name, suite_name, test_name) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(project_id)
+    .bind(&key.target_name)
+    .bind(&key.suite_name)
+    .bind(&key.test_name)
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| format!("Failed to record test: {}", e))?;
+    Ok(result.last_insert_rowid())
+}
+
+/// Record that test `test_id` is a Swift Testing function.
+pub async fn mark_swift_testing(conn: &mut SqliteConnection, test_id: i64) -> Result<(), String> {
+    sqlx::query("UPDATE tests SET swift_testing = 1 WHERE id = $1 AND swift_testing = 0")
+        .bind(test_id)
+        .execute(conn)
+        .await
+        .map_err(|e| format!("Failed to record test: {}", e))?;
+    Ok(())
+}
+
+/// Link test cases without a test id to their test, oldest runs first.
+pub async fn backfill_test_ids(pool: &SqlitePool) -> Result<(), String> {
+    let cases: Vec<(i64, Option<String>, String, String)> = sqlx::query_as(
+        "SELECT tc.id, r.project_id, tc.suite_name, tc.test_name FROM test_cases tc JOIN test_runs r ON r.id = tc.run_id WHERE tc.test_id IS NULL ORDER BY r.started_at, tc.id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    if cases.is_empty() {
+        return Ok(());
+    }
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for (case_id, project_id, suite_name, test_name) in cases {
+        let key = TestKey::new(None, &suite_name, &test_name);
+        let test_id = resolve_test_id(&mut tx, project_id.as_deref().unwrap_or(""), &key).await?;
+        sqlx::query("UPDATE test_cases SET test_id = $1 WHERE id = $2")
+            .bind(test_id)
+            .bind(case_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to link test case: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test_keys {
+    use super::*;
+    use crate::persistence::runs;
+    use crate::persistence::testing::{self, TestDb};
+
+    fn key(target: &str, suite: &str, name: &str) -> TestKey {
+        TestKey {
+            target_name: target.to_string(),
+            suite_name: suite.to_string(),
+            test_name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn output_and_bundle_names_share_a_key() {
+        let from_output = TestKey::new(None, "AppTests.LoginTests", "testValid");
+        let from_bundle = TestKey::new(Some("AppTests"), "LoginTests", "testValid()");
+        assert_eq!(from_output, key("AppTests", "LoginTests", "testValid"));
+        assert_eq!(from_bundle, from_output);
+        // A bundle suite may still carry the module
+        assert_eq!(
+            TestKey::new(Some("AppTests"), "AppTests.LoginTests", "testValid"),
+            from_output
+        );
+    }
+
+    #[test]
+    fn keys_without_a_module_have_no_target() {
+        assert_eq!(
+            TestKey::new(None, "LoginTests", "testValid"),
+            key("", "LoginTests", "testValid")
+        );
+        assert_eq!(
+            TestKey::new(None, "", "identity()"),
+            key("", "", "identity")
+        );
+        assert_eq!(
+            TestKey::new(Some("AppTests"), "", "identity()"),
+            key("AppTests", "", "identity")
+        );
+        // Not a module: display names and empty parts stay in the suite
+        assert_eq!(
+            TestKey::new(None, "\"Matrix math\".Inner", "identity()"),
+            key("", "\"Matrix math\".Inner", "identity")
+        );
+        assert_eq!(
+            TestKey::new(None, "AppTests.", "testValid"),
+            key("", "AppTests.", "testValid")
+        );
+    }
+
+    #[tokio::test]
+    async fn a_key_with_a_target_adopts_the_target_less_row() {
+        let db = TestDb::new().await;
+        let mut conn = db.pool.acquire().await.unwrap();
+        let without = resolve_test_id(&mut conn, "", &key("", "LoginTests", "testValid"))
+            .await
+            .unwrap();
+        // Later keys with and without the target resolve to the same row
+        let with = resolve_test_id(&mut conn, "", &key("AppTests", "LoginTests", "testValid"))
+            .await
+            .unwrap();
+        assert_eq!(with, without);
+        let target: String = sqlx::query_scalar("SELECT target_name FROM tests WHERE id = $1")
+            .bind(with)
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        assert_eq!(target, "AppTests");
+        let again = resolve_test_id(&mut conn, "", &key("", "LoginTests", "testValid"))
+            .await
+            .unwrap();
+        assert_eq!(again, with);
+
+        // Once two targets have the test, a key without one cannot pick either
+        let other = resolve_test_id(&mut conn, "", &key("OtherTests", "LoginTests", "testValid"))
+            .await
+            .unwrap();
+        assert_ne!(other, with);
+        let ambiguous = resolve_test_id(&mut conn, "", &key("", "LoginTests", "testValid"))
+            .await
+            .unwrap();
+        assert!(![with, other].contains(&ambiguous));
+
+        // Tests of another project are separate
+        let elsewhere = resolve_test_id(
+            &mut conn,
+            "project-2",
+            &key("AppTests", "LoginTests", "testValid"),
+        )
+        .await
+        .unwrap();
+        assert!(![with, other, ambiguous].contains(&elsewhere));
+    }
+
+    #[tokio::test]
+    async fn backfill_links_cases_to_their_tests() {
+        let db = TestDb::new().await;
+        let pool = &db.pool;
+        runs::insert_run(pool, &testing::run("run-1", "2026-01-11T10:00:00.000Z"))
+            .await
+            .unwrap();
+        runs::insert_run(pool, &testing::run("run-2", "2026-01-12T10:00:00.000Z"))
+            .await
+            .unwrap();
+        for (run_id, suite, name) in [
+            ("run-2", "LoginTests", "testValid"),
+            ("run-1", "AppTests.LoginTests", "testValid"),
+            ("run-1", "AppTests.LoginTests", "testInvalid"),
+        ] {
+            sqlx::query(
+                "INSERT INTO test_cases (run_id, suite_name, test_name, status) VALUES ($1, $2, $3, 'passed')",
+            )
+            .bind(run_id)
+            .bind(suite)
+            .bind(name)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+
+        backfill_test_ids(pool).await.unwrap();
+
+        let linked: Vec<(String, String, Option<i64>)> = sqlx::query_as(
+            "SELECT tc.run_id, tc.test_name, tc.test_id FROM test_cases tc ORDER BY tc.id",
+        )
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        assert!(linked.iter().all(|(_, _, id)| id.is_some()));
+        // The older run named the module, so the newer run's case joins its test
+        assert_eq!(linked[0].2, linked[1].2);
+        assert_ne!(linked[1].2, linked[2].2);
+        let tests: Vec<(String, String, String)> =
+            sqlx::query_as("SELECT target_name, suite_name, test_name FROM tests ORDER BY id")
+                .fetch_all(pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            tests,
+            [
+                (
+                    "AppTests".to_string(),
+                    "LoginTests".to_string(),
+                    "testValid".to_string()
+                ),
+                (
+                    "AppTests".to_string(),
+                    "LoginTests".to_string(),
+                    "testInvalid".to_string()
+                ),
+            ]
+        );
+    }
+}
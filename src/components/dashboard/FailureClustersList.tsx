@@ -0,0 +1,60 @@
+import { useNavigate } from "react-router-dom";
+import Badge from "../common/Badge";
+import { formatTimestamp, pluralize } from "../../lib/formatters";
+import type { FailureCluster } from "../../lib/db";
+
+interface FailureClustersListProps {
+  clusters: FailureCluster[];
+}
+
+export default function FailureClustersList({ clusters }: FailureClustersListProps) {
+  const navigate = useNavigate();
+
+  if (clusters.length === 0) {
+    return (
+      <div className="card muted" style={{ textAlign: "center", padding: "28px 20px" }}>
+        No failure recurs across recent runs.
+      </div>
+    );
+  }
+
+  return (
+    <div className="list">
+      {clusters.map((cluster) => {
+        const latest = cluster.failures[0];
+        const location = cluster.file_name
+          ? `${cluster.file_name}${cluster.line_number != null ? `:${cluster.line_number}` : ""}`
+          : null;
+        return (
+          <div
+            key={cluster.signature}
+            role="button"
+            onClick={() => latest && navigate(`/run/${latest.run_id}`)}
+            className="list-item"
+            title={cluster.representative_messages.join("\n")}
+          >
+            <div className="flex items-center gap-3 min-w-0">
+              <Badge variant="danger">{cluster.count}</Badge>
+              <span className="truncate" style={{ color: "var(--text-primary)", fontWeight: 500 }}>
+                {cluster.representative_messages[0] ?? cluster.normalized_message}
+              </span>
+            </div>
+
+            <div className="flex items-center gap-4" style={{ color: "var(--text-secondary)", fontSize: 12 }}>
+              <span>
+                {[
+                  cluster.assertion,
+                  location,
+                  `${cluster.tests} ${pluralize(cluster.tests, "test")} in ${cluster.runs} ${pluralize(cluster.runs, "run")}`,
+                ]
+                  .filter(Boolean)
+                  .join(" · ")}
+              </span>
+              <span className="muted">{formatTimestamp(cluster.last_seen_at)}</span>
+            </div>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
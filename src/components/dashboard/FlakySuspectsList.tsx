@@ -0,0 +1,80 @@
+import { useNavigate } from "react-router-dom";
+import Badge from "../common/Badge";
+import { formatTimestamp, pluralize } from "../../lib/formatters";
+import type { FlakyTest, TestInfo } from "../../lib/db";
+
+interface FlakySuspectsListProps {
+  tests: FlakyTest[];
+  /** Ids of tests already quarantined. */
+  quarantinedIds: Set<number>;
+  onQuarantine: (test: TestInfo) => void;
+}
+
+export default function FlakySuspectsList({
+  tests,
+  quarantinedIds,
+  onQuarantine,
+}: FlakySuspectsListProps) {
+  const navigate = useNavigate();
+
+  if (tests.length === 0) {
+    return (
+      <div className="card muted" style={{ textAlign: "center", padding: "28px 20px" }}>
+        No flaky suspects in recent runs.
+      </div>
+    );
+  }
+
+  return (
+    <div className="list">
+      {tests.map((flaky) => {
+        const latest = flaky.evidence[0];
+        const notes = [
+          `${flaky.failures}/${flaky.runs} runs failed`,
+          flaky.retry_passes > 0 &&
+            `${flaky.retry_passes} ${pluralize(flaky.retry_passes, "pass", "passes")} on retry`,
+          flaky.isolated_failures > 0 &&
+            `${flaky.isolated_failures} ${pluralize(flaky.isolated_failures, "failure")} on otherwise green runs`,
+        ].filter(Boolean);
+        return (
+          <div
+            key={flaky.test.id}
+            role="button"
+            onClick={() => latest && navigate(`/run/${latest.run_id}`)}
+            className="list-item"
+            title={latest?.failure_message ?? undefined}
+          >
+            <div className="flex items-center gap-3 min-w-0">
+              <Badge variant={flaky.score >= 0.5 ? "danger" : "warning"}>
+                {Math.round(flaky.score * 100)}%
+              </Badge>
+              <span className="truncate" style={{ color: "var(--text-primary)", fontWeight: 500 }}>
+                {flaky.test.suite_name}.{flaky.test.test_name}
+              </span>
+            </div>
+
+            <div className="flex items-center gap-4" style={{ color: "var(--text-secondary)", fontSize: 12 }}>
+              <span>{notes.join(" · ")}</span>
+              {latest && <span className="muted">{formatTimestamp(latest.started_at)}</span>}
+              {quarantinedIds.has(flaky.test.id) ? (
+                <span style={{ color: "var(--warning)" }}>Quarantined</span>
+              ) : (
+                <button
+                  type="button"
+                  className="btn"
+                  style={{ fontSize: 12 }}
+                  onClick={(e) => {
+                    e.stopPropagation();
+                    onQuarantine(flaky.test);
+                  }}
+                >
+                  Quarantine
+                </button>
+              )}
+            </div>
+          </div>
+        );
+      })}
+    </div>
+  );
+}
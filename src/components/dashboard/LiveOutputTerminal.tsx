@@ -1,4 +1,5 @@
 import { useEffect, useMemo, useRef, useState } from "react";
+import { parseAnsi } from "../../lib/ansi";
 import type { OutputLine } from "../../stores/executionStore";
 
 interface LiveOutputTerminalProps {
@@ -84,7 +85,19 @@ export default function LiveOutputTerminal({ lines }: LiveOutputTerminalProps) {
                         : "var(--text-primary)",
                 }}
               >
-                {line.text}
+                {line.raw
+                  ? parseAnsi(line.raw).map((span, spanIndex) => (
+                      <span
+                        key={spanIndex}
+                        style={{
+                          color: span.color ?? undefined,
+                          fontWeight: span.bold ? 700 : undefined,
+                        }}
+                      >
+                        {span.text}
+                      </span>
+                    ))
+                  : line.text}
               </span>
             </div>
           ))
@@ -0,0 +1,65 @@
+import Badge from "../common/Badge";
+import type { QuarantinedTest } from "../../lib/db";
+
+interface QuarantineListProps {
+  quarantined: QuarantinedTest[];
+  onEdit: (quarantine: QuarantinedTest) => void;
+  onRelease: (testId: number) => void;
+}
+
+export default function QuarantineList({
+  quarantined,
+  onEdit,
+  onRelease,
+}: QuarantineListProps) {
+  if (quarantined.length === 0) {
+    return (
+      <div className="card muted" style={{ textAlign: "center", padding: "28px 20px" }}>
+        No quarantined tests.
+      </div>
+    );
+  }
+
+  return (
+    <div className="list">
+      {quarantined.map((q) => (
+        <div key={q.test.id} className="list-item" style={{ cursor: "default" }}>
+          <div className="flex items-center gap-3 min-w-0">
+            <Badge variant={q.expired ? "danger" : "warning"}>
+              {q.expired ? "expired" : q.mode === "skip" ? "skipped" : "quarantined"}
+            </Badge>
+            <div className="min-w-0">
+              <div className="truncate" style={{ color: "var(--text-primary)", fontWeight: 500 }}>
+                {q.test.suite_name}.{q.test.test_name}
+              </div>
+              <div className="muted truncate" style={{ fontSize: 12 }}>
+                {[
+                  q.reason,
+                  q.owner && `owner: ${q.owner}`,
+                  q.expires_at &&
+                    (q.expired ? `expired ${q.expires_at}, review` : `until ${q.expires_at}`),
+                ]
+                  .filter(Boolean)
+                  .join(" · ")}
+              </div>
+            </div>
+          </div>
+
+          <div className="flex items-center gap-2">
+            <button type="button" className="btn" style={{ fontSize: 12 }} onClick={() => onEdit(q)}>
+              {q.expired ? "Extend" : "Edit"}
+            </button>
+            <button
+              type="button"
+              className="btn"
+              style={{ fontSize: 12 }}
+              onClick={() => onRelease(q.test.id)}
+            >
+              Release
+            </button>
+          </div>
+        </div>
+      ))}
+    </div>
+  );
+}
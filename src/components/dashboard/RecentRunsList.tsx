@@ -44,6 +44,7 @@ export default function RecentRunsList({ runs }: RecentRunsListProps) {
           </div>
 
           <div className="flex items-center gap-4" style={{ color: "var(--text-secondary)", fontSize: 12 }}>
+            {run.pinned && <span>Pinned</span>}
             <span>{run.total_tests} tests</span>
             {run.duration_ms != null && <span>{formatDuration(run.duration_ms)}</span>}
             <span className="muted">{formatTimestamp(run.started_at)}</span>
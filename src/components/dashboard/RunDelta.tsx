@@ -0,0 +1,52 @@
+import { formatDuration } from "../../lib/formatters";
+import type { RunComparison } from "../../lib/db";
+
+interface RunDeltaProps {
+  comparison: RunComparison;
+}
+
+export default function RunDelta({ comparison }: RunDeltaProps) {
+  const counts = [
+    { label: "newly failing", value: comparison.newly_failing.length, color: "var(--danger)" },
+    { label: "fixed", value: comparison.fixed.length, color: "var(--success)" },
+    { label: "still failing", value: comparison.still_failing.length, color: "var(--text-secondary)" },
+    { label: "added", value: comparison.added.length, color: "var(--text-secondary)" },
+    { label: "removed", value: comparison.removed.length, color: "var(--text-secondary)" },
+  ];
+  const slower = comparison.duration_changes.filter((c) => c.delta_ms > 0).slice(0, 3);
+
+  return (
+    <div className="card stack" style={{ gap: 10 }}>
+      <div className="flex gap-4 flex-wrap" style={{ fontSize: 13 }}>
+        {counts.map((count) => (
+          <span key={count.label} style={{ color: count.value > 0 ? count.color : "var(--text-tertiary)" }}>
+            <strong>{count.value}</strong> {count.label}
+          </span>
+        ))}
+      </div>
+      {comparison.newly_failing.length > 0 && (
+        <div className="stack" style={{ gap: 4, fontSize: 12 }}>
+          {comparison.newly_failing.slice(0, 5).map((change) => (
+            <div key={change.test.id} className="truncate" title={change.failure_message ?? undefined}>
+              <span style={{ color: "var(--danger)" }}>✗</span>{" "}
+              <span style={{ color: "var(--text-primary)" }}>
+                {change.test.suite_name}.{change.test.test_name}
+              </span>
+            </div>
+          ))}
+        </div>
+      )}
+      {slower.length > 0 && (
+        <div className="muted" style={{ fontSize: 12 }}>
+          Slower:{" "}
+          {slower
+            .map(
+              (c) =>
+                `${c.test.test_name} ${formatDuration(c.base_duration_ms)} → ${formatDuration(c.head_duration_ms)}`,
+            )
+            .join(" · ")}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,142 @@
+import { useState } from "react";
+import type { QuarantineMode, QuarantinedTest, TestInfo } from "../../lib/db";
+
+interface QuarantineDialogProps {
+  test: TestInfo;
+  /** The test's current quarantine, when editing one. */
+  existing?: QuarantinedTest;
+  onSubmit: (quarantine: {
+    testId: number;
+    mode: QuarantineMode;
+    reason: string;
+    owner: string | null;
+    expiresAt: string | null;
+  }) => Promise<void>;
+  onClose: () => void;
+}
+
+export default function QuarantineDialog({
+  test,
+  existing,
+  onSubmit,
+  onClose,
+}: QuarantineDialogProps) {
+  const [mode, setMode] = useState<QuarantineMode>(existing?.mode ?? "run");
+  const [reason, setReason] = useState(existing?.reason ?? "");
+  const [owner, setOwner] = useState(existing?.owner ?? "");
+  const [expiresAt, setExpiresAt] = useState(existing?.expires_at ?? "");
+  const [error, setError] = useState<string | null>(null);
+
+  const handleSubmit = async () => {
+    try {
+      await onSubmit({
+        testId: test.id,
+        mode,
+        reason: reason.trim(),
+        owner: owner.trim() || null,
+        expiresAt: expiresAt || null,
+      });
+      onClose();
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
+  return (
+    <>
+      <div
+        style={{
+          position: "fixed",
+          inset: 0,
+          backgroundColor: "rgba(0,0,0,0.4)",
+          zIndex: 99,
+        }}
+        onClick={onClose}
+        aria-hidden
+      />
+      <div
+        className="card"
+        style={{
+          position: "fixed",
+          inset: 0,
+          margin: "auto",
+          maxWidth: 420,
+          height: "fit-content",
+          maxHeight: "80vh",
+          zIndex: 100,
+          display: "flex",
+          flexDirection: "column",
+          gap: 16,
+        }}
+      >
+        <h3 className="section-title">
+          Quarantine {test.suite_name}.{test.test_name}
+        </h3>
+        <label style={{ display: "flex", flexDirection: "column", gap: 4 }}>
+          <span className="muted" style={{ fontSize: 12 }}>
+            While quarantined
+          </span>
+          <select
+            value={mode}
+            onChange={(e) => setMode(e.target.value as QuarantineMode)}
+            style={{ padding: 8, borderRadius: 6 }}
+          >
+            <option value="run">Run it, but don't fail runs on its failures</option>
+            <option value="skip">Skip it</option>
+          </select>
+        </label>
+        <label style={{ display: "flex", flexDirection: "column", gap: 4 }}>
+          <span className="muted" style={{ fontSize: 12 }}>
+            Reason
+          </span>
+          <input
+            type="text"
+            value={reason}
+            onChange={(e) => setReason(e.target.value)}
+            placeholder="e.g. Times out on CI simulators"
+            style={{ padding: 8, borderRadius: 6 }}
+            autoFocus
+          />
+        </label>
+        <label style={{ display: "flex", flexDirection: "column", gap: 4 }}>
+          <span className="muted" style={{ fontSize: 12 }}>
+            Owner
+          </span>
+          <input
+            type="text"
+            value={owner}
+            onChange={(e) => setOwner(e.target.value)}
+            style={{ padding: 8, borderRadius: 6 }}
+          />
+        </label>
+        <label style={{ display: "flex", flexDirection: "column", gap: 4 }}>
+          <span className="muted" style={{ fontSize: 12 }}>
+            Expires after
+          </span>
+          <input
+            type="date"
+            value={expiresAt}
+            onChange={(e) => setExpiresAt(e.target.value)}
+            style={{ padding: 8, borderRadius: 6 }}
+          />
+        </label>
+        {error && (
+          <div style={{ color: "var(--danger)", fontSize: 12 }}>{error}</div>
+        )}
+        <div style={{ display: "flex", gap: 8, justifyContent: "flex-end" }}>
+          <button type="button" className="btn" onClick={onClose}>
+            Cancel
+          </button>
+          <button
+            type="button"
+            className="btn btn-primary"
+            onClick={handleSubmit}
+            disabled={!reason.trim()}
+          >
+            Quarantine
+          </button>
+        </div>
+      </div>
+    </>
+  );
+}
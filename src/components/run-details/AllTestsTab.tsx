@@ -11,6 +11,9 @@ const statusIcon: Record<string, { symbol: string; color: string }> = {
   passed: { symbol: "✓", color: "var(--success)" },
   failed: { symbol: "✗", color: "var(--danger)" },
   skipped: { symbol: "-", color: "var(--text-tertiary)" },
+  crashed: { symbol: "✗", color: "var(--danger)" },
+  timed_out: { symbol: "⏱", color: "var(--danger)" },
+  expected_failure: { symbol: "✓", color: "var(--warning)" },
 };
 
 export default function AllTestsTab({ testCases }: AllTestsTabProps) {
@@ -55,6 +58,14 @@ export default function AllTestsTab({ testCases }: AllTestsTabProps) {
                     <div className="flex items-center gap-2">
                       <span style={{ color: icon.color, fontWeight: 700 }}>{icon.symbol}</span>
                       <span style={{ color: "var(--text-primary)" }}>{testCase.test_name}</span>
+                      {testCase.quarantined && (
+                        <span style={{ fontSize: 12, color: "var(--warning)" }}>quarantined</span>
+                      )}
+                      {testCase.status_reason && (
+                        <span className="muted" style={{ fontSize: 12 }}>
+                          · {testCase.status_reason}
+                        </span>
+                      )}
                     </div>
                     <span className="code muted" style={{ fontSize: 12 }}>
                       {testCase.duration_ms != null ? formatDuration(testCase.duration_ms) : ""}
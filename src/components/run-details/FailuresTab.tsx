@@ -1,13 +1,241 @@
-import type { TestCase } from "../../hooks/useRunHistory";
+import { convertFileSrc } from "@tauri-apps/api/core";
+import { useState } from "react";
+import { useTestOutput } from "../../hooks/useRunHistory";
+import type {
+  BuildDiagnostic,
+  TestAttachment,
+  TestCase,
+} from "../../hooks/useRunHistory";
+import { isFailingStatus } from "../../lib/constants";
+import type { FailureCluster } from "../../lib/db";
+import { formatTargetKey, pluralize } from "../../lib/formatters";
 
 interface FailuresTabProps {
+  runId: string;
   testCases: TestCase[];
+  diagnostics?: BuildDiagnostic[];
+  attachments?: TestAttachment[];
+  /** The run's failures grouped by cause (see `get_failure_clusters`). */
+  clusters?: FailureCluster[];
 }
 
-export default function FailuresTab({ testCases }: FailuresTabProps) {
-  const failures = testCases.filter((testCase) => testCase.status === "failed");
+const IMAGE_UTIS = ["public.png", "public.jpeg", "public.heic"];
 
-  if (failures.length === 0) {
+/** Failure screenshots for a test; identifiers look like "MyTests/testFoo()". */
+function failureScreenshots(testCase: TestCase, attachments: TestAttachment[]) {
+  return attachments.filter((attachment) => {
+    const idx = attachment.test_identifier.lastIndexOf("/");
+    const suite = attachment.test_identifier.slice(0, idx);
+    const name = attachment.test_identifier.slice(idx + 1).replace(/\(\)$/, "");
+    return (
+      attachment.associated_with_failure &&
+      IMAGE_UTIS.includes(attachment.uniform_type_identifier) &&
+      name === testCase.test_name &&
+      (suite === testCase.suite_name || testCase.suite_name.endsWith(`.${suite}`))
+    );
+  });
+}
+
+/** Lines the test printed, loaded on demand. */
+function TestOutput({ runId, testCase }: { runId: string; testCase: TestCase }) {
+  const [open, setOpen] = useState(false);
+  const { data: output, error, isLoading } = useTestOutput(runId, testCase, open);
+
+  return (
+    <div style={{ marginTop: 8, width: "100%" }}>
+      <button className="btn" onClick={() => setOpen(!open)}>
+        {open ? "Hide test output" : "Show test output"}
+      </button>
+      {open && (
+        <pre
+          className="code selectable"
+          style={{
+            marginTop: 8,
+            padding: 10,
+            width: "100%",
+            maxHeight: 320,
+            borderRadius: 10,
+            whiteSpace: "pre-wrap",
+            overflow: "auto",
+            backgroundColor: "var(--bg-active)",
+            fontSize: 12,
+          }}
+        >
+          {isLoading
+            ? "Loading..."
+            : error
+              ? "No output was captured for this test."
+              : output || "The test printed no output."}
+        </pre>
+      )}
+    </div>
+  );
+}
+
+/** One failing test case with its message, location, screenshots and output. */
+function FailureItem({
+  runId,
+  testCase,
+  attachments,
+}: {
+  runId: string;
+  testCase: TestCase;
+  attachments: TestAttachment[];
+}) {
+  return (
+    <div className="list-item" style={{ cursor: "default", alignItems: "start", flexDirection: "column" }}>
+      <div className="flex items-center gap-2" style={{ color: "var(--text-primary)", fontWeight: 600 }}>
+        <span
+          className="inline-block w-2 h-2 rounded-full"
+          style={{
+            backgroundColor: testCase.quarantined ? "var(--warning)" : "var(--danger)",
+          }}
+        />
+        {testCase.suite_name}.{testCase.test_name}
+        {testCase.quarantined && (
+          <span style={{ fontWeight: 400, color: "var(--warning)" }}>· quarantined</span>
+        )}
+        {testCase.status === "crashed" && (
+          <span className="muted" style={{ fontWeight: 400 }}>· crashed</span>
+        )}
+        {testCase.status === "timed_out" && (
+          <span className="muted" style={{ fontWeight: 400 }}>· timed out</span>
+        )}
+      </div>
+
+      {testCase.failure_message && (
+        <pre
+          className="code selectable"
+          style={{
+            marginTop: 8,
+            padding: 10,
+            width: "100%",
+            borderRadius: 10,
+            whiteSpace: "pre-wrap",
+            overflowX: "auto",
+            backgroundColor: "rgba(194, 64, 29, 0.08)",
+            color: "var(--danger)",
+            fontSize: 12,
+          }}
+        >
+          {testCase.failure_message}
+        </pre>
+      )}
+
+      {testCase.file_path && (
+        <div className="muted" style={{ marginTop: 6, fontSize: 12 }}>
+          {testCase.file_path}
+          {testCase.line_number != null ? `:${testCase.line_number}` : ""}
+        </div>
+      )}
+
+      {failureScreenshots(testCase, attachments).map((attachment) => (
+        <img
+          key={attachment.path}
+          src={convertFileSrc(attachment.path)}
+          alt={attachment.name}
+          title={attachment.name}
+          style={{
+            marginTop: 8,
+            maxWidth: 240,
+            borderRadius: 10,
+            border: "1px solid var(--border-color)",
+          }}
+        />
+      ))}
+
+      {testCase.crash_report_path && (
+        <div className="muted selectable" style={{ marginTop: 6, fontSize: 12 }}>
+          Crash report: {testCase.crash_report_path}
+        </div>
+      )}
+
+      <TestOutput runId={runId} testCase={testCase} />
+    </div>
+  );
+}
+
+/** Failures sharing a cause; a single failure is shown as is, several collapse under the cause. */
+function FailureGroup({
+  cluster,
+  failures,
+  renderFailure,
+}: {
+  cluster: FailureCluster;
+  failures: TestCase[];
+  renderFailure: (testCase: TestCase) => React.ReactNode;
+}) {
+  const [open, setOpen] = useState(false);
+
+  if (failures.length <= 1) {
+    return <>{failures.map(renderFailure)}</>;
+  }
+
+  const location = cluster.file_name
+    ? `${cluster.file_name}${cluster.line_number != null ? `:${cluster.line_number}` : ""}`
+    : null;
+
+  return (
+    <div className="list-item" style={{ cursor: "default", alignItems: "start", flexDirection: "column" }}>
+      <div className="flex items-center gap-2" style={{ color: "var(--text-primary)", fontWeight: 600 }}>
+        <span
+          className="inline-block w-2 h-2 rounded-full"
+          style={{ backgroundColor: "var(--danger)" }}
+        />
+        {cluster.assertion ?? "Failure"} · {cluster.tests} {pluralize(cluster.tests, "test")}
+        {location && (
+          <span className="muted" style={{ fontWeight: 400 }}>· {location}</span>
+        )}
+      </div>
+      <pre
+        className="code selectable"
+        style={{
+          marginTop: 8,
+          padding: 10,
+          width: "100%",
+          borderRadius: 10,
+          whiteSpace: "pre-wrap",
+          overflowX: "auto",
+          backgroundColor: "rgba(194, 64, 29, 0.08)",
+          color: "var(--danger)",
+          fontSize: 12,
+        }}
+      >
+        {cluster.representative_messages[0] ?? cluster.normalized_message}
+      </pre>
+      <div className="muted" style={{ marginTop: 6, fontSize: 12 }}>
+        {failures.map((testCase) => `${testCase.suite_name}.${testCase.test_name}`).join(", ")}
+      </div>
+      <button className="btn" style={{ marginTop: 8 }} onClick={() => setOpen(!open)}>
+        {open ? "Hide failures" : `Show ${failures.length} failures`}
+      </button>
+      {open && (
+        <div className="list" style={{ marginTop: 8, width: "100%" }}>
+          {failures.map(renderFailure)}
+        </div>
+      )}
+    </div>
+  );
+}
+
+export default function FailuresTab({
+  runId,
+  testCases,
+  diagnostics = [],
+  attachments = [],
+  clusters = [],
+}: FailuresTabProps) {
+  // Quarantined failures do not fail the run; they are listed after the others
+  const failures = testCases
+    .filter((testCase) => isFailingStatus(testCase.status))
+    .sort((a, b) => Number(a.quarantined) - Number(b.quarantined));
+
+  // Grouping helps once some cause is shared; otherwise every group is a single failure
+  const canGroup = clusters.some((cluster) => cluster.count > 1);
+  const [groupBy, setGroupBy] = useState(true);
+  const grouped = canGroup && groupBy;
+
+  if (failures.length === 0 && diagnostics.length === 0) {
     return (
       <div className="page-scroll">
         <div className="card muted" style={{ textAlign: "center", padding: "28px 20px" }}>
@@ -19,18 +247,20 @@ export default function FailuresTab({ testCases }: FailuresTabProps) {
 
   return (
     <div className="page-scroll">
-      <div className="list">
-        {failures.map((testCase) => (
-          <div key={`${testCase.suite_name}.${testCase.test_name}`} className="list-item" style={{ cursor: "default", alignItems: "start", flexDirection: "column" }}>
-            <div className="flex items-center gap-2" style={{ color: "var(--text-primary)", fontWeight: 600 }}>
-              <span
-                className="inline-block w-2 h-2 rounded-full"
-                style={{ backgroundColor: "var(--danger)" }}
-              />
-              {testCase.suite_name}.{testCase.test_name}
-            </div>
-
-            {testCase.failure_message && (
+      {diagnostics.length > 0 && (
+        <div className="list" style={{ marginBottom: 16 }}>
+          {diagnostics.map((diag) => (
+            <div key={diag.id} className="list-item" style={{ cursor: "default", alignItems: "start", flexDirection: "column" }}>
+              <div className="flex items-center gap-2" style={{ color: "var(--text-primary)", fontWeight: 600 }}>
+                <span
+                  className="inline-block w-2 h-2 rounded-full"
+                  style={{
+                    backgroundColor:
+                      diag.severity === "error" ? "var(--danger)" : "var(--warning)",
+                  }}
+                />
+                {diag.severity === "error" ? "Build error" : "Build warning"} · {formatTargetKey(diag.target_key)}
+              </div>
               <pre
                 className="code selectable"
                 style={{
@@ -40,24 +270,63 @@ export default function FailuresTab({ testCases }: FailuresTabProps) {
                   borderRadius: 10,
                   whiteSpace: "pre-wrap",
                   overflowX: "auto",
-                  backgroundColor: "rgba(194, 64, 29, 0.08)",
-                  color: "var(--danger)",
                   fontSize: 12,
                 }}
               >
-                {testCase.failure_message}
+                {diag.message}
               </pre>
-            )}
-
-            {testCase.file_path && (
-              <div className="muted" style={{ marginTop: 6, fontSize: 12 }}>
-                {testCase.file_path}
-                {testCase.line_number != null ? `:${testCase.line_number}` : ""}
-              </div>
-            )}
-          </div>
-        ))}
+              {diag.file_path && (
+                <div className="muted" style={{ marginTop: 6, fontSize: 12 }}>
+                  {diag.file_path}
+                  {diag.line_number != null ? `:${diag.line_number}` : ""}
+                  {diag.column_number != null ? `:${diag.column_number}` : ""}
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+      {canGroup && (
+        <div className="flex items-center gap-2" style={{ marginBottom: 8 }}>
+          <button className="btn" onClick={() => setGroupBy(!groupBy)}>
+            {grouped ? "Show each failure" : "Group by cause"}
+          </button>
+          {grouped && (
+            <span className="muted" style={{ fontSize: 12 }}>
+              {failures.length} {pluralize(failures.length, "failure")} in {clusters.length}{" "}
+              {pluralize(clusters.length, "group")}
+            </span>
+          )}
+        </div>
+      )}
+      <div className="list">
+        {grouped
+          ? clusters.map((cluster) => (
+              <FailureGroup
+                key={cluster.signature}
+                cluster={cluster}
+                failures={failures.filter((testCase) =>
+                  cluster.failures.some((f) => f.case_id === testCase.id),
+                )}
+                renderFailure={(testCase) => (
+                  <FailureItem
+                    key={testCase.id}
+                    runId={runId}
+                    testCase={testCase}
+                    attachments={attachments}
+                  />
+                )}
+              />
+            ))
+          : failures.map((testCase) => (
+              <FailureItem
+                key={testCase.id}
+                runId={runId}
+                testCase={testCase}
+                attachments={attachments}
+              />
+            ))}
       </div>
     </div>
   );
-}
+}
\ No newline at end of file
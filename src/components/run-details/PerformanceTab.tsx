@@ -0,0 +1,93 @@
+import Badge from "../common/Badge";
+import {
+  usePinPerformanceBaseline,
+  type PerformanceMetric,
+} from "../../hooks/useRunHistory";
+
+interface PerformanceTabProps {
+  metrics: PerformanceMetric[];
+}
+
+function formatValue(value: number, unit: string): string {
+  return `${value.toPrecision(4)} ${unit}`;
+}
+
+export default function PerformanceTab({ metrics }: PerformanceTabProps) {
+  const pin = usePinPerformanceBaseline();
+
+  if (metrics.length === 0) {
+    return (
+      <div className="page-scroll">
+        <div className="card muted" style={{ textAlign: "center", padding: "28px 20px" }}>
+          No performance metrics recorded in this run.
+        </div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="page-scroll">
+      <div className="list">
+        {metrics.map((metric) => (
+          <div
+            key={metric.id}
+            className="list-item"
+            style={{ cursor: "default", alignItems: "start", flexDirection: "column" }}
+          >
+            <div
+              className="flex items-center gap-2"
+              style={{ color: "var(--text-primary)", fontWeight: 600 }}
+            >
+              {metric.suite_name}.{metric.test_name}
+              <span className="muted" style={{ fontWeight: 400 }}>
+                · {metric.metric}
+              </span>
+              {metric.regressed && <Badge variant="danger">Regressed</Badge>}
+            </div>
+
+            <div className="code muted" style={{ fontSize: 12, marginTop: 4 }}>
+              average {formatValue(metric.average, metric.unit)} · RSD{" "}
+              {metric.relative_std_dev.toFixed(1)}% · {metric.measurements.length} values
+            </div>
+
+            <div
+              className="flex items-center justify-between gap-3"
+              style={{ marginTop: 6, width: "100%" }}
+            >
+              <span className="muted" style={{ fontSize: 12 }}>
+                {metric.baseline_average != null ? (
+                  <>
+                    {metric.change_percent != null &&
+                      `${metric.change_percent >= 0 ? "+" : ""}${metric.change_percent.toFixed(1)}% `}
+                    vs {metric.baseline_pinned ? "pinned baseline" : "previous run"} (
+                    {formatValue(metric.baseline_average, metric.unit)})
+                  </>
+                ) : (
+                  "No baseline yet"
+                )}
+              </span>
+              <div className="flex items-center gap-2">
+                {metric.baseline_pinned && (
+                  <button
+                    className="btn"
+                    disabled={pin.isPending}
+                    onClick={() => pin.mutate({ metric, pin: false })}
+                  >
+                    Unpin baseline
+                  </button>
+                )}
+                <button
+                  className="btn"
+                  disabled={pin.isPending}
+                  onClick={() => pin.mutate({ metric, pin: true })}
+                >
+                  Pin as baseline
+                </button>
+              </div>
+            </div>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
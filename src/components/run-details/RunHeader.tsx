@@ -1,5 +1,8 @@
+import { ask } from "@tauri-apps/plugin-dialog";
+import { useNavigate } from "react-router-dom";
 import Badge from "../common/Badge";
 import { formatDuration, formatTimestamp } from "../../lib/formatters";
+import { useDeleteRun, usePinRun } from "../../hooks/useRunHistory";
 import type { TestRun } from "../../hooks/useRunHistory";
 
 interface RunHeaderProps {
@@ -7,6 +10,20 @@ interface RunHeaderProps {
 }
 
 export default function RunHeader({ run }: RunHeaderProps) {
+  const navigate = useNavigate();
+  const pinRun = usePinRun();
+  const deleteRun = useDeleteRun();
+
+  const confirmDelete = async () => {
+    const confirmed = await ask(
+      "Delete this run with its results, log and result bundles? This cannot be undone.",
+      { title: "Delete run", kind: "warning" },
+    );
+    if (confirmed) {
+      deleteRun.mutate(run.id, { onSuccess: () => navigate("/history") });
+    }
+  };
+
   return (
     <div className="run-header">
       <div className="run-header-main">
@@ -16,7 +33,7 @@ export default function RunHeader({ run }: RunHeaderProps) {
               ? "success"
               : run.status === "failed"
                 ? "danger"
-                : run.status === "running"
+                : run.status === "running" || run.status === "interrupted"
                   ? "warning"
                   : "neutral"
           }
@@ -29,9 +46,34 @@ export default function RunHeader({ run }: RunHeaderProps) {
       </div>
 
       <div className="run-header-meta">
+        {run.status === "interrupted" && (
+          <span>Partial results: the app quit before the run finished</span>
+        )}
         <span>{run.total_tests} tests</span>
         {run.duration_ms != null && <span>{formatDuration(run.duration_ms)}</span>}
         <span className="muted">{formatTimestamp(run.started_at)}</span>
+        {run.status !== "running" && (
+          <>
+            <button
+              type="button"
+              className="btn"
+              style={{ fontSize: 12 }}
+              title={run.pinned ? undefined : "Pinned runs are never removed by retention"}
+              onClick={() => pinRun.mutate({ runId: run.id, pinned: !run.pinned })}
+            >
+              {run.pinned ? "Unpin" : "Pin"}
+            </button>
+            <button
+              type="button"
+              className="btn"
+              style={{ fontSize: 12, color: "var(--danger)" }}
+              disabled={deleteRun.isPending}
+              onClick={confirmDelete}
+            >
+              Delete
+            </button>
+          </>
+        )}
       </div>
     </div>
   );
@@ -1,18 +1,23 @@
 import { formatDuration, formatTargetKey } from "../../lib/formatters";
 import type { TestCase, TestRun } from "../../hooks/useRunHistory";
+import type { RunAnnotation } from "../../lib/db";
 
 interface SummaryTabProps {
   run: TestRun;
   testCases: TestCase[];
+  annotations: RunAnnotation[];
 }
 
-export default function SummaryTab({ run, testCases }: SummaryTabProps) {
+export default function SummaryTab({ run, testCases, annotations }: SummaryTabProps) {
   const slowest = [...testCases]
     .filter((testCase) => testCase.duration_ms != null)
     .sort((a, b) => (b.duration_ms ?? 0) - (a.duration_ms ?? 0))
     .slice(0, 5);
 
   const targetResults = run.target_results ?? [];
+  const durationRegressions = annotations.filter(
+    (annotation) => annotation.kind === "duration_regression" && annotation.duration,
+  );
 
   return (
     <div className="page-scroll">
@@ -50,6 +55,15 @@ export default function SummaryTab({ run, testCases }: SummaryTabProps) {
             { label: "Passed", value: run.passed_tests, color: "var(--success)" },
             { label: "Failed", value: run.failed_tests, color: "var(--danger)" },
             { label: "Skipped", value: run.skipped_tests, color: "var(--text-tertiary)" },
+            ...(run.quarantined_failures > 0
+              ? [
+                  {
+                    label: "Quarantined failures",
+                    value: run.quarantined_failures,
+                    color: "var(--warning)",
+                  },
+                ]
+              : []),
           ].map((item) => (
             <div key={item.label} className="card" style={{ textAlign: "center" }}>
               <div style={{ fontSize: 32, fontWeight: 700, color: item.color }}>{item.value}</div>
@@ -60,6 +74,32 @@ export default function SummaryTab({ run, testCases }: SummaryTabProps) {
           ))}
         </div>
 
+        {durationRegressions.length > 0 && (
+          <section className="stack" style={{ gap: 8 }}>
+            <h3 className="section-title">Duration Regressions</h3>
+            <div className="list">
+              {durationRegressions.map(({ id, test, target_key, duration, message }) => (
+                <div key={id} className="list-item" style={{ cursor: "default" }} title={message}>
+                  <span style={{ color: "var(--text-primary)" }}>
+                    {test ? (
+                      <>
+                        <span style={{ color: "var(--text-secondary)" }}>{test.suite_name}.</span>
+                        {test.test_name}
+                      </>
+                    ) : (
+                      formatTargetKey(target_key ?? "")
+                    )}
+                  </span>
+                  <span className="code" style={{ color: "var(--warning)", fontSize: 12 }}>
+                    {formatDuration(duration!.duration_ms)} vs {formatDuration(duration!.median_ms)}{" "}
+                    median (+{Math.round(duration!.change_percent)}%)
+                  </span>
+                </div>
+              ))}
+            </div>
+          </section>
+        )}
+
         {slowest.length > 0 && (
           <section className="stack" style={{ gap: 8 }}>
             <h3 className="section-title">Slowest Tests</h3>
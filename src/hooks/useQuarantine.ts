@@ -0,0 +1,37 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import {
+  getQuarantinedTests,
+  quarantineTest,
+  releaseQuarantinedTest,
+} from "../lib/db";
+import type { QuarantinedTest } from "../lib/db";
+
+export function useQuarantine(projectId: string | null) {
+  const queryClient = useQueryClient();
+
+  const query = useQuery<QuarantinedTest[]>({
+    queryKey: ["quarantine", projectId ?? "all"],
+    queryFn: () => getQuarantinedTests(projectId),
+  });
+
+  const quarantineMutation = useMutation({
+    mutationFn: quarantineTest,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["quarantine"] });
+    },
+  });
+
+  const releaseMutation = useMutation({
+    mutationFn: releaseQuarantinedTest,
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["quarantine"] });
+    },
+  });
+
+  return {
+    quarantined: query.data ?? [],
+    isLoading: query.isLoading,
+    quarantineTest: quarantineMutation.mutateAsync,
+    releaseTest: releaseMutation.mutateAsync,
+  };
+}
@@ -1,9 +1,39 @@
-import { useQuery } from "@tanstack/react-query";
-import { getRuns, getRun, getTestCases } from "../lib/db";
-import type { DbTestRun, DbTestCase } from "../lib/db";
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import { invoke } from "@tauri-apps/api/core";
+import {
+  getRuns,
+  getRun,
+  getTestCases,
+  getTestHistory,
+  getFlakyTests,
+  compareRuns,
+  getRunAnnotations,
+  getDurationRegressions,
+  getFailureClusters,
+  getRecentFailureClusters,
+  getBuildDiagnostics,
+  getPerformanceMetrics,
+  setPerformanceBaselinePinned,
+  applyRetention,
+  deleteRun,
+  setRunPinned,
+} from "../lib/db";
+import type {
+  DbTestRun,
+  DbTestCase,
+  DbBuildDiagnostic,
+  DbPerformanceMetric,
+  TestHistory,
+  FlakyTest,
+  RunComparison,
+  RunAnnotation,
+  FailureCluster,
+} from "../lib/db";
 
 export type TestRun = DbTestRun;
 export type TestCase = DbTestCase;
+export type BuildDiagnostic = DbBuildDiagnostic;
+export type PerformanceMetric = DbPerformanceMetric;
 
 export function useRuns(projectId?: string | null) {
   return useQuery<TestRun[]>({
@@ -28,6 +58,194 @@ export function useTestDetails(runId: string | undefined) {
   });
 }
 
+/** One test's timeline and stats across runs, by its `test_id`. */
+export function useTestHistory(testId: number | null | undefined, limit?: number) {
+  return useQuery<TestHistory | null>({
+    queryKey: ["testHistory", testId, limit ?? "default"],
+    queryFn: () => getTestHistory(testId!, limit),
+    enabled: testId != null,
+  });
+}
+
+/** Flaky suspects of a project, flakiest first. */
+export function useFlakyTests(projectId?: string | null, limit?: number) {
+  return useQuery<FlakyTest[]>({
+    queryKey: ["flakyTests", projectId ?? "all", limit ?? "default"],
+    queryFn: () => getFlakyTests(projectId, limit),
+  });
+}
+
+/** Changes from a base run to a head run. */
+export function useRunComparison(
+  baseId: string | null | undefined,
+  headId: string | null | undefined,
+) {
+  return useQuery<RunComparison>({
+    queryKey: ["runComparison", baseId, headId],
+    queryFn: () => compareRuns(baseId!, headId!),
+    enabled: !!baseId && !!headId,
+    staleTime: Infinity,
+  });
+}
+
+export function useRunAnnotations(runId: string | undefined) {
+  return useQuery<RunAnnotation[]>({
+    queryKey: ["runAnnotations", runId],
+    queryFn: () => getRunAnnotations(runId!),
+    enabled: !!runId,
+  });
+}
+
+/** Duration regressions of a project's recent runs, newest run first. */
+export function useDurationRegressions(projectId?: string | null, limit?: number) {
+  return useQuery<RunAnnotation[]>({
+    queryKey: ["durationRegressions", projectId ?? "all", limit ?? "default"],
+    queryFn: () => getDurationRegressions(projectId, limit),
+  });
+}
+
+export function useFailureClusters(runId: string | undefined, enabled = true) {
+  return useQuery<FailureCluster[]>({
+    queryKey: ["failureClusters", runId],
+    queryFn: () => getFailureClusters(runId!),
+    enabled: !!runId && enabled,
+  });
+}
+
+/** Failure causes across a project's last `runs` runs. */
+export function useRecentFailureClusters(projectId?: string | null, runs?: number) {
+  return useQuery<FailureCluster[]>({
+    queryKey: ["failureClusters", "recent", projectId ?? "all", runs ?? "default"],
+    queryFn: () => getRecentFailureClusters(projectId, runs),
+  });
+}
+
+export function useBuildDiagnostics(runId: string | undefined) {
+  return useQuery<BuildDiagnostic[]>({
+    queryKey: ["buildDiagnostics", runId],
+    queryFn: () => getBuildDiagnostics(runId!),
+    enabled: !!runId,
+  });
+}
+
+export function usePerformanceMetrics(runId: string | undefined) {
+  return useQuery<PerformanceMetric[]>({
+    queryKey: ["performanceMetrics", runId],
+    queryFn: () => getPerformanceMetrics(runId!),
+    enabled: !!runId,
+  });
+}
+
+/** Pin (or unpin) a run's metric as its test's baseline for later runs. */
+export function usePinPerformanceBaseline() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: ({ metric, pin }: { metric: PerformanceMetric; pin: boolean }) =>
+      setPerformanceBaselinePinned(metric.id, pin),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["performanceMetrics"] });
+    },
+  });
+}
+
+/** Queries showing runs or data derived from them, refreshed when runs are deleted. */
+function invalidateRunQueries(queryClient: ReturnType<typeof useQueryClient>) {
+  for (const key of [
+    "runs",
+    "history",
+    "run",
+    "flakyTests",
+    "runComparison",
+    "durationRegressions",
+    "failureClusters",
+  ]) {
+    queryClient.invalidateQueries({ queryKey: [key] });
+  }
+}
+
+/** Pin (or unpin) a run so retention keeps it. */
+export function usePinRun() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: ({ runId, pinned }: { runId: string; pinned: boolean }) =>
+      setRunPinned(runId, pinned),
+    onSuccess: (_, { runId }) => {
+      queryClient.invalidateQueries({ queryKey: ["run", runId] });
+      queryClient.invalidateQueries({ queryKey: ["runs"] });
+      queryClient.invalidateQueries({ queryKey: ["history"] });
+    },
+  });
+}
+
+export function useDeleteRun() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: deleteRun,
+    onSuccess: () => invalidateRunQueries(queryClient),
+  });
+}
+
+/** Apply the retention limits now, e.g. after they were changed in settings. */
+export function useApplyRetention() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: applyRetention,
+    onSuccess: (report) => {
+      if (report.deleted_runs.length > 0) invalidateRunQueries(queryClient);
+    },
+  });
+}
+
+/** Identifier `get_test_output` takes: "Suite/test", or the bare name without a suite. */
+export function testOutputId(testCase: TestCase): string {
+  return testCase.suite_name
+    ? `${testCase.suite_name}/${testCase.test_name}`
+    : testCase.test_name;
+}
+
+/** Output a test printed, as captured by the runner under the test's run target. */
+export function useTestOutput(
+  runId: string | undefined,
+  testCase: TestCase,
+  enabled: boolean,
+) {
+  return useQuery<string>({
+    queryKey: ["testOutput", runId, testCase.id],
+    queryFn: () =>
+      invoke<string>("get_test_output", {
+        runId,
+        targetKey: testCase.target_key ?? "",
+        testId: testOutputId(testCase),
+      }),
+    enabled: !!runId && enabled,
+    staleTime: Infinity,
+    retry: false,
+  });
+}
+
+/** Attachment exported from a run's result bundles (see `export_attachments`). */
+export interface TestAttachment {
+  /** e.g. "MyTests/testFoo()" */
+  test_identifier: string;
+  name: string;
+  uniform_type_identifier: string;
+  timestamp: string | null;
+  path: string;
+  associated_with_failure: boolean;
+}
+
+/** Exports attachments from the run's result bundles; only runs with failures are exported. */
+export function useAttachments(runId: string | undefined, enabled = true) {
+  return useQuery<TestAttachment[]>({
+    queryKey: ["attachments", runId],
+    queryFn: () =>
+      invoke<TestAttachment[]>("export_attachments", { runId, testId: null }),
+    enabled: !!runId && enabled,
+    staleTime: Infinity,
+    retry: false,
+  });
+}
+
 export function useHistory(limit = 50, projectId?: string | null) {
   return useQuery<TestRun[]>({
     queryKey: ["history", limit, projectId ?? "all"],
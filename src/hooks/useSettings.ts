@@ -1,5 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from "@tanstack/react-query";
 import { getAllSettings, updateSetting } from "../lib/db";
+import { DEFAULT_PERF_REGRESSION_THRESHOLD } from "../lib/performance";
 
 export interface AppSettings {
   project_path: string;
@@ -9,7 +10,13 @@ export interface AppSettings {
   notify_on_completion: boolean;
   notify_only_on_failure: boolean;
   retain_last_runs: number;
+  /** Days runs are kept; 0 for no limit. */
+  retain_max_age_days: number;
+  /** Megabytes of result bundles and other run artifacts kept; 0 for no limit. */
+  retain_max_disk_mb: number;
   default_simulator: string;
+  /** Percent increase over the baseline at which a performance metric is flagged. */
+  perf_regression_threshold: number;
 }
 
 function parseSettings(raw: Record<string, string>): AppSettings {
@@ -21,7 +28,12 @@ function parseSettings(raw: Record<string, string>): AppSettings {
     notify_on_completion: raw.notify_on_completion !== "false",
     notify_only_on_failure: raw.notify_only_on_failure === "true",
     retain_last_runs: parseInt(raw.retain_last_runs ?? "50", 10),
+    retain_max_age_days: parseInt(raw.retain_max_age_days ?? "0", 10),
+    retain_max_disk_mb: parseInt(raw.retain_max_disk_mb ?? "0", 10),
     default_simulator: raw.default_simulator ?? "",
+    perf_regression_threshold: parseFloat(
+      raw.perf_regression_threshold ?? String(DEFAULT_PERF_REGRESSION_THRESHOLD),
+    ),
   };
 }
 
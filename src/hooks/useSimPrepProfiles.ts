@@ -0,0 +1,63 @@
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import {
+  getSimPrepProfiles,
+  insertSimPrepProfile,
+  updateSimPrepProfile,
+  deleteSimPrepProfile,
+} from "../lib/db";
+import type { SimPrepConfig } from "../lib/db";
+
+export function useSimPrepProfiles(projectId: string | null) {
+  const queryClient = useQueryClient();
+
+  const query = useQuery({
+    queryKey: ["simPrepProfiles", projectId],
+    queryFn: () => getSimPrepProfiles(projectId!),
+    enabled: !!projectId,
+  });
+
+  const createMutation = useMutation({
+    mutationFn: (profile: { name: string; config: SimPrepConfig }) => {
+      if (!projectId) throw new Error("No project selected");
+      const id = crypto.randomUUID();
+      return insertSimPrepProfile({
+        id,
+        project_id: projectId,
+        name: profile.name,
+        config: profile.config,
+      });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["simPrepProfiles", projectId] });
+    },
+  });
+
+  const updateMutation = useMutation({
+    mutationFn: ({
+      id,
+      updates,
+    }: {
+      id: string;
+      updates: { name?: string; config?: SimPrepConfig };
+    }) => updateSimPrepProfile(id, updates),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["simPrepProfiles", projectId] });
+    },
+  });
+
+  const deleteMutation = useMutation({
+    mutationFn: (id: string) => deleteSimPrepProfile(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["simPrepProfiles", projectId] });
+    },
+  });
+
+  return {
+    profiles: query.data ?? [],
+    isLoading: query.isLoading,
+    error: query.error,
+    createProfile: createMutation.mutateAsync,
+    updateProfile: updateMutation.mutateAsync,
+    deleteProfile: deleteMutation.mutateAsync,
+  };
+}
@@ -1,8 +1,10 @@
 import { useQueryClient } from "@tanstack/react-query";
 import { Channel, invoke } from "@tauri-apps/api/core";
-import { insertRun, insertTestCase, updateRunCompletion } from "../lib/db";
+import { getSimPrepProfile } from "../lib/db";
+import type { DbBuildDiagnostic } from "../lib/db";
 import { useSettings } from "./useSettings";
 import { useExecutionStore } from "../stores/executionStore";
+import type { LiveTestResult } from "../stores/executionStore";
 import { useSelectionStore } from "../stores/selectionStore";
 
 /** Build scheme_targets from keys "scheme" or "scheme|TargetName". Whole-scheme key runs entire scheme once. */
@@ -88,17 +90,35 @@ function buildTargetKeysForRun(
 
 interface TestRunEvent {
   type: string;
-  line?: string;
+  /** Clean output text for Stdout/Stderr; source line number for BuildDiagnostic. */
+  line?: string | number | null;
+  raw?: string | null;
   name?: string;
   suite?: string;
   status?: string;
   duration_ms?: number;
   tests_run?: number;
   tests_total?: number;
+  eta_ms?: number | null;
   run_id?: string;
   success?: boolean;
   message?: string;
   key?: string;
+  severity?: DbBuildDiagnostic["severity"];
+  category?: DbBuildDiagnostic["category"];
+  file_path?: string | null;
+  column?: number | null;
+  failure_message?: string | null;
+  line_number?: number | null;
+  target?: string | null;
+  step?: string;
+  completed_steps?: number;
+  total_steps?: number | null;
+  reason?: string;
+  log_excerpt?: string;
+  crash_report_path?: string | null;
+  status_reason?: string | null;
+  bundle_path?: string;
 }
 
 export function useTestExecution() {
@@ -126,18 +146,12 @@ export function useTestExecution() {
       defaultSchemeForTestPlans?: string;
       /** For mode "all" + run by test plans. */
       allTestPlanRuns?: { scheme: string; test_plan_name: string }[];
+      /** Simulator prep profile to apply to the destination before the run. */
+      simPrepProfileId?: string | null;
     },
   ) => {
-    const startTime = Date.now();
-    const collectedLines: string[] = [];
-    const collectedResults: {
-      name: string;
-      suite: string;
-      status: string;
-      duration_ms: number;
-    }[] = [];
-    const collectedTargetResults: { key: string; success: boolean }[] = [];
-
+    // The backend records the run, its output, test cases, target results, build
+    // diagnostics and performance metrics; events here only update the live view
     const onEvent = new Channel<TestRunEvent>();
 
     onEvent.onmessage = (event) => {
@@ -152,47 +166,73 @@ export function useTestExecution() {
             });
           }
           break;
-        case "Stdout":
+        case "Stdout": {
+          const text = typeof event.line === "string" ? event.line : "";
           store.appendOutput({
             timestamp: Date.now(),
-            text: event.line ?? "",
+            text,
+            raw: event.raw ?? undefined,
             kind: "stdout",
           });
-          collectedLines.push(event.line ?? "");
           break;
-        case "Stderr":
+        }
+        case "Stderr": {
+          const text = typeof event.line === "string" ? event.line : "";
           store.appendOutput({
             timestamp: Date.now(),
-            text: event.line ?? "",
+            text,
+            raw: event.raw ?? undefined,
             kind: "stderr",
           });
-          collectedLines.push(`[stderr] ${event.line ?? ""}`);
           break;
+        }
         case "TestCompleted":
           if (event.name && event.status) {
             store.addTestResult({
               name: event.name,
-              status: event.status as "passed" | "failed" | "skipped",
+              status: event.status as LiveTestResult["status"],
               durationMs: event.duration_ms ?? 0,
             });
-            collectedResults.push({
-              name: event.name,
-              suite: event.suite ?? "",
-              status: event.status,
-              duration_ms: event.duration_ms ?? 0,
-            });
           }
           break;
         case "Progress":
-          store.setProgress(event.tests_run ?? 0, event.tests_total ?? 0);
+          store.setProgress(
+            event.tests_run ?? 0,
+            event.tests_total ?? 0,
+            event.eta_ms ?? null,
+          );
+          break;
+        case "BuildProgress":
+          store.setBuildProgress({
+            target: event.target ?? null,
+            step: event.step ?? "",
+            completedSteps: event.completed_steps ?? 0,
+            totalSteps: event.total_steps ?? null,
+          });
+          break;
+        case "BuildDiagnostic":
+          if (event.severity === "error") {
+            store.appendOutput({
+              timestamp: Date.now(),
+              text: `Build error: ${event.message ?? ""}`,
+              kind: "system",
+            });
+          }
+          break;
+        case "TestCrashed":
+          store.appendOutput({
+            timestamp: Date.now(),
+            text: `Crashed: ${event.suite ?? ""}.${event.name ?? ""} — ${event.reason ?? ""}${
+              event.crash_report_path
+                ? ` (crash report: ${event.crash_report_path})`
+                : ""
+            }`,
+            kind: "system",
+          });
           break;
         case "TargetCompleted":
           if (event.key != null) {
             store.setTargetCompleted(event.key, event.success ?? false);
-            collectedTargetResults.push({
-              key: event.key,
-              success: event.success ?? false,
-            });
           }
           break;
         case "RunFinished":
@@ -286,6 +326,9 @@ export function useTestExecution() {
     try {
       const destination =
         settings?.default_simulator?.trim() || null;
+      const simPrepProfile = options?.simPrepProfileId
+        ? await getSimPrepProfile(options.simPrepProfileId)
+        : null;
       const runId = await invoke<string>("run_tests", {
         params: {
           project_path: projectPath,
@@ -294,60 +337,22 @@ export function useTestExecution() {
           stop_on_first_failure: stopOnFirstFailure,
           test_plan_runs: runMode === "testPlans" ? testPlanRuns : [],
           destination,
+          sim_prep_profile: simPrepProfile
+            ? { name: simPrepProfile.name, ...simPrepProfile.config }
+            : null,
+          project_id: projectId ?? null,
+          scope,
         },
         onEvent,
       });
 
-      // Persist run to DB
-      await insertRun({
-        id: runId,
-        project_path: projectPath,
-        project_id: projectId ?? undefined,
-        scope,
-        started_at: new Date().toISOString(),
-      });
-
-      const endTime = Date.now();
-      const passed = collectedResults.filter(
-        (r) => r.status === "passed",
-      ).length;
-      const failed = collectedResults.filter(
-        (r) => r.status === "failed",
-      ).length;
-      const skipped = collectedResults.filter(
-        (r) => r.status === "skipped",
-      ).length;
-
-      // Update run completion in DB
-      await updateRunCompletion({
-        id: runId,
-        status: failed > 0 ? "failed" : "passed",
-        finished_at: new Date().toISOString(),
-        duration_ms: endTime - startTime,
-        total_tests: collectedResults.length,
-        passed_tests: passed,
-        failed_tests: failed,
-        skipped_tests: skipped,
-        raw_log: collectedLines.join("\n"),
-        target_results:
-          collectedTargetResults.length > 0 ? collectedTargetResults : undefined,
-      });
-
-      // Persist individual test cases
-      for (const result of collectedResults) {
-        await insertTestCase({
-          run_id: runId,
-          suite_name: result.suite,
-          test_name: result.name,
-          status: result.status,
-          duration_ms: result.duration_ms,
-          failure_message: null,
-        });
-      }
-
       // Refresh queries
       queryClient.invalidateQueries({ queryKey: ["runs"] });
       queryClient.invalidateQueries({ queryKey: ["history"] });
+      queryClient.invalidateQueries({ queryKey: ["flakyTests"] });
+      queryClient.invalidateQueries({ queryKey: ["runAnnotations"] });
+      queryClient.invalidateQueries({ queryKey: ["durationRegressions"] });
+      queryClient.invalidateQueries({ queryKey: ["failureClusters"] });
 
       return runId;
     } catch (err) {
@@ -0,0 +1,66 @@
+/** A run of text with the SGR styling that applied to it. */
+export interface AnsiSpan {
+  text: string;
+  color: string | null;
+  bold: boolean;
+}
+
+/** Standard colors 0–7 mapped onto the theme; white would vanish on the light terminal. */
+const ANSI_COLORS = [
+  "var(--text-primary)",
+  "var(--danger)",
+  "var(--success)",
+  "var(--warning)",
+  "var(--accent)",
+  "#9b4f96",
+  "#2f8595",
+  "var(--text-secondary)",
+];
+
+// CSI (ESC [ ... final), OSC (ESC ] ... BEL / ESC \) and two-character escapes
+const ESCAPE_RE =
+  /\x1b(?:\[([0-?]*)[ -/]*([@-~])|\][^\x07\x1b]*(?:\x07|\x1b\\)?|[@-Z\\-_])/g;
+
+/**
+ * Split a raw terminal line into styled spans. Colors and bold are kept; other escape
+ * sequences are dropped and only the text after the last carriage return is shown.
+ */
+export function parseAnsi(raw: string): AnsiSpan[] {
+  const line = raw.replace(/\r+$/, "");
+  const visible = line.slice(line.lastIndexOf("\r") + 1);
+
+  const spans: AnsiSpan[] = [];
+  let color: string | null = null;
+  let bold = false;
+  let last = 0;
+  for (const match of visible.matchAll(ESCAPE_RE)) {
+    if (match.index > last) {
+      spans.push({ text: visible.slice(last, match.index), color, bold });
+    }
+    last = match.index + match[0].length;
+    if (match[2] !== "m") {
+      continue;
+    }
+    const codes = (match[1] || "0").split(";").map((c) => parseInt(c || "0", 10));
+    for (const code of codes) {
+      if (code === 0) {
+        color = null;
+        bold = false;
+      } else if (code === 1) {
+        bold = true;
+      } else if (code === 22) {
+        bold = false;
+      } else if (code >= 30 && code <= 37) {
+        color = ANSI_COLORS[code - 30];
+      } else if (code >= 90 && code <= 97) {
+        color = ANSI_COLORS[code - 90];
+      } else if (code === 39) {
+        color = null;
+      }
+    }
+  }
+  if (last < visible.length) {
+    spans.push({ text: visible.slice(last), color, bold });
+  }
+  return spans;
+}
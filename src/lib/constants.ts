@@ -11,4 +11,21 @@ export const RUN_STATUS = {
   FAILED: "failed",
   RUNNING: "running",
   CANCELLED: "cancelled",
+  /** The app quit or crashed mid-run; the run keeps what was recorded until then. */
+  INTERRUPTED: "interrupted",
 } as const;
+
+export type TestStatus =
+  | "passed"
+  | "failed"
+  | "skipped"
+  | "crashed"
+  | "expected_failure"
+  | "timed_out";
+
+/** Statuses counted as failures. Expected failures count as neither passed nor failed. */
+export const FAILING_TEST_STATUSES: readonly string[] = ["failed", "crashed", "timed_out"];
+
+export function isFailingStatus(status: string): boolean {
+  return FAILING_TEST_STATUSES.includes(status);
+}
@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import Database from "@tauri-apps/plugin-sql";
 
 let db: Database | null = null;
@@ -31,11 +32,21 @@ export interface DbTestRun {
   skipped_tests: number;
   raw_log: string | null;
   target_results: TargetResult[] | null;
+  /** Commit checked out in the project when the run started, if it is a git repository. */
+  git_commit: string | null;
+  git_branch: string | null;
+  /** Failures of quarantined tests, not included in failed_tests. */
+  quarantined_failures: number;
+  /** Kept regardless of retention limits. */
+  pinned: boolean;
 }
 
 export interface TargetResult {
   key: string;
   success: boolean;
+  /** Tests parsed for the target (absent on runs recorded before progress tracking). */
+  tests_run?: number;
+  duration_ms?: number;
 }
 
 export interface DbSuite {
@@ -47,6 +58,38 @@ export interface DbSuite {
   created_at: string;
 }
 
+/** simctl setup applied before a run (mirrors models::simulator::SimPrepProfile minus the name). */
+export interface SimPrepConfig {
+  permissions: {
+    action: "grant" | "revoke" | "reset";
+    service: string;
+    bundle_id?: string | null;
+  }[];
+  status_bar?: {
+    time?: string | null;
+    data_network?: string | null;
+    wifi_mode?: string | null;
+    wifi_bars?: number | null;
+    cellular_mode?: string | null;
+    cellular_bars?: number | null;
+    operator_name?: string | null;
+    battery_state?: string | null;
+    battery_level?: number | null;
+  } | null;
+  locale?: string | null;
+  language?: string | null;
+  appearance?: "light" | "dark" | null;
+  media: string[];
+}
+
+export interface DbSimPrepProfile {
+  id: string;
+  project_id: string;
+  name: string;
+  config: SimPrepConfig;
+  created_at: string;
+}
+
 export interface DbTestCase {
   id: number;
   run_id: string;
@@ -57,6 +100,240 @@ export interface DbTestCase {
   failure_message: string | null;
   file_path: string | null;
   line_number: number | null;
+  /** `.ips` crash report for a crashed test, when one was found. */
+  crash_report_path: string | null;
+  /** Skip reason, expected-failure reason or time limit message. */
+  status_reason: string | null;
+  /** Run target (scheme, test plan or package) the test was reported under. */
+  target_key: string | null;
+  /** Row in `tests` identifying this test across runs. */
+  test_id: number | null;
+  /** Test target, when a result bundle or the suite name tells. */
+  target_name: string | null;
+  /** The test was quarantined when the case was recorded. */
+  quarantined: boolean;
+}
+
+/** A test across runs (`tests` row). */
+export interface TestInfo {
+  id: number;
+  project_id: string | null;
+  target_name: string | null;
+  suite_name: string;
+  test_name: string;
+  /** A Swift Testing function rather than an XCTest method, once its output has said so. */
+  swift_testing: boolean;
+}
+
+/** One result of a test in one run. */
+export interface TestHistoryEntry {
+  run_id: string;
+  started_at: string;
+  status: string;
+  duration_ms: number | null;
+  failure_message: string | null;
+  git_commit: string | null;
+  git_branch: string | null;
+}
+
+/** A test over its most recent runs; first-seen and last-failed cover all history. */
+export interface TestStats {
+  runs: number;
+  passed: number;
+  /** Failed, crashed and timed-out results. */
+  failed: number;
+  skipped: number;
+  /** Passed over passed and failed results; null when the test never passed or failed. */
+  pass_rate: number | null;
+  p50_duration_ms: number | null;
+  p90_duration_ms: number | null;
+  max_duration_ms: number | null;
+  first_seen_at: string | null;
+  last_failed_at: string | null;
+}
+
+export interface TestHistory {
+  test: TestInfo;
+  /** Most recent first. */
+  entries: TestHistoryEntry[];
+  stats: TestStats;
+}
+
+/** A failing run behind a test's flakiness score. */
+export interface FlakyEvidence {
+  run_id: string;
+  started_at: string;
+  failure_message: string | null;
+  /** The test also passed in the run. */
+  passed_on_retry: boolean;
+  /** No other test failed in the run. */
+  only_failure: boolean;
+}
+
+/** A flaky suspect, as scored after the project's last run. */
+export interface FlakyTest {
+  test: TestInfo;
+  /** 0 (stable) to 1. */
+  score: number;
+  runs: number;
+  failures: number;
+  flips: number;
+  retry_passes: number;
+  isolated_failures: number;
+  /** Most recent first. */
+  evidence: FlakyEvidence[];
+  computed_at: string;
+}
+
+/** Distinct tests of a run by their last result. */
+export interface RunTotals {
+  total: number;
+  passed: number;
+  failed: number;
+  skipped: number;
+}
+
+export interface TestChange {
+  test: TestInfo;
+  /** null when the test did not run in that run. */
+  base_status: string | null;
+  head_status: string | null;
+  failure_message: string | null;
+}
+
+export interface DurationChange {
+  test: TestInfo;
+  base_duration_ms: number;
+  head_duration_ms: number;
+  delta_ms: number;
+  change_percent: number;
+}
+
+/** What changed from a base run to a head run (see `compare_runs`). */
+export interface RunComparison {
+  base_run_id: string;
+  head_run_id: string;
+  base_totals: RunTotals;
+  head_totals: RunTotals;
+  newly_failing: TestChange[];
+  fixed: TestChange[];
+  still_failing: TestChange[];
+  added: TestChange[];
+  removed: TestChange[];
+  /** Largest changes first. */
+  duration_changes: DurationChange[];
+}
+
+/** A duration well above the test's or target's recent median. */
+export interface DurationRegression {
+  duration_ms: number;
+  median_ms: number;
+  /** Median absolute deviation of the recent durations. */
+  mad_ms: number;
+  history_runs: number;
+  change_percent: number;
+}
+
+export type AnnotationKind = "duration_regression";
+
+/** A finding recorded against a run after it completed. */
+export interface RunAnnotation {
+  id: number;
+  run_id: string;
+  run_started_at: string;
+  kind: AnnotationKind;
+  /** The test the finding is about, or null for a target-level finding. */
+  test: TestInfo | null;
+  target_key: string | null;
+  message: string;
+  duration: DurationRegression | null;
+  created_at: string;
+}
+
+export interface ClusterFailure {
+  /** The test case row. */
+  case_id: number;
+  run_id: string;
+  run_started_at: string;
+  test: TestInfo;
+  status: string;
+  failure_message: string | null;
+  file_path: string | null;
+  line_number: number | null;
+  quarantined: boolean;
+}
+
+/** Failures sharing an assertion, normalized message and location. */
+export interface FailureCluster {
+  signature: string;
+  /** e.g. "XCTAssertEqual", "#expect", "crash", "timeout". */
+  assertion: string | null;
+  /** The message with numbers, UUIDs and addresses replaced by placeholders. */
+  normalized_message: string;
+  file_name: string | null;
+  line_number: number | null;
+  count: number;
+  tests: number;
+  runs: number;
+  representative_messages: string[];
+  first_seen_at: string;
+  last_seen_at: string;
+  /** Most recent first. */
+  failures: ClusterFailure[];
+}
+
+/** "run": the test runs but its failures do not fail the run; "skip": it is left out. */
+export type QuarantineMode = "run" | "skip";
+
+export interface QuarantinedTest {
+  test: TestInfo;
+  mode: QuarantineMode;
+  reason: string;
+  owner: string | null;
+  /** Last day (YYYY-MM-DD) the quarantine applies. */
+  expires_at: string | null;
+  created_at: string;
+  /** Past its expiry date: no longer applied, and due for review. */
+  expired: boolean;
+}
+
+export interface DbBuildDiagnostic {
+  id: number;
+  run_id: string;
+  target_key: string;
+  severity: "error" | "warning";
+  category:
+    | "compiler"
+    | "linker"
+    | "code_signing"
+    | "package_resolution"
+    | "build";
+  file_path: string | null;
+  line_number: number | null;
+  column_number: number | null;
+  message: string;
+}
+
+export interface DbPerformanceMetric {
+  id: number;
+  run_id: string;
+  test_id: number | null;
+  suite_name: string;
+  test_name: string;
+  /** e.g. "Time" or "Clock Monotonic Time" */
+  metric: string;
+  unit: string;
+  identifier: string | null;
+  average: number;
+  /** Relative standard deviation, in percent. */
+  relative_std_dev: number;
+  measurements: number[];
+  baseline_average: number | null;
+  baseline_run_id: string | null;
+  baseline_pinned: boolean;
+  /** Change of the average against the baseline, in percent. */
+  change_percent: number | null;
+  regressed: boolean;
 }
 
 /** Stored discovery result for a project (schemes, packages, test plans). */
@@ -67,133 +344,155 @@ export interface ProjectInfo {
   test_plans: { name: string; path: string }[];
 }
 
-export async function insertRun(run: {
-  id: string;
-  project_path: string;
-  project_id?: string | null;
-  scope: string;
-  started_at: string;
-}): Promise<void> {
+export async function getBuildDiagnostics(
+  runId: string,
+): Promise<DbBuildDiagnostic[]> {
   const d = await getDb();
-  await d.execute(
-    "INSERT INTO test_runs (id, status, project_path, project_id, scope, started_at) VALUES ($1, $2, $3, $4, $5, $6)",
-    [
-      run.id,
-      "running",
-      run.project_path,
-      run.project_id ?? null,
-      run.scope,
-      run.started_at,
-    ],
+  return d.select<DbBuildDiagnostic[]>(
+    "SELECT * FROM build_diagnostics WHERE run_id = $1 ORDER BY CASE severity WHEN 'error' THEN 0 ELSE 1 END, id",
+    [runId],
   );
 }
 
-export async function updateRunCompletion(run: {
-  id: string;
-  status: string;
-  finished_at: string;
-  duration_ms: number;
-  total_tests: number;
-  passed_tests: number;
-  failed_tests: number;
-  skipped_tests: number;
-  raw_log?: string;
-  target_results?: TargetResult[];
-}): Promise<void> {
+export async function getPerformanceMetrics(
+  runId: string,
+): Promise<DbPerformanceMetric[]> {
   const d = await getDb();
-  const targetResultsJson =
-    run.target_results != null
-      ? JSON.stringify(run.target_results)
-      : null;
-  await d.execute(
-    "UPDATE test_runs SET status=$1, finished_at=$2, duration_ms=$3, total_tests=$4, passed_tests=$5, failed_tests=$6, skipped_tests=$7, raw_log=$8, target_results=$9 WHERE id=$10",
-    [
-      run.status,
-      run.finished_at,
-      run.duration_ms,
-      run.total_tests,
-      run.passed_tests,
-      run.failed_tests,
-      run.skipped_tests,
-      run.raw_log ?? null,
-      targetResultsJson,
-      run.id,
-    ],
+  const rows = await d.select<
+    (Omit<DbPerformanceMetric, "measurements" | "baseline_pinned" | "regressed"> & {
+      measurements: string;
+      baseline_pinned: number;
+      regressed: number;
+    })[]
+  >(
+    "SELECT * FROM performance_metrics WHERE run_id = $1 ORDER BY regressed DESC, suite_name, test_name, metric",
+    [runId],
   );
+  return rows.map((r) => ({
+    ...r,
+    measurements: JSON.parse(r.measurements) as number[],
+    baseline_pinned: r.baseline_pinned === 1,
+    regressed: r.regressed === 1,
+  }));
 }
 
-export async function insertTestCase(tc: {
-  run_id: string;
-  suite_name: string;
-  test_name: string;
-  status: string;
-  duration_ms: number | null;
-  failure_message: string | null;
-}): Promise<void> {
-  const d = await getDb();
-  await d.execute(
-    "INSERT INTO test_cases (run_id, suite_name, test_name, status, duration_ms, failure_message) VALUES ($1, $2, $3, $4, $5, $6)",
-    [
-      tc.run_id,
-      tc.suite_name,
-      tc.test_name,
-      tc.status,
-      tc.duration_ms,
-      tc.failure_message,
-    ],
-  );
+/**
+ * Pin a run's metric as the baseline for later runs of its test, or unpin the test's baseline.
+ */
+export async function setPerformanceBaselinePinned(
+  metricId: number,
+  pinned: boolean,
+): Promise<void> {
+  await invoke("set_performance_baseline_pinned", { metricId, pinned });
 }
 
+/** Runs are recorded by `run_tests`; these read them through the backend's typed queries. */
 export async function getRuns(
   limit = 50,
   projectId?: string | null,
 ): Promise<DbTestRun[]> {
-  const d = await getDb();
-  let rows: (DbTestRun & { target_results?: string | null })[];
-  if (projectId) {
-    rows = await d.select<(DbTestRun & { target_results?: string | null })[]>(
-      "SELECT * FROM test_runs WHERE project_id = $1 ORDER BY started_at DESC LIMIT $2",
-      [projectId, limit],
-    );
-  } else {
-    rows = await d.select<(DbTestRun & { target_results?: string | null })[]>(
-      "SELECT * FROM test_runs ORDER BY started_at DESC LIMIT $1",
-      [limit],
-    );
-  }
-  return rows.map(parseRunRow);
-}
-
-function parseRunRow(row: DbTestRun & { target_results?: string | null }): DbTestRun {
-  let target_results: TargetResult[] | null = null;
-  if (row.target_results != null && row.target_results !== "") {
-    try {
-      target_results = JSON.parse(row.target_results) as TargetResult[];
-    } catch {
-      // ignore
-    }
-  }
-  const { target_results: _tr, ...rest } = row;
-  return { ...rest, target_results };
+  return invoke<DbTestRun[]>("list_runs", { limit, projectId: projectId ?? null });
 }
 
 export async function getRun(id: string): Promise<DbTestRun | null> {
-  const d = await getDb();
-  const rows = await d.select<(DbTestRun & { target_results?: string | null })[]>(
-    "SELECT * FROM test_runs WHERE id = $1",
-    [id],
-  );
-  const row = rows[0];
-  if (!row) return null;
-  return parseRunRow(row);
+  return invoke<DbTestRun | null>("get_run", { runId: id });
 }
 
 export async function getTestCases(runId: string): Promise<DbTestCase[]> {
-  const d = await getDb();
-  return d.select<DbTestCase[]>(
-    "SELECT * FROM test_cases WHERE run_id = $1 ORDER BY suite_name, test_name",
-    [runId],
-  );
+  return invoke<DbTestCase[]>("get_test_cases", { runId });
+}
+
+/** A test's results over its last `limit` runs (backend default when omitted), with stats. */
+export async function getTestHistory(
+  testId: number,
+  limit?: number,
+): Promise<TestHistory | null> {
+  return invoke<TestHistory | null>("get_test_history", {
+    testId,
+    limit: limit ?? null,
+  });
+}
+
+export async function getFlakyTests(
+  projectId?: string | null,
+  limit?: number,
+): Promise<FlakyTest[]> {
+  return invoke<FlakyTest[]>("list_flaky_tests", {
+    projectId: projectId ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/** Quarantined tests, expired ones first. */
+export async function getQuarantinedTests(
+  projectId?: string | null,
+): Promise<QuarantinedTest[]> {
+  return invoke<QuarantinedTest[]>("list_quarantined_tests", {
+    projectId: projectId ?? null,
+  });
+}
+
+export async function quarantineTest(quarantine: {
+  testId: number;
+  mode: QuarantineMode;
+  reason: string;
+  owner?: string | null;
+  expiresAt?: string | null;
+}): Promise<void> {
+  await invoke("quarantine_test", {
+    testId: quarantine.testId,
+    mode: quarantine.mode,
+    reason: quarantine.reason,
+    owner: quarantine.owner ?? null,
+    expiresAt: quarantine.expiresAt ?? null,
+  });
+}
+
+export async function releaseQuarantinedTest(testId: number): Promise<void> {
+  await invoke("release_quarantined_test", { testId });
+}
+
+export async function compareRuns(
+  baseId: string,
+  headId: string,
+  minDeltaMs?: number,
+): Promise<RunComparison> {
+  return invoke<RunComparison>("compare_runs", {
+    baseId,
+    headId,
+    minDeltaMs: minDeltaMs ?? null,
+  });
+}
+
+export async function getRunAnnotations(runId: string): Promise<RunAnnotation[]> {
+  return invoke<RunAnnotation[]>("get_run_annotations", { runId });
+}
+
+/** Duration regressions of recent runs, newest run first. */
+export async function getDurationRegressions(
+  projectId?: string | null,
+  limit?: number,
+): Promise<RunAnnotation[]> {
+  return invoke<RunAnnotation[]>("list_duration_regressions", {
+    projectId: projectId ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/** Failures of a run grouped by cause, largest groups first. */
+export async function getFailureClusters(runId: string): Promise<FailureCluster[]> {
+  return invoke<FailureCluster[]>("get_failure_clusters", { runId });
+}
+
+/** Failures of the last `runs` runs grouped by cause. */
+export async function getRecentFailureClusters(
+  projectId?: string | null,
+  runs?: number,
+): Promise<FailureCluster[]> {
+  return invoke<FailureCluster[]>("list_failure_clusters", {
+    projectId: projectId ?? null,
+    runs: runs ?? null,
+  });
 }
 
 export async function getProjects(): Promise<DbProject[]> {
@@ -259,6 +558,7 @@ export async function deleteProject(id: string): Promise<void> {
   const d = await getDb();
   await d.execute("DELETE FROM project_discovery WHERE project_id = $1", [id]);
   await d.execute("DELETE FROM suites WHERE project_id = $1", [id]);
+  await d.execute("DELETE FROM sim_prep_profiles WHERE project_id = $1", [id]);
   await d.execute("DELETE FROM projects WHERE id = $1", [id]);
 }
 
@@ -352,6 +652,90 @@ export async function deleteSuite(id: string): Promise<void> {
   await d.execute("DELETE FROM suites WHERE id = $1", [id]);
 }
 
+export async function getSimPrepProfiles(
+  projectId: string,
+): Promise<DbSimPrepProfile[]> {
+  const d = await getDb();
+  const rows = await d.select<
+    { id: string; project_id: string; name: string; config: string; created_at: string }[]
+  >(
+    "SELECT id, project_id, name, config, created_at FROM sim_prep_profiles WHERE project_id = $1 ORDER BY name",
+    [projectId],
+  );
+  return rows.map((r) => ({
+    id: r.id,
+    project_id: r.project_id,
+    name: r.name,
+    config: JSON.parse(r.config) as SimPrepConfig,
+    created_at: r.created_at,
+  }));
+}
+
+export async function getSimPrepProfile(
+  id: string,
+): Promise<DbSimPrepProfile | null> {
+  const d = await getDb();
+  const rows = await d.select<
+    { id: string; project_id: string; name: string; config: string; created_at: string }[]
+  >(
+    "SELECT id, project_id, name, config, created_at FROM sim_prep_profiles WHERE id = $1",
+    [id],
+  );
+  const r = rows[0];
+  if (!r) return null;
+  return {
+    id: r.id,
+    project_id: r.project_id,
+    name: r.name,
+    config: JSON.parse(r.config) as SimPrepConfig,
+    created_at: r.created_at,
+  };
+}
+
+export async function insertSimPrepProfile(profile: {
+  id: string;
+  project_id: string;
+  name: string;
+  config: SimPrepConfig;
+}): Promise<void> {
+  const d = await getDb();
+  const created_at = new Date().toISOString();
+  await d.execute(
+    "INSERT INTO sim_prep_profiles (id, project_id, name, config, created_at) VALUES ($1, $2, $3, $4, $5)",
+    [
+      profile.id,
+      profile.project_id,
+      profile.name,
+      JSON.stringify(profile.config),
+      created_at,
+    ],
+  );
+}
+
+export async function updateSimPrepProfile(
+  id: string,
+  updates: { name?: string; config?: SimPrepConfig },
+): Promise<void> {
+  const d = await getDb();
+  if (updates.name !== undefined) {
+    await d.execute("UPDATE sim_prep_profiles SET name = $1 WHERE id = $2", [
+      updates.name,
+      id,
+    ]);
+  }
+  if (updates.config !== undefined) {
+    await d.execute("UPDATE sim_prep_profiles SET config = $1 WHERE id = $2", [
+      JSON.stringify(updates.config),
+      id,
+    ]);
+  }
+}
+
+export async function deleteSimPrepProfile(id: string): Promise<void> {
+  const d = await getDb();
+  await d.execute("DELETE FROM sim_prep_profiles WHERE id = $1", [id]);
+}
+
 export async function getProjectDiscovery(
   projectId: string,
 ): Promise<ProjectInfo | null> {
@@ -434,10 +818,25 @@ export async function updateSetting(key: string, value: string): Promise<void> {
   );
 }
 
-export async function cleanupOldRuns(retainCount: number): Promise<void> {
-  const d = await getDb();
-  await d.execute(
-    "DELETE FROM test_runs WHERE id NOT IN (SELECT id FROM test_runs ORDER BY started_at DESC LIMIT $1)",
-    [retainCount],
-  );
+/** What one application of the retention limits removed. */
+export interface RetentionReport {
+  deleted_runs: string[];
+  /** Artifacts directories left behind by runs no longer recorded. */
+  removed_orphans: number;
+  freed_bytes: number;
+  errors: string[];
+}
+
+/** Apply the retention limits in settings now; the backend also applies them on startup and after each run. */
+export async function applyRetention(): Promise<RetentionReport> {
+  return invoke<RetentionReport>("apply_retention");
+}
+
+/** Delete a run with its results, log, result bundles and other artifacts. */
+export async function deleteRun(runId: string): Promise<void> {
+  await invoke("delete_run", { runId });
+}
+
+export async function setRunPinned(runId: string, pinned: boolean): Promise<void> {
+  await invoke("set_run_pinned", { runId, pinned });
 }
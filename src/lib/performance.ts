@@ -0,0 +1,2 @@
+/** Default regression threshold, in percent of the baseline average. */
+export const DEFAULT_PERF_REGRESSION_THRESHOLD = 10;
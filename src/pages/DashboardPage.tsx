@@ -1,19 +1,32 @@
+import { useState } from "react";
 import { Link, useNavigate } from "react-router-dom";
 import ProgressBar from "../components/common/ProgressBar";
 import ActionButtons from "../components/dashboard/ActionButtons";
+import FailureClustersList from "../components/dashboard/FailureClustersList";
+import FlakySuspectsList from "../components/dashboard/FlakySuspectsList";
+import QuarantineList from "../components/dashboard/QuarantineList";
 import LiveOutputTerminal from "../components/dashboard/LiveOutputTerminal";
 import RecentRunsList from "../components/dashboard/RecentRunsList";
+import RunDelta from "../components/dashboard/RunDelta";
 import StatusTile from "../components/dashboard/StatusTile";
 import TopBar from "../components/layout/TopBar";
+import QuarantineDialog from "../components/quarantine/QuarantineDialog";
 import { useStoredDiscovery } from "../hooks/useDiscovery";
 import { useCurrentProject } from "../hooks/useProjects";
-import { useRuns } from "../hooks/useRunHistory";
+import { useQuarantine } from "../hooks/useQuarantine";
+import {
+  useFlakyTests,
+  useRecentFailureClusters,
+  useRunComparison,
+  useRuns,
+} from "../hooks/useRunHistory";
 import { useSuites } from "../hooks/useSuites";
 import { useTestExecution } from "../hooks/useTestExecution";
+import { isFailingStatus } from "../lib/constants";
 import { formatDuration, formatTargetKey, formatTimestamp } from "../lib/formatters";
 import { useExecutionStore, type TargetRunStatus } from "../stores/executionStore";
 import { useSelectionStore } from "../stores/selectionStore";
-import type { DbSuite } from "../lib/db";
+import type { DbSuite, QuarantinedTest, TestInfo } from "../lib/db";
 import type { TestRun } from "../hooks/useRunHistory";
 
 function lastRunForSuite(runs: TestRun[], suiteName: string): TestRun | null {
@@ -25,6 +38,17 @@ export default function DashboardPage() {
   const navigate = useNavigate();
   const { data: currentProject } = useCurrentProject();
   const { data: runs = [] } = useRuns(currentProject?.id ?? null);
+  const { data: flakyTests = [] } = useFlakyTests(currentProject?.id ?? null, 5);
+  const { data: failureClusters = [] } = useRecentFailureClusters(currentProject?.id ?? null);
+  // Causes seen in more than one run; single-run groups are on that run's Failures tab
+  const recurringFailures = failureClusters.filter((cluster) => cluster.runs > 1).slice(0, 5);
+  const { quarantined, quarantineTest, releaseTest } = useQuarantine(
+    currentProject?.id ?? null,
+  );
+  const [quarantineTarget, setQuarantineTarget] = useState<{
+    test: TestInfo;
+    existing?: QuarantinedTest;
+  } | null>(null);
   const { suites } = useSuites(currentProject?.id ?? null);
   const { runTests, cancelRun } = useTestExecution();
   const execution = useExecutionStore();
@@ -33,6 +57,8 @@ export default function DashboardPage() {
   const projectPath = currentProject?.path ?? "";
   const { data: discovered } = useStoredDiscovery(currentProject?.id ?? null);
   const lastRun = runs[0] ?? null;
+  const [headRun, baseRun] = runs.filter((r) => r.status !== "running");
+  const { data: comparison } = useRunComparison(baseRun?.id, headRun?.id);
   const runMode = selection.runMode;
   const hasTargetSelection =
     selection.selectedSchemeTargets.size > 0 ||
@@ -245,10 +271,22 @@ export default function DashboardPage() {
             <div className="card">
               <div className="flex items-center justify-between mb-3">
                 <span style={{ fontWeight: 700, color: "var(--text-primary)" }}>
-                  Running tests
+                  {execution.buildProgress
+                    ? `Building${
+                        execution.buildProgress.target
+                          ? ` ${execution.buildProgress.target}`
+                          : ""
+                      } (${execution.buildProgress.completedSteps}${
+                        execution.buildProgress.totalSteps != null
+                          ? `/${execution.buildProgress.totalSteps}`
+                          : ""
+                      })`
+                    : "Running tests"}
                 </span>
                 <span style={{ color: "var(--text-secondary)", fontSize: 12 }}>
                   {execution.testsRun} / {execution.testsTotal || "?"}
+                  {execution.etaMs != null &&
+                    ` · ~${formatDuration(execution.etaMs)} left`}
                 </span>
               </div>
               <ProgressBar
@@ -269,8 +307,8 @@ export default function DashboardPage() {
                 </span>
                 <span style={{ color: "var(--danger)" }}>
                   {
-                    execution.liveResults.filter(
-                      (result) => result.status === "failed",
+                    execution.liveResults.filter((result) =>
+                      isFailingStatus(result.status),
                     ).length
                   }{" "}
                   failed
@@ -318,13 +356,58 @@ export default function DashboardPage() {
             />
           </div>
 
+          {comparison && (
+            <section className="stack" style={{ gap: 8 }}>
+              <h2 className="section-title">Since Previous Run</h2>
+              <RunDelta comparison={comparison} />
+            </section>
+          )}
+
           <section className="stack" style={{ gap: 8 }}>
             <h2 className="section-title">Recent Runs</h2>
             <RecentRunsList runs={runs.slice(0, 10)} />
           </section>
+
+          <section className="stack" style={{ gap: 8 }}>
+            <h2 className="section-title">Flaky Suspects</h2>
+            <FlakySuspectsList
+              tests={flakyTests}
+              quarantinedIds={new Set(quarantined.map((q) => q.test.id))}
+              onQuarantine={(test) => setQuarantineTarget({ test })}
+            />
+          </section>
+
+          <section className="stack" style={{ gap: 8 }}>
+            <h2 className="section-title">Recurring Failures</h2>
+            <FailureClustersList clusters={recurringFailures} />
+          </section>
+
+          {quarantined.length > 0 && (
+            <section className="stack" style={{ gap: 8 }}>
+              <h2 className="section-title">
+                Quarantined Tests
+                {quarantined.some((q) => q.expired) &&
+                  ` (${quarantined.filter((q) => q.expired).length} expired)`}
+              </h2>
+              <QuarantineList
+                quarantined={quarantined}
+                onEdit={(existing) => setQuarantineTarget({ test: existing.test, existing })}
+                onRelease={releaseTest}
+              />
+            </section>
+          )}
         </div>
       </div>
 
+      {quarantineTarget && (
+        <QuarantineDialog
+          test={quarantineTarget.test}
+          existing={quarantineTarget.existing}
+          onSubmit={quarantineTest}
+          onClose={() => setQuarantineTarget(null)}
+        />
+      )}
+
       <style>{`
         .dashboard-suites-grid {
           display: grid;
@@ -4,16 +4,37 @@ import LiveOutputTerminal from "../components/dashboard/LiveOutputTerminal";
 import AllTestsTab from "../components/run-details/AllTestsTab";
 import FailuresTab from "../components/run-details/FailuresTab";
 import LogsTab from "../components/run-details/LogsTab";
+import PerformanceTab from "../components/run-details/PerformanceTab";
 import RunHeader from "../components/run-details/RunHeader";
 import SummaryTab from "../components/run-details/SummaryTab";
 import TopBar from "../components/layout/TopBar";
-import { useRun, useTestDetails } from "../hooks/useRunHistory";
+import {
+  useAttachments,
+  useBuildDiagnostics,
+  useFailureClusters,
+  usePerformanceMetrics,
+  useRun,
+  useRunAnnotations,
+  useTestDetails,
+} from "../hooks/useRunHistory";
+import { isFailingStatus } from "../lib/constants";
 import { useExecutionStore } from "../stores/executionStore";
 
 export default function RunDetailsPage() {
   const { runId } = useParams<{ runId: string }>();
   const { data: run, isLoading: runLoading } = useRun(runId);
   const { data: testCases = [], isLoading: casesLoading } = useTestDetails(runId);
+  const { data: diagnostics = [] } = useBuildDiagnostics(runId);
+  const { data: performanceMetrics = [] } = usePerformanceMetrics(runId);
+  const { data: annotations = [] } = useRunAnnotations(runId);
+  const { data: attachments = [] } = useAttachments(
+    runId,
+    testCases.some((testCase) => isFailingStatus(testCase.status)),
+  );
+  const { data: failureClusters = [] } = useFailureClusters(
+    runId,
+    testCases.some((testCase) => isFailingStatus(testCase.status)),
+  );
   const execution = useExecutionStore();
 
   if (runLoading || casesLoading) {
@@ -38,7 +59,10 @@ export default function RunDetailsPage() {
     );
   }
 
-  const failureCount = testCases.filter((testCase) => testCase.status === "failed").length;
+  const failureCount =
+    testCases.filter(
+      (testCase) => isFailingStatus(testCase.status) && !testCase.quarantined,
+    ).length + diagnostics.filter((diag) => diag.severity === "error").length;
   const hasLiveOutput =
     execution.runId === run.id && execution.outputLines.length > 0;
 
@@ -54,24 +78,42 @@ export default function RunDetailsPage() {
           </Tabs.Trigger>
           <Tabs.Trigger className="btn" value="failures">
             Failures{failureCount > 0 ? ` (${failureCount})` : ""}
+            {run.quarantined_failures > 0 ? ` · ${run.quarantined_failures} quarantined` : ""}
           </Tabs.Trigger>
           <Tabs.Trigger className="btn" value="all-tests">
             All Tests
           </Tabs.Trigger>
+          {performanceMetrics.length > 0 && (
+            <Tabs.Trigger className="btn" value="performance">
+              Performance
+              {performanceMetrics.some((m) => m.regressed)
+                ? ` (${performanceMetrics.filter((m) => m.regressed).length} regressed)`
+                : ""}
+            </Tabs.Trigger>
+          )}
           <Tabs.Trigger className="btn" value="logs">
             Logs
           </Tabs.Trigger>
         </Tabs.List>
 
         <Tabs.Content value="summary" className="flex-1 min-h-0 overflow-auto">
-          <SummaryTab run={run} testCases={testCases} />
+          <SummaryTab run={run} testCases={testCases} annotations={annotations} />
         </Tabs.Content>
         <Tabs.Content value="failures" className="flex-1 min-h-0 overflow-auto">
-          <FailuresTab testCases={testCases} />
+          <FailuresTab
+            runId={run.id}
+            testCases={testCases}
+            diagnostics={diagnostics}
+            attachments={attachments}
+            clusters={failureClusters}
+          />
         </Tabs.Content>
         <Tabs.Content value="all-tests" className="flex-1 min-h-0 overflow-auto">
           <AllTestsTab testCases={testCases} />
         </Tabs.Content>
+        <Tabs.Content value="performance" className="flex-1 min-h-0 overflow-auto">
+          <PerformanceTab metrics={performanceMetrics} />
+        </Tabs.Content>
         <Tabs.Content value="logs" className="flex-1 min-h-0 overflow-hidden">
           {hasLiveOutput ? (
             <div className="page-scroll">
@@ -2,7 +2,9 @@ import { useQuery } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
 import TopBar from "../components/layout/TopBar";
 import { useCurrentProject } from "../hooks/useProjects";
+import { useApplyRetention } from "../hooks/useRunHistory";
 import { useSettings, useUpdateSetting } from "../hooks/useSettings";
+import { formatBytes, pluralize } from "../lib/formatters";
 
 interface SimulatorDevice {
   udid: string;
@@ -14,6 +16,7 @@ export default function SettingsPage() {
   const { data: settings, isLoading } = useSettings();
   const { data: currentProject } = useCurrentProject();
   const updateSetting = useUpdateSetting();
+  const applyRetention = useApplyRetention();
   const { data: simulators = [], isLoading: simulatorsLoading } = useQuery({
     queryKey: ["simulators"],
     queryFn: () => invoke<SimulatorDevice[]>("list_simulators"),
@@ -23,6 +26,11 @@ export default function SettingsPage() {
     updateSetting.mutate({ key, value: (!current).toString() });
   };
 
+  // New limits apply right away rather than after the next run
+  const updateRetention = (key: string, value: string) => {
+    updateSetting.mutate({ key, value }, { onSuccess: () => applyRetention.mutate() });
+  };
+
   if (isLoading || !settings) {
     return (
       <>
@@ -129,21 +137,42 @@ export default function SettingsPage() {
           </section>
 
           <section className="stack" style={{ gap: 8 }}>
-            <h2 className="section-title">Storage</h2>
+            <h2 className="section-title">Performance tests</h2>
             <div className="card flex items-center justify-between gap-3">
               <span style={{ color: "var(--text-primary)", fontWeight: 500 }}>
-                Retain last runs
+                Flag regressions slower than baseline by
               </span>
               <select
-                value={settings.retain_last_runs}
+                value={settings.perf_regression_threshold}
                 onChange={(event) =>
                   updateSetting.mutate({
-                    key: "retain_last_runs",
+                    key: "perf_regression_threshold",
                     value: event.target.value,
                   })
                 }
                 className="ui-select"
                 style={{ width: 110 }}
+              >
+                {[5, 10, 20, 50].map((percent) => (
+                  <option key={percent} value={percent}>
+                    {percent}%
+                  </option>
+                ))}
+              </select>
+            </div>
+          </section>
+
+          <section className="stack" style={{ gap: 8 }}>
+            <h2 className="section-title">Storage</h2>
+            <div className="card flex items-center justify-between gap-3">
+              <span style={{ color: "var(--text-primary)", fontWeight: 500 }}>
+                Retain last runs
+              </span>
+              <select
+                value={settings.retain_last_runs}
+                onChange={(event) => updateRetention("retain_last_runs", event.target.value)}
+                className="ui-select"
+                style={{ width: 110 }}
               >
                 {[10, 25, 50, 100, 200].map((count) => (
                   <option key={count} value={count}>
@@ -152,6 +181,48 @@ export default function SettingsPage() {
                 ))}
               </select>
             </div>
+            <div className="card flex items-center justify-between gap-3">
+              <span style={{ color: "var(--text-primary)", fontWeight: 500 }}>
+                Delete runs older than
+              </span>
+              <select
+                value={settings.retain_max_age_days}
+                onChange={(event) => updateRetention("retain_max_age_days", event.target.value)}
+                className="ui-select"
+                style={{ width: 110 }}
+              >
+                {[0, 7, 30, 90, 365].map((days) => (
+                  <option key={days} value={days}>
+                    {days === 0 ? "Never" : `${days} days`}
+                  </option>
+                ))}
+              </select>
+            </div>
+            <div className="card flex items-center justify-between gap-3">
+              <span style={{ color: "var(--text-primary)", fontWeight: 500 }}>
+                Limit result bundles and artifacts to
+              </span>
+              <select
+                value={settings.retain_max_disk_mb}
+                onChange={(event) => updateRetention("retain_max_disk_mb", event.target.value)}
+                className="ui-select"
+                style={{ width: 110 }}
+              >
+                {[0, 1024, 5120, 10240, 51200].map((mb) => (
+                  <option key={mb} value={mb}>
+                    {mb === 0 ? "No limit" : `${mb / 1024} GB`}
+                  </option>
+                ))}
+              </select>
+            </div>
+            <span className="muted" style={{ fontSize: 12 }}>
+              Pinned runs are always kept and do not count towards these limits.
+              {applyRetention.data &&
+                ` Last cleanup removed ${applyRetention.data.deleted_runs.length} ${pluralize(
+                  applyRetention.data.deleted_runs.length,
+                  "run",
+                )} and freed ${formatBytes(applyRetention.data.freed_bytes)}.`}
+            </span>
           </section>
         </div>
       </div>
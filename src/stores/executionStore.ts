@@ -1,17 +1,29 @@
 import { create } from "zustand";
+import type { TestStatus } from "../lib/constants";
 
 export interface OutputLine {
   timestamp: number;
+  /** Clean text: escape sequences and carriage-return rewrites removed. */
   text: string;
+  /** The line as printed, with terminal escapes, when it differs from `text`. */
+  raw?: string;
   kind: "stdout" | "stderr" | "system";
 }
 
 export interface LiveTestResult {
   name: string;
-  status: "passed" | "failed" | "skipped";
+  status: TestStatus;
   durationMs: number;
 }
 
+/** Latest build step while a target is compiling, e.g. "Building MyFeature (120/340)". */
+export interface BuildProgressState {
+  target: string | null;
+  step: string;
+  completedSteps: number;
+  totalSteps: number | null;
+}
+
 export type TargetRunStatus = "pending" | "running" | "passed" | "failed";
 
 interface ExecutionState {
@@ -21,6 +33,9 @@ interface ExecutionState {
   liveResults: LiveTestResult[];
   testsRun: number;
   testsTotal: number;
+  /** Estimated time left, from historical target durations (null when unknown). */
+  etaMs: number | null;
+  buildProgress: BuildProgressState | null;
   targetKeys: string[];
   targetStatuses: Record<string, TargetRunStatus>;
 
@@ -28,7 +43,8 @@ interface ExecutionState {
   setTargetCompleted: (key: string, success: boolean) => void;
   appendOutput: (line: OutputLine) => void;
   addTestResult: (result: LiveTestResult) => void;
-  setProgress: (run: number, total: number) => void;
+  setProgress: (run: number, total: number, etaMs: number | null) => void;
+  setBuildProgress: (progress: BuildProgressState | null) => void;
   finishRun: () => void;
   reset: () => void;
 }
@@ -40,6 +56,8 @@ export const useExecutionStore = create<ExecutionState>((set) => ({
   liveResults: [],
   testsRun: 0,
   testsTotal: 0,
+  etaMs: null,
+  buildProgress: null,
   targetKeys: [],
   targetStatuses: {},
 
@@ -57,6 +75,8 @@ export const useExecutionStore = create<ExecutionState>((set) => ({
       liveResults: [],
       testsRun: 0,
       testsTotal: 0,
+      etaMs: null,
+      buildProgress: null,
       targetKeys: targetKeys ?? [],
       targetStatuses: statuses,
     });
@@ -72,20 +92,27 @@ export const useExecutionStore = create<ExecutionState>((set) => ({
       if (idx >= 0 && idx < state.targetKeys.length - 1) {
         next[state.targetKeys[idx + 1]] = "running";
       }
-      return { targetStatuses: next };
+      return { targetStatuses: next, buildProgress: null };
     }),
 
   appendOutput: (line) =>
     set((state) => ({ outputLines: [...state.outputLines, line] })),
 
   addTestResult: (result) =>
-    set((state) => ({ liveResults: [...state.liveResults, result] })),
+    set((state) => ({
+      liveResults: [...state.liveResults, result],
+      buildProgress: null,
+    })),
+
+  setProgress: (testsRun, testsTotal, etaMs) =>
+    set({ testsRun, testsTotal, etaMs }),
 
-  setProgress: (testsRun, testsTotal) => set({ testsRun, testsTotal }),
+  setBuildProgress: (buildProgress) => set({ buildProgress }),
 
   finishRun: () =>
     set({
       isRunning: false,
+      buildProgress: null,
       targetKeys: [],
       targetStatuses: {},
     }),
@@ -98,6 +125,8 @@ export const useExecutionStore = create<ExecutionState>((set) => ({
       liveResults: [],
       testsRun: 0,
       testsTotal: 0,
+      etaMs: null,
+      buildProgress: null,
       targetKeys: [],
       targetStatuses: {},
     }),